@@ -0,0 +1,121 @@
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use lazy_static::lazy_static;
+use reqwest::{Certificate, Client, ClientBuilder, Proxy};
+use crate::website::DEFAULT_TIMEOUT_SECONDS;
+
+lazy_static! {
+    /// PEM files of the additional root certificates listed in `EXTRA_ROOT_CERTIFICATES`
+    static ref EXTRA_ROOT_CERTIFICATES: Vec<Vec<u8>> = {
+        match env::var_os("EXTRA_ROOT_CERTIFICATES") {
+            Some(paths) => env::split_paths(&paths)
+                .map(|path| std::fs::read(&path).unwrap_or_else(|_| panic!("Failed to read the root certificate: {}", path.display())))
+                .collect(),
+            None => Vec::new()
+        }
+    };
+
+    static ref CLIENT: Client = create_builder().build().expect("Failed to create the HTTP client");
+
+    static ref INSECURE_CLIENT: Client = create_builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .expect("Failed to create the HTTP client");
+}
+
+/// Returns the HTTP client shared by the site checker and the site analysis.
+///
+/// The client waits for a response for `DEFAULT_TIMEOUT_SECONDS`, a request can set another timeout.
+///
+/// # Arguments
+///
+/// * `accept_invalid_certs` - Whether the client should accept invalid and self-signed certificates.
+///
+/// # Panics
+///
+/// This function panics on the first call if the client settings in the environment variables are invalid.
+pub fn get_client(accept_invalid_certs: bool) -> &'static Client {
+    if accept_invalid_certs {
+        &INSECURE_CLIENT
+    }
+    else {
+        &CLIENT
+    }
+}
+
+/// Creates a builder of the HTTP client with the settings from the environment variables:
+///
+/// - `PROXY_URL` - the proxy through which all requests are sent;
+/// - `EXTRA_ROOT_CERTIFICATES` - paths to PEM files of root certificates for internal sites,
+///   separated like the `PATH` variable;
+/// - `DNS_OVERRIDES` - addresses used instead of DNS for some hosts, for example `intranet.local=10.0.0.5,api.local=10.0.0.6`.
+///
+/// It is used for clients that need additional settings, such as a specific HTTP version.
+///
+/// # Panics
+///
+/// This function panics if the settings in the environment variables are invalid.
+pub fn create_builder() -> ClientBuilder {
+    let mut builder = Client::builder().timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECONDS));
+
+    if let Ok(proxy_url) = env::var("PROXY_URL") {
+        builder = builder.proxy(Proxy::all(&proxy_url).expect("PROXY_URL is not a valid proxy URL"));
+    }
+
+    for pem in EXTRA_ROOT_CERTIFICATES.iter() {
+        builder = builder.add_root_certificate(Certificate::from_pem(pem).expect("Failed to parse the root certificate"));
+    }
+
+    if let Ok(overrides) = env::var("DNS_OVERRIDES") {
+        for (host, address) in parse_dns_overrides(&overrides).expect("DNS_OVERRIDES must look like host=ip,host=ip") {
+            // The port of the overridden address is ignored, the port from the URL is used
+            builder = builder.resolve(&host, SocketAddr::new(address, 0));
+        }
+    }
+
+    builder
+}
+
+/// Returns the PEM files of the additional root certificates for clients that do not use `reqwest`.
+pub fn get_extra_root_certificates() -> &'static [Vec<u8>] {
+    &EXTRA_ROOT_CERTIFICATES
+}
+
+/// Parses the list of DNS overrides.
+///
+/// # Arguments
+///
+/// * `text` - Comma-separated pairs like `intranet.local=10.0.0.5`.
+///
+/// # Returns
+///
+/// Pairs of the host and its address, or `None` if the list is invalid.
+fn parse_dns_overrides(text: &str) -> Option<Vec<(String, IpAddr)>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (host, address) = pair.split_once('=')?;
+            Some((host.trim().to_lowercase(), address.trim().parse::<IpAddr>().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod client_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dns_overrides() {
+        let overrides = parse_dns_overrides("Intranet.local=10.0.0.5, api.local=::1,").unwrap();
+
+        assert_eq!(overrides, vec![
+            (String::from("intranet.local"), "10.0.0.5".parse::<IpAddr>().unwrap()),
+            (String::from("api.local"), "::1".parse::<IpAddr>().unwrap())
+        ]);
+
+        assert_eq!(parse_dns_overrides("intranet.local"), None);
+        assert_eq!(parse_dns_overrides("intranet.local=10.0.0"), None);
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::DateTime;
+use crate::report;
+use crate::website::{self, FileStatus, HealthSection, SiteInformation};
+
+/// Minimum change of the response time in milliseconds that is reported
+const MIN_DURATION_CHANGE: u128 = 100;
+
+/// Represents a part of the site analysis that is compared.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ComparedField {
+    StatusCode,
+    Duration,
+    Protocols,
+    Robots,
+    Sitemap,
+    Certificate,
+    CertificateExpiration,
+    /// One of the security or caching headers, with its name
+    Header(&'static str),
+    MixedContent,
+    Score,
+    SectionScore(HealthSection)
+}
+
+/// Represents a change between two analyses of the same site.
+#[derive(Debug, PartialEq)]
+pub struct AnalysisChange {
+    pub field: ComparedField,
+    pub before: String,
+    pub after: String
+}
+
+/// Finds the differences between two analyses of the same site.
+///
+/// The response time is reported only if it changed by at least `MIN_DURATION_CHANGE` milliseconds.
+///
+/// # Arguments
+///
+/// * `before` - The earlier analysis.
+/// * `after` - The later analysis.
+///
+/// # Returns
+///
+/// The changes in the order of the report sections.
+pub fn compare_analyses(before: &SiteInformation, after: &SiteInformation) -> Vec<AnalysisChange> {
+    let mut changes: Vec<AnalysisChange> = Vec::new();
+
+    let mut compare = |field: ComparedField, before: String, after: String| {
+        if before != after {
+            changes.push(AnalysisChange { field, before, after });
+        }
+    };
+
+    compare(ComparedField::StatusCode, before.status_code.to_string(), after.status_code.to_string());
+
+    if before.duration.abs_diff(after.duration) >= MIN_DURATION_CHANGE {
+        compare(ComparedField::Duration, before.duration.to_string(), after.duration.to_string());
+    }
+
+    compare(ComparedField::Protocols, describe_protocols(before), describe_protocols(after));
+    compare(ComparedField::Robots, report::describe_file_status(before.robots), report::describe_file_status(after.robots));
+    compare(ComparedField::Sitemap, report::describe_file_status(before.sitemap), report::describe_file_status(after.sitemap));
+
+    let describe_certificate = |site_information: &SiteInformation| match &site_information.certificate {
+        Some(cert) => format!("{}, {}", cert.issuer, if cert.is_valid { "действителен" } else { "недействителен" }),
+        None => String::from("нет")
+    };
+    let describe_expiration = |site_information: &SiteInformation| match &site_information.certificate {
+        Some(cert) => format_date(cert.expires_at),
+        None => String::from("нет")
+    };
+
+    compare(ComparedField::Certificate, describe_certificate(before), describe_certificate(after));
+    compare(ComparedField::CertificateExpiration, describe_expiration(before), describe_expiration(after));
+
+    for (name, before_value, after_value) in [
+        ("Strict-Transport-Security", &before.security_headers.strict_transport_security, &after.security_headers.strict_transport_security),
+        ("Content-Security-Policy", &before.security_headers.content_security_policy, &after.security_headers.content_security_policy),
+        ("X-Content-Type-Options", &before.security_headers.x_content_type_options, &after.security_headers.x_content_type_options),
+        ("X-Frame-Options", &before.security_headers.x_frame_options, &after.security_headers.x_frame_options),
+        ("Referrer-Policy", &before.security_headers.referrer_policy, &after.security_headers.referrer_policy),
+        ("Permissions-Policy", &before.security_headers.permissions_policy, &after.security_headers.permissions_policy),
+        ("Cache-Control", &before.performance.cache_control, &after.performance.cache_control),
+        ("ETag", &before.performance.etag.as_ref().map(|_| String::from("есть")), &after.performance.etag.as_ref().map(|_| String::from("есть"))),
+        ("Last-Modified", &before.performance.last_modified.as_ref().map(|_| String::from("есть")), &after.performance.last_modified.as_ref().map(|_| String::from("есть")))
+    ] {
+        let describe = |value: &Option<String>| value.clone().unwrap_or_else(|| String::from("нет"));
+
+        compare(ComparedField::Header(name), describe(before_value), describe(after_value));
+    }
+
+    let describe_mixed_content = |site_information: &SiteInformation| match &site_information.mixed_content {
+        Some(mixed_content) => mixed_content.len().to_string(),
+        None => String::from("не HTTPS")
+    };
+
+    compare(ComparedField::MixedContent, describe_mixed_content(before), describe_mixed_content(after));
+
+    let before_score = website::calculate_health_score(before);
+    let after_score = website::calculate_health_score(after);
+
+    compare(ComparedField::Score,
+            format!("{} ({})", before_score.grade, before_score.score),
+            format!("{} ({})", after_score.grade, after_score.score));
+
+    for (before_section, after_section) in before_score.sections.iter().zip(&after_score.sections) {
+        compare(ComparedField::SectionScore(after_section.section),
+                format!("{} ({})", before_section.grade, before_section.score),
+                format!("{} ({})", after_section.grade, after_section.score));
+    }
+
+    changes
+}
+
+/// Represents a row of the side-by-side comparison of two sites.
+pub struct SiteComparisonRow {
+    pub name: &'static str,
+    pub first: String,
+    pub second: String
+}
+
+/// Puts the key results of the analyses of two different sites side by side.
+///
+/// # Arguments
+///
+/// * `first` - The analysis of the first site.
+/// * `second` - The analysis of the second site.
+///
+/// # Returns
+///
+/// Rows with short values that fit in a table: response time, protocols, TLS, security headers, robots/sitemap and score.
+pub fn compare_sites(first: &SiteInformation, second: &SiteInformation) -> Vec<SiteComparisonRow> {
+    let first_score = website::calculate_health_score(first);
+    let second_score = website::calculate_health_score(second);
+
+    let mut rows: Vec<SiteComparisonRow> = Vec::new();
+
+    let mut add = |name: &'static str, describe: &dyn Fn(&SiteInformation) -> String| {
+        rows.push(SiteComparisonRow { name, first: describe(first), second: describe(second) });
+    };
+
+    let describe_header = |value: &Option<String>| String::from(if value.is_some() { "да" } else { "нет" });
+    let describe_file = |status: FileStatus| String::from(if status == FileStatus::Found { "да" } else { "нет" });
+
+    add("Код ответа", &|site| site.status_code.to_string());
+    add("Ответ, мс", &|site| site.duration.to_string());
+    add("Протоколы", &|site| {
+        let protocols = &site.protocols;
+
+        [(protocols.http1, "1.1"), (protocols.http2, "2"), (protocols.http3, "3")].iter()
+            .filter(|(is_supported, _)| *is_supported)
+            .map(|(_, name)| *name)
+            .collect::<Vec<&str>>()
+            .join("/")
+    });
+    add("HTTPS", &|site| String::from(if site.mixed_content.is_some() { "да" } else { "нет" }));
+    add("Сертификат", &|site| match &site.certificate {
+        Some(cert) if cert.is_valid => format!("{} дн.", cert.days_left()),
+        Some(_) => String::from("недейств."),
+        None => String::from("нет")
+    });
+    add("HSTS", &|site| describe_header(&site.security_headers.strict_transport_security));
+    add("CSP", &|site| describe_header(&site.security_headers.content_security_policy));
+    add("X-Content-Type", &|site| describe_header(&site.security_headers.x_content_type_options));
+    add("X-Frame-Options", &|site| describe_header(&site.security_headers.x_frame_options));
+    add("Referrer-Policy", &|site| describe_header(&site.security_headers.referrer_policy));
+    add("Permissions", &|site| describe_header(&site.security_headers.permissions_policy));
+    add("robots.txt", &|site| describe_file(site.robots));
+    add("sitemap.xml", &|site| describe_file(site.sitemap));
+
+    rows.push(SiteComparisonRow {
+        name: "Оценка",
+        first: format!("{} ({})", first_score.grade, first_score.score),
+        second: format!("{} ({})", second_score.grade, second_score.score)
+    });
+
+    rows
+}
+
+/// Returns the supported HTTP versions separated by commas
+fn describe_protocols(site_information: &SiteInformation) -> String {
+    let protocols = &site_information.protocols;
+
+    [(protocols.http1, "HTTP/1.1"), (protocols.http2, "HTTP/2"), (protocols.http3, "HTTP/3")].iter()
+        .filter(|(is_supported, _)| *is_supported)
+        .map(|(_, name)| *name)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+/// Formats a Unix timestamp as a UTC date and time, for example `01.06.2024 12:00 UTC`.
+pub fn format_date(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|date| date.format("%d.%m.%Y %H:%M UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod compare_tests {
+    use super::*;
+
+    fn create_site_information(status_code: u16, duration: u128) -> SiteInformation {
+        SiteInformation {
+            status_code,
+            duration,
+            mixed_content: Some(Vec::new()),
+            ..SiteInformation::new_for_tests("https://example.com/")
+        }
+    }
+
+    #[test]
+    fn test_compare_same_analyses() {
+        let before = create_site_information(200, 100);
+        let after = create_site_information(200, 150);
+
+        assert!(compare_analyses(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn test_compare_analyses() {
+        let before = create_site_information(200, 100);
+        let mut after = create_site_information(500, 900);
+
+        after.robots = FileStatus::Missing { status_code: 404 };
+        after.security_headers.x_frame_options = Some(String::from("DENY"));
+
+        let fields: Vec<ComparedField> = compare_analyses(&before, &after).into_iter().map(|change| change.field).collect();
+
+        assert_eq!(fields, vec![
+            ComparedField::StatusCode,
+            ComparedField::Duration,
+            ComparedField::Robots,
+            ComparedField::Header("X-Frame-Options"),
+            ComparedField::Score,
+            ComparedField::SectionScore(HealthSection::Availability),
+            ComparedField::SectionScore(HealthSection::SecurityHeaders),
+            ComparedField::SectionScore(HealthSection::Seo)
+        ]);
+    }
+
+    #[test]
+    fn test_compare_sites() {
+        let first = create_site_information(200, 100);
+        let mut second = create_site_information(503, 2500);
+
+        second.protocols.http2 = false;
+        second.mixed_content = None;
+
+        let rows = compare_sites(&first, &second);
+        let row = |name: &str| rows.iter().find(|row| row.name == name).map(|row| (row.first.as_str(), row.second.as_str()));
+
+        assert_eq!(row("Код ответа"), Some(("200", "503")));
+        assert_eq!(row("Протоколы"), Some(("1.1/2", "1.1")));
+        assert_eq!(row("HTTPS"), Some(("да", "нет")));
+        assert_eq!(rows.last().unwrap().name, "Оценка");
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(1717243200), "01.06.2024 12:00 UTC");
+    }
+}
@@ -0,0 +1,195 @@
+use regex::Regex;
+use scraper::{Html, Node, Selector};
+use sha2::{Digest, Sha256};
+
+/// Elements whose text is never considered part of the page content
+const SKIPPED_ELEMENTS: [&str; 4] = ["script", "style", "noscript", "template"];
+
+/// A rule that excludes a dynamic region of the page (counters, dates, advertising) from the comparison.
+pub enum IgnoreRule {
+    /// Removes the elements matching the CSS selector
+    Selector(Selector),
+    /// Removes the text matching the regular expression
+    Regex(Regex)
+}
+
+/// Represents the difference between two versions of the page content.
+pub struct ContentChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>
+}
+
+/// Parses the rules for ignoring dynamic regions of the page.
+///
+/// Each rule is written on a separate line and starts with `css:` for a CSS selector
+/// or with `regex:` for a regular expression.
+///
+/// # Arguments
+///
+/// * `text` - Rules, one per line.
+///
+/// # Returns
+///
+/// A vector of rules, or the line that could not be parsed.
+///
+/// # Example
+///
+/// ```
+/// let rules = parse_ignore_rules("css:.banner\nregex:\\d{2}:\\d{2}").unwrap();
+///
+/// assert_eq!(rules.len(), 2);
+/// ```
+pub fn parse_ignore_rules(text: &str) -> Result<Vec<IgnoreRule>, String> {
+    let mut rules: Vec<IgnoreRule> = Vec::new();
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let rule = if let Some(selector) = line.strip_prefix("css:") {
+            Selector::parse(selector.trim()).ok().map(IgnoreRule::Selector)
+        }
+        else if let Some(pattern) = line.strip_prefix("regex:") {
+            Regex::new(pattern.trim()).ok().map(IgnoreRule::Regex)
+        }
+        else {
+            None
+        };
+
+        match rule {
+            Some(rule) => rules.push(rule),
+            None => return Err(line.to_string())
+        }
+    }
+
+    Ok(rules)
+}
+
+/// Extracts the visible text of the page, one text block per line, without the ignored regions.
+///
+/// Whitespace is collapsed, so changes in the markup formatting do not affect the result.
+///
+/// # Arguments
+///
+/// * `body` - HTML code of the page.
+/// * `rules` - Rules for ignoring dynamic regions.
+///
+/// # Returns
+///
+/// The normalized text of the page.
+pub fn normalize_content(body: &str, rules: &[IgnoreRule]) -> String {
+    let mut html = Html::parse_document(body);
+
+    for rule in rules {
+        if let IgnoreRule::Selector(selector) = rule {
+            let ids: Vec<_> = html.select(selector).map(|element| element.id()).collect();
+
+            for id in ids {
+                if let Some(mut node) = html.tree.get_mut(id) {
+                    node.detach();
+                }
+            }
+        }
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+
+    for node in html.tree.root().descendants() {
+        let Node::Text(text) = node.value() else { continue };
+
+        let is_skipped = node.ancestors().any(|ancestor| {
+            ancestor.value().as_element().is_some_and(|element| SKIPPED_ELEMENTS.contains(&element.name()))
+        });
+
+        if is_skipped {
+            continue;
+        }
+
+        let mut line = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+        for rule in rules {
+            if let IgnoreRule::Regex(regex) = rule {
+                line = regex.replace_all(&line, "").trim().to_string();
+            }
+        }
+
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Calculates the SHA-256 hash of the normalized content.
+///
+/// # Arguments
+///
+/// * `content` - Normalized text of the page.
+///
+/// # Returns
+///
+/// The hash as a hexadecimal string.
+pub fn hash_content(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Compares two versions of the normalized content line by line.
+///
+/// # Arguments
+///
+/// * `old` - The previously saved content.
+/// * `new` - The current content.
+///
+/// # Returns
+///
+/// Lines that appeared and disappeared, in the order they appear on the page.
+pub fn compare_content(old: &str, new: &str) -> ContentChanges {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    ContentChanges {
+        added: new_lines.iter().filter(|line| !old_lines.contains(line)).map(|line| line.to_string()).collect(),
+        removed: old_lines.iter().filter(|line| !new_lines.contains(line)).map(|line| line.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod content_tests {
+    use super::*;
+
+    static PAGE: &str = "<html><head><title>Shop</title><style>p { color: red; }</style></head>\
+        <body><h1>Welcome</h1><p>Today:   12:45</p><div class=\"banner\">Sale!</div><script>var a = 1;</script></body></html>";
+
+    #[test]
+    fn test_normalize_content() {
+        let content = normalize_content(PAGE, &[]);
+
+        assert_eq!(content, "Shop\nWelcome\nToday: 12:45\nSale!");
+    }
+
+    #[test]
+    fn test_normalize_content_with_rules() {
+        let rules = parse_ignore_rules("css:.banner\nregex:\\d{2}:\\d{2}").unwrap();
+        let content = normalize_content(PAGE, &rules);
+
+        assert_eq!(content, "Shop\nWelcome\nToday:");
+    }
+
+    #[test]
+    fn test_parse_ignore_rules() {
+        assert!(parse_ignore_rules("").unwrap().is_empty());
+        assert_eq!(parse_ignore_rules("css:.ok\nxpath://div").err(), Some(String::from("xpath://div")));
+        assert_eq!(parse_ignore_rules("regex:(").err(), Some(String::from("regex:(")));
+    }
+
+    #[test]
+    fn test_compare_content() {
+        let changes = compare_content("Welcome\nPrice: 10", "Welcome\nPrice: 99\nNew item");
+
+        assert_eq!(changes.added, vec!["Price: 99", "New item"]);
+        assert_eq!(changes.removed, vec!["Price: 10"]);
+        assert_eq!(hash_content("a"), hash_content("a"));
+        assert_ne!(hash_content("a"), hash_content("b"));
+    }
+}
@@ -1,17 +1,239 @@
 use std::env;
-use sqlite3::{State, Statement};
+use std::sync::Once;
+use sqlite3::{Connection, State, Statement, Type};
 
-/// Represents a link associated with a user.
+/// Columns that are read into the `Links` structure, in the order of reading
+const LINKS_COLUMNS: &str = "user_id, link, timeout, expected_codes, slow_threshold, watch_content, content_ignore, content_hash, content_text, dns_records, dual_stack, accept_invalid_certs, \
+    name, paused, last_checked, last_up, last_status_code, last_duration, checks_total, checks_up, \
+    maintenance_since, maintenance_checks, maintenance_failures, added_at, labels, escalation_minutes, pending_dns_records";
+
+/// Columns that are read into the `StoredAlert` structure, in the order of reading
+const ALERTS_COLUMNS: &str = "rowid, chat_id, link, text, sent_at, level, escalated_at, acknowledged_by, acknowledged_name, acknowledged_at, resolved_at";
+
+/// Number of site analyses stored for each user and URL
+const MAX_ANALYSES: i64 = 10;
+
+/// Tables and indexes that are created on the first connection if the database does not have them yet
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS links (user_id INTEGER NOT NULL, link TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS link_subscribers (chat_id INTEGER NOT NULL, link TEXT NOT NULL, user_id INTEGER NOT NULL, name TEXT NOT NULL, PRIMARY KEY (chat_id, link, user_id));
+    CREATE TABLE IF NOT EXISTS alerts (chat_id INTEGER NOT NULL, link TEXT NOT NULL, text TEXT NOT NULL, sent_at INTEGER NOT NULL, level INTEGER NOT NULL DEFAULT 0, \
+        escalated_at INTEGER NOT NULL, acknowledged_by INTEGER, acknowledged_name TEXT, acknowledged_at INTEGER, resolved_at INTEGER);
+    CREATE INDEX IF NOT EXISTS alerts_link ON alerts (chat_id, link);
+    CREATE INDEX IF NOT EXISTS alerts_escalated_at ON alerts (escalated_at);
+    CREATE TABLE IF NOT EXISTS chat_members (chat_id INTEGER NOT NULL, user_id INTEGER NOT NULL, name TEXT NOT NULL, muted_until INTEGER, PRIMARY KEY (chat_id, user_id));
+    CREATE TABLE IF NOT EXISTS alert_filters (user_id INTEGER NOT NULL PRIMARY KEY, labels TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS maintenance_windows (user_id INTEGER NOT NULL, link TEXT NOT NULL, schedule TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS analyses (user_id INTEGER NOT NULL, url TEXT NOT NULL, checked_at INTEGER NOT NULL, data TEXT NOT NULL);";
+
+/// Columns of the `links` table added after its first version `(user_id, link)` with their definitions, in the order of adding
+const LINKS_ADDED_COLUMNS: [(&str, &str); 25] = [
+    ("timeout", "INTEGER"),
+    ("expected_codes", "TEXT"),
+    ("slow_threshold", "INTEGER"),
+    ("watch_content", "INTEGER NOT NULL DEFAULT 0"),
+    ("content_ignore", "TEXT"),
+    ("content_hash", "TEXT"),
+    ("content_text", "TEXT"),
+    ("dns_records", "TEXT"),
+    ("dual_stack", "INTEGER NOT NULL DEFAULT 0"),
+    ("accept_invalid_certs", "INTEGER NOT NULL DEFAULT 0"),
+    ("name", "TEXT"),
+    ("paused", "INTEGER NOT NULL DEFAULT 0"),
+    ("last_checked", "INTEGER"),
+    ("last_up", "INTEGER"),
+    ("last_status_code", "INTEGER"),
+    ("last_duration", "INTEGER"),
+    ("checks_total", "INTEGER NOT NULL DEFAULT 0"),
+    ("checks_up", "INTEGER NOT NULL DEFAULT 0"),
+    ("maintenance_since", "INTEGER"),
+    ("maintenance_checks", "INTEGER NOT NULL DEFAULT 0"),
+    ("maintenance_failures", "INTEGER NOT NULL DEFAULT 0"),
+    ("added_at", "INTEGER"),
+    ("labels", "TEXT"),
+    ("escalation_minutes", "INTEGER"),
+    ("pending_dns_records", "TEXT")
+];
+
+/// Upgrades the database once per run, on the first connection
+static MIGRATION: Once = Once::new();
+
+/// Represents a stored site analysis.
+pub struct Analysis {
+    /// Time of the analysis as a Unix timestamp
+    pub checked_at: u64,
+    /// The analysis serialized to JSON
+    pub data: String
+}
+
+/// Represents a stored maintenance window of a link.
+pub struct StoredMaintenanceWindow {
+    /// Row ID of the window
+    pub id: i64,
+    /// The window as written by `MaintenanceWindow::to_string`
+    pub schedule: String
+}
+
+/// Represents a member of a chat who receives the alerts of the chat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatMember {
+    pub user_id: u64,
+    /// The name of the user used to mention them in the alerts
+    pub name: String,
+    /// Time until which the member does not receive alerts as a Unix timestamp, `None` if the member is not muted
+    pub muted_until: Option<u64>
+}
+
+impl ChatMember {
+    /// Checks if the member does not receive alerts at the moment.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time as a Unix timestamp.
+    pub fn is_muted(&self, now: u64) -> bool {
+        self.muted_until.is_some_and(|muted_until| muted_until > now)
+    }
+}
+
+/// Represents a user who receives the alerts about a link in a private chat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinkSubscriber {
+    pub user_id: u64,
+    /// The name of the user shown in the list of subscribers
+    pub name: String
+}
+
+/// Represents an alert about a link that can be acknowledged.
+///
+/// A link has at most one open alert, which stays open until the link works again, however many
+/// failed checks there are.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredAlert {
+    /// Row ID of the alert
+    pub id: i64,
+    /// ID of the chat that owns the link
+    pub chat_id: i64,
+    pub link: String,
+    /// Text of the alert
+    pub text: String,
+    /// Time when the alert was sent as a Unix timestamp
+    pub sent_at: u64,
+    /// Index of the first subscriber of the link who has not been notified yet
+    pub level: u64,
+    /// Time when the last subscriber was notified as a Unix timestamp
+    pub escalated_at: u64,
+    /// The user who acknowledged the alert with their name, `None` if nobody has acknowledged it
+    pub acknowledged_by: Option<(u64, String)>,
+    /// Time of the acknowledgement as a Unix timestamp
+    pub acknowledged_at: Option<u64>,
+    /// Time when the link started working again as a Unix timestamp, `None` while the alert is open
+    pub resolved_at: Option<u64>
+}
+
+/// Represents a link associated with a chat.
+#[derive(Clone)]
 pub struct Links {
+    /// ID of the chat that owns the link: the ID of the user for a private chat, or the negative ID of a group
     pub user_id: f64,
     pub link: String,
+    /// Request timeout in seconds, `None` means the default timeout
+    pub timeout: Option<u64>,
+    /// Status codes that are considered healthy, `None` means 2xx and 3xx
+    pub expected_codes: Option<String>,
+    /// Response time in milliseconds after which the site is considered slow
+    pub slow_threshold: Option<u64>,
+    /// Whether the checker should notice changes in the page content
+    pub watch_content: bool,
+    /// Rules for ignoring dynamic regions of the page, one per line
+    pub content_ignore: Option<String>,
+    /// Hash of the normalized content at the last check
+    pub content_hash: Option<String>,
+    /// Normalized content at the last check, used to describe the changes
+    pub content_text: Option<String>,
+    /// A, AAAA and NS records of the host at the last check
+    pub dns_records: Option<String>,
+    /// A, AAAA and NS records of the host that differ from the saved ones and are accepted if they persist at the next check
+    pub pending_dns_records: Option<String>,
+    /// Whether the site should be checked over IPv4 and IPv6 separately
+    pub dual_stack: bool,
+    /// Whether invalid and self-signed certificates of the site are accepted
+    pub accept_invalid_certs: bool,
+    /// Name of the link given by the user
+    pub name: Option<String>,
+    /// Whether the monitoring of the link is paused
+    pub paused: bool,
+    /// Time of the last check as a Unix timestamp
+    pub last_checked: Option<u64>,
+    /// Whether the link worked at the last check
+    pub last_up: Option<bool>,
+    /// Status code received at the last check, `None` for services and failed requests
+    pub last_status_code: Option<u64>,
+    /// Response time in milliseconds at the last check
+    pub last_duration: Option<u64>,
+    /// Number of checks of the link
+    pub checks_total: u64,
+    /// Number of checks in which the link worked
+    pub checks_up: u64,
+    /// Time of the start of the current maintenance window as a Unix timestamp, `None` outside of maintenance
+    pub maintenance_since: Option<u64>,
+    /// Number of checks during the current maintenance window
+    pub maintenance_checks: u64,
+    /// Number of failed checks during the current maintenance window
+    pub maintenance_failures: u64,
+    /// Time when the link was added as a Unix timestamp, `None` for links added before it was saved
+    pub added_at: Option<u64>,
+    /// Labels of the link in alphabetical order, stored separated by spaces
+    pub labels: Vec<String>,
+    /// Delay in minutes before an unacknowledged alert is sent to the next subscriber, `None` sends it to all subscribers at once
+    pub escalation_minutes: Option<u64>,
+}
+
+/// Opens a connection to the database specified in the `DATABASE_URL` environment variable.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+fn connect() -> Connection {
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+    let connection = sqlite3::open(database_url).expect("Failed to connect to the database");
+
+    MIGRATION.call_once(|| migrate(&connection));
+
+    connection
+}
+
+/// Upgrades a database created for an earlier version of the bot: creates the missing tables and indexes
+/// and adds the missing columns of the `links` table with their default values.
+///
+/// # Arguments
+///
+/// * `connection` - The connection to the database.
+///
+/// # Panics
+///
+/// This function panics if the database cannot be changed.
+fn migrate(connection: &Connection) {
+    connection.execute(SCHEMA).expect("Failed to create the tables of the database");
+
+    let mut columns: Vec<String> = Vec::new();
+    let mut db = connection.prepare("PRAGMA table_info(links)").unwrap();
+
+    while let State::Row = db.next().unwrap() {
+        columns.push(db.read::<String>(1).unwrap());
+    }
+
+    for (column, definition) in LINKS_ADDED_COLUMNS {
+        if !columns.iter().any(|name| name == column) {
+            connection.execute(format!("ALTER TABLE links ADD COLUMN {column} {definition}")).expect("Failed to add a column to the links table");
+        }
+    }
 }
 
 /// Adds a new link to the database for a given user.
 ///
 /// # Arguments
 ///
-/// * `user_id` - The ID of the user.
+/// * `user_id` - The ID of the chat that owns the link.
 /// * `link` - The link to be added.
 ///
 /// # Returns
@@ -21,12 +243,11 @@ pub struct Links {
 /// # Panics
 ///
 /// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
-pub fn add_link(user_id: u64, link: &str) -> State {
+pub fn add_link(user_id: i64, link: &str) -> State {
     // Adding a new row to the database
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let connection = sqlite3::open(database_url).expect("Failed to connect to the database");
+    let connection = connect();
 
-    let mut db = connection.prepare("INSERT INTO links VALUES (?, ?)").unwrap();
+    let mut db = connection.prepare("INSERT INTO links (user_id, link, added_at) VALUES (?, ?, strftime('%s', 'now'))").unwrap();
 
     // The numbers 1 and 2 denote the location of the question mark in the query
     db.bind(1, user_id.to_string().as_str()).unwrap();
@@ -40,16 +261,16 @@ pub fn add_link(user_id: u64, link: &str) -> State {
 ///
 /// # Arguments
 ///
-/// * `user_id` - The ID of the user.
+/// * `user_id` - The ID of the chat that owns the link.
 /// * `link` - The link to check.
 ///
 /// # Returns
 ///
 /// Returns `true` if the link exists for the user, `false` otherwise.
-pub fn is_link_exists(user_id: u64, link: &str) -> bool {
+pub fn is_link_exists(user_id: i64, link: &str) -> bool {
     // We get the link list and check if there are any items in it
     let vec: Vec<Links> = get_all_links_from_user(user_id, Option::from(link));
-    return vec.iter().count() > 0
+    !vec.is_empty()
 }
 
 /// Returns a vector of links for a given user ID and optional link.
@@ -60,30 +281,27 @@ pub fn is_link_exists(user_id: u64, link: &str) -> bool {
 ///
 /// # Arguments
 ///
-/// * `user_id` - The ID of the user.
+/// * `user_id` - The ID of the chat that owns the link.
 /// * `link` - An optional link to filter the results.
 ///
 /// # Returns
 ///
-/// A vector containing the links that match the specified user ID and link.
+/// A vector containing the links that match the specified user ID and link, in the order they were saved.
 ///
 /// # Panics
 ///
 /// This function will panic if the `DATABASE_URL` environment variable is not set
 /// or if there is a problem connecting to the database.
-pub fn get_all_links_from_user(user_id: u64, link: Option<&str>) -> Vec<Links> {
-    let query: &str;
-
+pub fn get_all_links_from_user(user_id: i64, link: Option<&str>) -> Vec<Links> {
     // Depending on whether the reference is None, type in your query
-    if link.is_some() {
-        query = "SELECT * FROM links WHERE user_id = ? AND link = ?";
+    let query = if link.is_some() {
+        format!("SELECT {LINKS_COLUMNS} FROM links WHERE user_id = ? AND link = ?")
     }
     else {
-        query = "SELECT * FROM links WHERE user_id = ?";
-    }
+        format!("SELECT {LINKS_COLUMNS} FROM links WHERE user_id = ? ORDER BY rowid")
+    };
 
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let connection = sqlite3::open(database_url).expect("Failed to connect to the database");
+    let connection = connect();
 
     let mut db = connection.prepare(query).unwrap();
     db.bind(1, user_id.to_string().as_str()).unwrap();
@@ -113,10 +331,9 @@ pub fn get_all_links_from_user(user_id: u64, link: Option<&str>) -> Vec<Links> {
 /// This function will panic if the `DATABASE_URL` environment variable is not set
 /// or if there is a problem connecting to the database.
 pub fn get_all_links() -> Vec<Links> {
-    let query = "SELECT * FROM links";
+    let query = format!("SELECT {LINKS_COLUMNS} FROM links");
 
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let connection = sqlite3::open(database_url).expect("Failed to connect to the database");
+    let connection = connect();
 
     let db = connection.prepare(query).unwrap();
 
@@ -142,6 +359,29 @@ pub fn get_all_links() -> Vec<Links> {
 /// struct Links {
 ///     user_id: f64,
 ///     link: String,
+///     timeout: Option<u64>,
+///     expected_codes: Option<String>,
+///     slow_threshold: Option<u64>,
+///     watch_content: bool,
+///     content_ignore: Option<String>,
+///     content_hash: Option<String>,
+///     content_text: Option<String>,
+///     dns_records: Option<String>,
+///     dual_stack: bool,
+///     accept_invalid_certs: bool,
+///     name: Option<String>,
+///     paused: bool,
+///     last_checked: Option<u64>,
+///     last_up: Option<bool>,
+///     last_status_code: Option<u64>,
+///     last_duration: Option<u64>,
+///     checks_total: u64,
+///     checks_up: u64,
+///     maintenance_since: Option<u64>,
+///     maintenance_checks: u64,
+///     maintenance_failures: u64,
+///     added_at: Option<u64>,
+///     labels: Vec<String>,
 /// }
 ///
 /// let mut db: Statement = ...; // database statement
@@ -155,116 +395,1582 @@ fn add_to_vec_from_database(mut db: Statement, vec: &mut Vec<Links>) {
         vec.push(Links {
             user_id: db.read::<f64>(0).unwrap(),
             link: db.read::<String>(1).unwrap(),
+            timeout: read_optional_integer(&db, 2),
+            expected_codes: db.read::<String>(3).ok(),
+            slow_threshold: read_optional_integer(&db, 4),
+            watch_content: db.read::<i64>(5).unwrap() != 0,
+            content_ignore: db.read::<String>(6).ok(),
+            content_hash: db.read::<String>(7).ok(),
+            content_text: db.read::<String>(8).ok(),
+            dns_records: db.read::<String>(9).ok(),
+            dual_stack: db.read::<i64>(10).unwrap() != 0,
+            accept_invalid_certs: db.read::<i64>(11).unwrap() != 0,
+            name: db.read::<String>(12).ok(),
+            paused: db.read::<i64>(13).unwrap() != 0,
+            last_checked: read_optional_integer(&db, 14),
+            last_up: read_optional_integer(&db, 15).map(|value| value != 0),
+            last_status_code: read_optional_integer(&db, 16),
+            last_duration: read_optional_integer(&db, 17),
+            checks_total: db.read::<i64>(18).unwrap() as u64,
+            checks_up: db.read::<i64>(19).unwrap() as u64,
+            maintenance_since: read_optional_integer(&db, 20),
+            maintenance_checks: db.read::<i64>(21).unwrap() as u64,
+            maintenance_failures: db.read::<i64>(22).unwrap() as u64,
+            added_at: read_optional_integer(&db, 23),
+            labels: db.read::<String>(24).map(|labels| labels.split_whitespace().map(str::to_string).collect()).unwrap_or_default(),
+            escalation_minutes: read_optional_integer(&db, 25),
+            pending_dns_records: db.read::<String>(26).ok(),
         })
     }
 }
 
-/// Clears all links associated with a user.
+/// Reads a nullable integer column of the current row.
 ///
 /// # Arguments
 ///
-/// * `user_id` - The ID of the user whose links should be cleared.
+/// * `db` - The database statement to retrieve data from.
+/// * `index` - Column index.
 ///
 /// # Returns
 ///
-/// The state after clearing the links.
+/// `None` if the column contains NULL, otherwise the value of the column.
+fn read_optional_integer(db: &Statement, index: usize) -> Option<u64> {
+    match db.kind(index) {
+        Type::Null => None,
+        _ => db.read::<i64>(index).ok().map(|value| value as u64)
+    }
+}
+
+/// Binds an optional value to the statement, `None` is bound as NULL.
+///
+/// # Arguments
+///
+/// * `db` - The database statement.
+/// * `index` - Position of the question mark in the query.
+/// * `value` - The value to bind.
+fn bind_optional<T: sqlite3::Bindable>(db: &mut Statement, index: usize, value: Option<T>) {
+    match value {
+        Some(value) => db.bind(index, value).unwrap(),
+        None => db.bind(index, ()).unwrap()
+    }
+}
+
+/// Updates the check settings of a user's link.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link whose settings are being changed.
+/// * `timeout` - Request timeout in seconds, `None` resets it to the default.
+/// * `expected_codes` - Healthy status codes, `None` resets them to 2xx and 3xx.
+/// * `slow_threshold` - Response time in milliseconds after which the site is considered slow, `None` disables the check.
+/// * `dual_stack` - Whether to check the site over IPv4 and IPv6 separately.
+/// * `accept_invalid_certs` - Whether to accept invalid and self-signed certificates of the site.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
 ///
 /// # Panics
 ///
-/// This function will panic if the `DATABASE_URL` environment variable is not set
-/// or if there is a problem connecting to the database.
-pub fn clear_all_links(user_id: u64) -> State {
-    // Specify in the request that we want to delete all histories in which the user ID matches the required one
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let connection = sqlite3::open(database_url).expect("Failed to connect to the database");
-    let mut db = connection.prepare("DELETE FROM links WHERE user_id = ?").unwrap();
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_link_settings(user_id: i64, link: &str, timeout: Option<u64>, expected_codes: Option<&str>, slow_threshold: Option<u64>, dual_stack: bool, accept_invalid_certs: bool) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET timeout = ?, expected_codes = ?, slow_threshold = ?, dual_stack = ?, accept_invalid_certs = ? WHERE user_id = ? AND link = ?").unwrap();
+
+    bind_optional(&mut db, 1, timeout.map(|value| value as i64));
+    bind_optional(&mut db, 2, expected_codes);
+    bind_optional(&mut db, 3, slow_threshold.map(|value| value as i64));
+    db.bind(4, dual_stack as i64).unwrap();
+    db.bind(5, accept_invalid_certs as i64).unwrap();
+    db.bind(6, user_id.to_string().as_str()).unwrap();
+    db.bind(7, link).unwrap();
 
-    db.bind(1, user_id.to_string().as_str()).unwrap();
-    
-    // Also, don't forget to save the changes
     db.next().unwrap()
 }
 
-/// Deletes some links from the database for a given user ID.
+/// Enables or disables content change detection for a user's link.
+///
+/// The saved content is reset, so the next check saves the page without notifying the user.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link whose content should be watched.
+/// * `watch_content` - Whether to watch the content.
+/// * `content_ignore` - Rules for ignoring dynamic regions of the page, one per line.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_content_watch(user_id: i64, link: &str, watch_content: bool, content_ignore: Option<&str>) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET watch_content = ?, content_ignore = ?, content_hash = NULL, content_text = NULL WHERE user_id = ? AND link = ?").unwrap();
+
+    db.bind(1, watch_content as i64).unwrap();
+    bind_optional(&mut db, 2, content_ignore);
+    db.bind(3, user_id.to_string().as_str()).unwrap();
+    db.bind(4, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Saves the content of a user's link received during the last check.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The checked link.
+/// * `content_hash` - Hash of the normalized content.
+/// * `content_text` - Normalized content.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_content_snapshot(user_id: i64, link: &str, content_hash: &str, content_text: &str) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET content_hash = ?, content_text = ? WHERE user_id = ? AND link = ?").unwrap();
+
+    db.bind(1, content_hash).unwrap();
+    db.bind(2, content_text).unwrap();
+    db.bind(3, user_id.to_string().as_str()).unwrap();
+    db.bind(4, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Saves the DNS records of the host of a user's link received during the last check.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The checked link.
+/// * `dns_records` - The records, one per line.
+/// * `pending_dns_records` - The records of the last check that are not accepted yet, `None` if there are none.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_dns_records(user_id: i64, link: &str, dns_records: &str, pending_dns_records: Option<&str>) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET dns_records = ?, pending_dns_records = ? WHERE user_id = ? AND link = ?").unwrap();
+
+    db.bind(1, dns_records).unwrap();
+    bind_optional(&mut db, 2, pending_dns_records);
+    db.bind(3, user_id.to_string().as_str()).unwrap();
+    db.bind(4, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Saves the site analysis requested by the user and removes the oldest ones beyond `MAX_ANALYSES`.
 ///
 /// # Arguments
 ///
 /// * `user_id` - The ID of the user.
-/// * `links` - A vector of links to be deleted.
+/// * `url` - The analyzed URL.
+/// * `checked_at` - Time of the analysis as a Unix timestamp.
+/// * `data` - The analysis serialized to JSON.
+///
+/// # Returns
+///
+/// The state of the database after removing the oldest analyses.
 ///
 /// # Panics
 ///
-/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure
-/// to connect to the database.
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn add_analysis(user_id: u64, url: &str, checked_at: u64, data: &str) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("INSERT INTO analyses (user_id, url, checked_at, data) VALUES (?, ?, ?, ?)").unwrap();
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    db.bind(2, url).unwrap();
+    db.bind(3, checked_at as i64).unwrap();
+    db.bind(4, data).unwrap();
+
+    db.next().unwrap();
+
+    let mut db = connection.prepare("DELETE FROM analyses WHERE user_id = ? AND url = ? AND rowid NOT IN \
+        (SELECT rowid FROM analyses WHERE user_id = ? AND url = ? ORDER BY checked_at DESC, rowid DESC LIMIT ?)").unwrap();
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    db.bind(2, url).unwrap();
+    db.bind(3, user_id.to_string().as_str()).unwrap();
+    db.bind(4, url).unwrap();
+    db.bind(5, MAX_ANALYSES).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Returns the stored analyses of the URL requested by the user, from the newest to the oldest.
 ///
-/// # Examples
+/// # Arguments
 ///
-/// ```rust
-/// let user_id = 123;
-/// let links = vec!["http://example.com", "http://example.org"];
+/// * `user_id` - The ID of the user.
+/// * `url` - The analyzed URL.
 ///
-/// delete_some_links(user_id, links);
-/// ```
-pub fn delete_some_links(user_id: u64, links: Vec<&str>) {
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL is not set");
-    let connection = sqlite3::open(database_url).expect("Failed to connect to the database");
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_analyses(user_id: u64, url: &str) -> Vec<Analysis> {
+    let connection = connect();
 
-    for link in links {
-        let mut db = connection.prepare("DELETE FROM links WHERE user_id = ? AND link = ?").unwrap();
+    let mut db = connection.prepare("SELECT checked_at, data FROM analyses WHERE user_id = ? AND url = ? ORDER BY checked_at DESC, rowid DESC").unwrap();
 
-        db.bind(1, user_id.to_string().as_str()).unwrap();
-        db.bind(2, link).unwrap();
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    db.bind(2, url).unwrap();
 
-        db.next().unwrap();
+    let mut analyses: Vec<Analysis> = Vec::new();
+
+    while let State::Row = db.next().unwrap() {
+        analyses.push(Analysis {
+            checked_at: db.read::<i64>(0).unwrap() as u64,
+            data: db.read::<String>(1).unwrap()
+        });
     }
+
+    analyses
 }
 
-#[cfg(test)]
-mod database_test {
-    use super::*;
+/// Saves the result of a check of a user's link and updates its uptime counters.
+///
+/// During a maintenance window the counters of the window are updated too.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The checked link.
+/// * `checked_at` - Time of the check as a Unix timestamp.
+/// * `is_up` - Whether the link worked.
+/// * `status_code` - The received status code, if the link is a website and it responded.
+/// * `duration` - Response time in milliseconds, if the link responded.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn record_check(user_id: i64, link: &str, checked_at: u64, is_up: bool, status_code: Option<u16>, duration: Option<u128>) -> State {
+    let connection = connect();
 
-    #[test]
-    fn test_insert_into_database() {
-        add_link(654352, "Hello world!");
-        add_link(654352, "No");
-        add_link(654352, "Yes");
-        add_link(654352, "Ggg");
-        add_link(3552, "Lol");
-        add_link(3552, "Go");
+    let mut db = connection.prepare("UPDATE links SET last_checked = ?, last_up = ?, last_status_code = ?, last_duration = ?, \
+        checks_total = checks_total + 1, checks_up = checks_up + ?, \
+        maintenance_checks = maintenance_checks + (maintenance_since IS NOT NULL), \
+        maintenance_failures = maintenance_failures + (maintenance_since IS NOT NULL AND ? = 0) WHERE user_id = ? AND link = ?").unwrap();
 
-        assert!(true)
-    }
+    db.bind(1, checked_at as i64).unwrap();
+    db.bind(2, is_up as i64).unwrap();
+    bind_optional(&mut db, 3, status_code.map(|value| value as i64));
+    bind_optional(&mut db, 4, duration.map(|value| value as i64));
+    db.bind(5, is_up as i64).unwrap();
+    db.bind(6, is_up as i64).unwrap();
+    db.bind(7, user_id.to_string().as_str()).unwrap();
+    db.bind(8, link).unwrap();
 
-    #[test]
-    fn test_is_link_exists() {
-        let bool1 = is_link_exists(654352, "Ggg");
-        let bool2 = is_link_exists(654352, "Gg");
+    db.next().unwrap()
+}
+
+/// Changes the name of a user's link.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link being renamed.
+/// * `name` - The new name, `None` removes it.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_link_name(user_id: i64, link: &str, name: Option<&str>) -> State {
+    let connection = connect();
 
-        println!("Is exist: {}", bool1);
-        println!("Is exist: {}", bool2);
+    let mut db = connection.prepare("UPDATE links SET name = ? WHERE user_id = ? AND link = ?").unwrap();
 
-        assert!(true)
-    }
+    bind_optional(&mut db, 1, name);
+    db.bind(2, user_id.to_string().as_str()).unwrap();
+    db.bind(3, link).unwrap();
 
-    #[test]
-    fn test_get_histories() {
-        let vec = get_all_links_from_user(654352, None);
+    db.next().unwrap()
+}
 
-        for one_link in vec {
-            println!("User ID: {} | Link: {}", one_link.user_id, one_link.link);
-        }
+/// Replaces the labels of a user's link.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `labels` - The new labels, an empty slice removes all labels.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_link_labels(user_id: i64, link: &str, labels: &[String]) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET labels = ? WHERE user_id = ? AND link = ?").unwrap();
+
+    bind_optional(&mut db, 1, Some(labels.join(" ")).filter(|labels| !labels.is_empty()).as_deref());
+    db.bind(2, user_id.to_string().as_str()).unwrap();
+    db.bind(3, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Returns the labels whose links the chat receives alerts about.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat.
+///
+/// # Returns
+///
+/// The labels, an empty vector if the chat receives alerts about all links.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_alert_labels(user_id: i64) -> Vec<String> {
+    let connection = connect();
+
+    let mut db = connection.prepare("SELECT labels FROM alert_filters WHERE user_id = ?").unwrap();
 
-        assert!(true)
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+
+    match db.next().unwrap() {
+        State::Row => db.read::<String>(0).unwrap().split_whitespace().map(str::to_string).collect(),
+        State::Done => Vec::new()
     }
+}
 
-    #[test]
-    fn test_clear_all_links() {
-        clear_all_links(654352);
-        clear_all_links(3552);
+/// Sets the labels whose links the chat receives alerts about.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat.
+/// * `labels` - The labels, an empty slice turns the filter off.
+///
+/// # Returns
+///
+/// The state of the database after updating the filter.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_alert_labels(user_id: i64, labels: &[String]) -> State {
+    let connection = connect();
+
+    let mut db = if labels.is_empty() {
+        connection.prepare("DELETE FROM alert_filters WHERE user_id = ?").unwrap()
+    } else {
+        let mut db = connection.prepare("INSERT OR REPLACE INTO alert_filters (user_id, labels) VALUES (?, ?)").unwrap();
+        db.bind(2, labels.join(" ").as_str()).unwrap();
+        db
+    };
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Pauses or resumes the monitoring of a user's link.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `paused` - Whether the monitoring should be paused.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn set_link_paused(user_id: i64, link: &str, paused: bool) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET paused = ? WHERE user_id = ? AND link = ?").unwrap();
+
+    db.bind(1, paused as i64).unwrap();
+    db.bind(2, user_id.to_string().as_str()).unwrap();
+    db.bind(3, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Marks the start or the end of a maintenance window of a user's link and resets the counters of the window.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `maintenance_since` - Time of the start of the window as a Unix timestamp, `None` when the window closes.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_maintenance(user_id: i64, link: &str, maintenance_since: Option<u64>) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET maintenance_since = ?, maintenance_checks = 0, maintenance_failures = 0 WHERE user_id = ? AND link = ?").unwrap();
+
+    bind_optional(&mut db, 1, maintenance_since.map(|value| value as i64));
+    db.bind(2, user_id.to_string().as_str()).unwrap();
+    db.bind(3, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Saves a maintenance window of a user's link.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `schedule` - The window as written by `MaintenanceWindow::to_string`.
+///
+/// # Returns
+///
+/// The state of the database after inserting the window.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn add_maintenance_window(user_id: i64, link: &str, schedule: &str) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("INSERT INTO maintenance_windows (user_id, link, schedule) VALUES (?, ?, ?)").unwrap();
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    db.bind(2, link).unwrap();
+    db.bind(3, schedule).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Returns the maintenance windows of a user's link in the order they were added.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_maintenance_windows(user_id: i64, link: &str) -> Vec<StoredMaintenanceWindow> {
+    let connection = connect();
+
+    let mut db = connection.prepare("SELECT rowid, schedule FROM maintenance_windows WHERE user_id = ? AND link = ? ORDER BY rowid").unwrap();
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    db.bind(2, link).unwrap();
+
+    let mut windows: Vec<StoredMaintenanceWindow> = Vec::new();
+
+    while let State::Row = db.next().unwrap() {
+        windows.push(StoredMaintenanceWindow {
+            id: db.read::<i64>(0).unwrap(),
+            schedule: db.read::<String>(1).unwrap()
+        });
+    }
+
+    windows
+}
+
+/// Deletes maintenance windows of a user's link.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `id` - Row ID of the window to delete, `None` deletes all windows of the link.
+///
+/// # Returns
+///
+/// The state of the database after deleting the windows.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn delete_maintenance_windows(user_id: i64, link: &str, id: Option<i64>) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("DELETE FROM maintenance_windows WHERE user_id = ? AND link = ? AND (? IS NULL OR rowid = ?)").unwrap();
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    db.bind(2, link).unwrap();
+    bind_optional(&mut db, 3, id);
+    bind_optional(&mut db, 4, id);
+
+    db.next().unwrap()
+}
+
+/// Adds a member who receives the alerts of a chat, or updates the name of the member.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat.
+/// * `user_id` - The ID of the member.
+/// * `name` - The name of the member.
+///
+/// # Returns
+///
+/// The state of the database after adding the member.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn add_chat_member(chat_id: i64, user_id: u64, name: &str) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("INSERT OR IGNORE INTO chat_members (chat_id, user_id, name) VALUES (?, ?, ?)").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, user_id as i64).unwrap();
+    db.bind(3, name).unwrap();
+
+    db.next().unwrap();
+
+    // The mute of a member who is already added is kept
+    let mut db = connection.prepare("UPDATE chat_members SET name = ? WHERE chat_id = ? AND user_id = ?").unwrap();
+
+    db.bind(1, name).unwrap();
+    db.bind(2, chat_id).unwrap();
+    db.bind(3, user_id as i64).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Deletes a member of a chat, so that the member no longer receives the alerts of the chat.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat.
+/// * `user_id` - The ID of the member.
+///
+/// # Returns
+///
+/// The state of the database after deleting the member.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn delete_chat_member(chat_id: i64, user_id: u64) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("DELETE FROM chat_members WHERE chat_id = ? AND user_id = ?").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, user_id as i64).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Returns the members of a chat in the order they were added.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_chat_members(chat_id: i64) -> Vec<ChatMember> {
+    let connection = connect();
+
+    let mut db = connection.prepare("SELECT user_id, name, muted_until FROM chat_members WHERE chat_id = ? ORDER BY rowid").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+
+    let mut members: Vec<ChatMember> = Vec::new();
+
+    while let State::Row = db.next().unwrap() {
+        members.push(ChatMember {
+            user_id: db.read::<i64>(0).unwrap() as u64,
+            name: db.read::<String>(1).unwrap(),
+            muted_until: read_optional_integer(&db, 2)
+        });
+    }
+
+    members
+}
+
+/// Mutes or unmutes the alerts of a chat for one of its members.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat.
+/// * `user_id` - The ID of the member.
+/// * `muted_until` - Time until which the member does not receive alerts as a Unix timestamp, `None` unmutes the member.
+///
+/// # Returns
+///
+/// The state of the database after updating the member.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_member_mute(chat_id: i64, user_id: u64, muted_until: Option<u64>) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE chat_members SET muted_until = ? WHERE chat_id = ? AND user_id = ?").unwrap();
+
+    bind_optional(&mut db, 1, muted_until.map(|value| value as i64));
+    db.bind(2, chat_id).unwrap();
+    db.bind(3, user_id as i64).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Sets the delay before an unacknowledged alert about a link is sent to its next subscriber.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `escalation_minutes` - The delay in minutes, `None` sends the alerts to all subscribers at once.
+///
+/// # Returns
+///
+/// The state of the database after updating the link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_escalation(user_id: i64, link: &str, escalation_minutes: Option<u64>) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE links SET escalation_minutes = ? WHERE user_id = ? AND link = ?").unwrap();
+
+    bind_optional(&mut db, 1, escalation_minutes.map(|value| value as i64));
+    db.bind(2, user_id.to_string().as_str()).unwrap();
+    db.bind(3, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Subscribes a user to the alerts about a link, the user becomes the last subscriber of the link.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `user_id` - The ID of the user.
+/// * `name` - The name of the user.
+///
+/// # Returns
+///
+/// The state of the database after adding the subscriber.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn add_link_subscriber(chat_id: i64, link: &str, user_id: u64, name: &str) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("INSERT OR IGNORE INTO link_subscribers (chat_id, link, user_id, name) VALUES (?, ?, ?, ?)").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, link).unwrap();
+    db.bind(3, user_id as i64).unwrap();
+    db.bind(4, name).unwrap();
+
+    db.next().unwrap();
+
+    // The position of a subscriber who is already subscribed is kept
+    let mut db = connection.prepare("UPDATE link_subscribers SET name = ? WHERE chat_id = ? AND link = ? AND user_id = ?").unwrap();
+
+    db.bind(1, name).unwrap();
+    db.bind(2, chat_id).unwrap();
+    db.bind(3, link).unwrap();
+    db.bind(4, user_id as i64).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Unsubscribes a user from the alerts about a link.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `user_id` - The ID of the user.
+///
+/// # Returns
+///
+/// The state of the database after deleting the subscriber.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn delete_link_subscriber(chat_id: i64, link: &str, user_id: u64) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("DELETE FROM link_subscribers WHERE chat_id = ? AND link = ? AND user_id = ?").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, link).unwrap();
+    db.bind(3, user_id as i64).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Returns the subscribers of a link in the order they are notified: the primary first.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_link_subscribers(chat_id: i64, link: &str) -> Vec<LinkSubscriber> {
+    let connection = connect();
+
+    let mut db = connection.prepare("SELECT user_id, name FROM link_subscribers WHERE chat_id = ? AND link = ? ORDER BY rowid").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, link).unwrap();
+
+    let mut subscribers: Vec<LinkSubscriber> = Vec::new();
+
+    while let State::Row = db.next().unwrap() {
+        subscribers.push(LinkSubscriber {
+            user_id: db.read::<i64>(0).unwrap() as u64,
+            name: db.read::<String>(1).unwrap()
+        });
+    }
+
+    subscribers
+}
+
+/// Saves an alert about a link so that it can be acknowledged and escalated.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `text` - Text of the alert.
+/// * `sent_at` - Time when the alert was sent as a Unix timestamp.
+///
+/// # Returns
+///
+/// The row ID of the alert.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn add_alert(chat_id: i64, link: &str, text: &str, sent_at: u64) -> i64 {
+    let connection = connect();
+
+    let mut db = connection.prepare("INSERT INTO alerts (chat_id, link, text, sent_at, level, escalated_at) VALUES (?, ?, ?, ?, 0, ?)").unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, link).unwrap();
+    db.bind(3, text).unwrap();
+    db.bind(4, sent_at as i64).unwrap();
+    db.bind(5, sent_at as i64).unwrap();
+
+    db.next().unwrap();
+
+    let mut db = connection.prepare("SELECT last_insert_rowid()").unwrap();
+
+    db.next().unwrap();
+    db.read::<i64>(0).unwrap()
+}
+
+/// Reads the alerts of the statement into a vector.
+///
+/// # Arguments
+///
+/// * `db` - The statement that selects the columns of the `alerts` table with the row ID first.
+fn read_alerts(mut db: Statement) -> Vec<StoredAlert> {
+    let mut alerts: Vec<StoredAlert> = Vec::new();
+
+    while let State::Row = db.next().unwrap() {
+        let acknowledged_by = read_optional_integer(&db, 7).zip(db.read::<String>(8).ok());
+
+        alerts.push(StoredAlert {
+            id: db.read::<i64>(0).unwrap(),
+            chat_id: db.read::<i64>(1).unwrap(),
+            link: db.read::<String>(2).unwrap(),
+            text: db.read::<String>(3).unwrap(),
+            sent_at: db.read::<i64>(4).unwrap() as u64,
+            level: db.read::<i64>(5).unwrap() as u64,
+            escalated_at: db.read::<i64>(6).unwrap() as u64,
+            acknowledged_by,
+            acknowledged_at: read_optional_integer(&db, 9),
+            resolved_at: read_optional_integer(&db, 10)
+        });
+    }
+
+    alerts
+}
+
+/// Returns a saved alert.
+///
+/// # Arguments
+///
+/// * `id` - The row ID of the alert.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_alert(id: i64) -> Option<StoredAlert> {
+    let connection = connect();
+
+    let mut db = connection.prepare(format!("SELECT {ALERTS_COLUMNS} FROM alerts WHERE rowid = ?")).unwrap();
+
+    db.bind(1, id).unwrap();
+
+    read_alerts(db).pop()
+}
+
+/// Returns the alerts about a link, the newest first.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `limit` - Maximum number of alerts.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_link_alerts(chat_id: i64, link: &str, limit: i64) -> Vec<StoredAlert> {
+    let connection = connect();
+
+    let mut db = connection.prepare(format!("SELECT {ALERTS_COLUMNS} FROM alerts WHERE chat_id = ? AND link = ? ORDER BY rowid DESC LIMIT ?")).unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, link).unwrap();
+    db.bind(3, limit).unwrap();
+
+    read_alerts(db)
+}
+
+/// Returns the open alert about a link.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+///
+/// # Returns
+///
+/// The alert that is not resolved yet, `None` if the link has no open alert.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_open_alert(chat_id: i64, link: &str) -> Option<StoredAlert> {
+    let connection = connect();
+
+    let mut db = connection.prepare(format!("SELECT {ALERTS_COLUMNS} FROM alerts WHERE chat_id = ? AND link = ? AND resolved_at IS NULL ORDER BY rowid DESC LIMIT 1")).unwrap();
+
+    db.bind(1, chat_id).unwrap();
+    db.bind(2, link).unwrap();
+
+    read_alerts(db).pop()
+}
+
+/// Returns the open alert about a link that does not work, or saves a new alert if the link has none.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `text` - Text of the alert, used only for a new alert.
+/// * `sent_at` - Time when the alert was sent as a Unix timestamp.
+///
+/// # Returns
+///
+/// The open alert and whether it has just been saved.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn open_alert(chat_id: i64, link: &str, text: &str, sent_at: u64) -> (StoredAlert, bool) {
+    if let Some(alert) = get_open_alert(chat_id, link) {
+        return (alert, false);
+    }
+
+    let id = add_alert(chat_id, link, text, sent_at);
+
+    (get_alert(id).expect("The saved alert is not found"), true)
+}
+
+/// Closes the open alert about a link when the link works again, which stops its escalation.
+///
+/// # Arguments
+///
+/// * `chat_id` - The ID of the chat that owns the link.
+/// * `link` - The link.
+/// * `resolved_at` - Time of the successful check as a Unix timestamp.
+///
+/// # Returns
+///
+/// The state of the database after updating the alerts.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn resolve_alerts(chat_id: i64, link: &str, resolved_at: u64) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE alerts SET resolved_at = ? WHERE chat_id = ? AND link = ? AND resolved_at IS NULL").unwrap();
+
+    db.bind(1, resolved_at as i64).unwrap();
+    db.bind(2, chat_id).unwrap();
+    db.bind(3, link).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Returns the open alerts that nobody has acknowledged, the oldest first.
+///
+/// # Arguments
+///
+/// * `since` - Alerts whose last subscriber was notified before this time as a Unix timestamp are not returned.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn get_unacknowledged_alerts(since: u64) -> Vec<StoredAlert> {
+    let connection = connect();
+
+    let mut db = connection.prepare(format!("SELECT {ALERTS_COLUMNS} FROM alerts WHERE acknowledged_at IS NULL AND resolved_at IS NULL AND escalated_at >= ? ORDER BY rowid")).unwrap();
+
+    db.bind(1, since as i64).unwrap();
+
+    read_alerts(db)
+}
+
+/// Saves the alerts of a link again after the deletion of the link was undone.
+///
+/// # Arguments
+///
+/// * `alerts` - The alerts read from the database before deletion, the oldest first. They get new row IDs.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn restore_alerts(alerts: &[StoredAlert]) {
+    let connection = connect();
+
+    for alert in alerts {
+        let mut db = connection.prepare("INSERT INTO alerts (chat_id, link, text, sent_at, level, escalated_at, acknowledged_by, acknowledged_name, acknowledged_at, resolved_at) \
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)").unwrap();
+
+        db.bind(1, alert.chat_id).unwrap();
+        db.bind(2, alert.link.as_str()).unwrap();
+        db.bind(3, alert.text.as_str()).unwrap();
+        db.bind(4, alert.sent_at as i64).unwrap();
+        db.bind(5, alert.level as i64).unwrap();
+        db.bind(6, alert.escalated_at as i64).unwrap();
+        bind_optional(&mut db, 7, alert.acknowledged_by.as_ref().map(|(user_id, _)| *user_id as i64));
+        bind_optional(&mut db, 8, alert.acknowledged_by.as_ref().map(|(_, name)| name.as_str()));
+        bind_optional(&mut db, 9, alert.acknowledged_at.map(|value| value as i64));
+        bind_optional(&mut db, 10, alert.resolved_at.map(|value| value as i64));
+
+        db.next().unwrap();
+    }
+}
+
+/// Deletes the resolved alerts whose links started working again before the time.
+///
+/// Open alerts are kept however old they are, since a link has at most one of them and it prevents
+/// paging the subscribers again about the same outage.
+///
+/// # Arguments
+///
+/// * `before` - The time as a Unix timestamp.
+///
+/// # Returns
+///
+/// The state of the database after deleting the alerts.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn prune_alerts(before: u64) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("DELETE FROM alerts WHERE resolved_at < ?").unwrap();
+
+    db.bind(1, before as i64).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Records that the subscribers of an alert up to the level have been notified.
+///
+/// # Arguments
+///
+/// * `id` - The row ID of the alert.
+/// * `level` - Index of the first subscriber who has not been notified yet.
+/// * `escalated_at` - Time of the notification as a Unix timestamp.
+///
+/// # Returns
+///
+/// The state of the database after updating the alert.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn update_alert_level(id: i64, level: u64, escalated_at: u64) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE alerts SET level = ?, escalated_at = ? WHERE rowid = ?").unwrap();
+
+    db.bind(1, level as i64).unwrap();
+    db.bind(2, escalated_at as i64).unwrap();
+    db.bind(3, id).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Records who acknowledged an alert and when, unless it has already been acknowledged.
+///
+/// # Arguments
+///
+/// * `id` - The row ID of the alert.
+/// * `user_id` - The ID of the user who acknowledged the alert.
+/// * `name` - The name of the user.
+/// * `acknowledged_at` - Time of the acknowledgement as a Unix timestamp.
+///
+/// # Returns
+///
+/// The state of the database after updating the alert.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn acknowledge_alert(id: i64, user_id: u64, name: &str, acknowledged_at: u64) -> State {
+    let connection = connect();
+
+    let mut db = connection.prepare("UPDATE alerts SET acknowledged_by = ?, acknowledged_name = ?, acknowledged_at = ? WHERE rowid = ? AND acknowledged_at IS NULL").unwrap();
+
+    db.bind(1, user_id as i64).unwrap();
+    db.bind(2, name).unwrap();
+    db.bind(3, acknowledged_at as i64).unwrap();
+    db.bind(4, id).unwrap();
+
+    db.next().unwrap()
+}
+
+/// Restores deleted links together with all their settings, saved state and maintenance windows.
+///
+/// # Arguments
+///
+/// * `links` - The links read from the database before deletion with the schedules of their maintenance windows.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure connecting to the database.
+pub fn restore_links(links: &[(Links, Vec<String>)]) {
+    let connection = connect();
+
+    for (link, maintenance_windows) in links {
+        let mut db = connection.prepare(format!("INSERT INTO links ({LINKS_COLUMNS}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)")).unwrap();
+
+        db.bind(1, link.user_id as i64).unwrap();
+        db.bind(2, link.link.as_str()).unwrap();
+        bind_optional(&mut db, 3, link.timeout.map(|value| value as i64));
+        bind_optional(&mut db, 4, link.expected_codes.as_deref());
+        bind_optional(&mut db, 5, link.slow_threshold.map(|value| value as i64));
+        db.bind(6, link.watch_content as i64).unwrap();
+        bind_optional(&mut db, 7, link.content_ignore.as_deref());
+        bind_optional(&mut db, 8, link.content_hash.as_deref());
+        bind_optional(&mut db, 9, link.content_text.as_deref());
+        bind_optional(&mut db, 10, link.dns_records.as_deref());
+        db.bind(11, link.dual_stack as i64).unwrap();
+        db.bind(12, link.accept_invalid_certs as i64).unwrap();
+        bind_optional(&mut db, 13, link.name.as_deref());
+        db.bind(14, link.paused as i64).unwrap();
+        bind_optional(&mut db, 15, link.last_checked.map(|value| value as i64));
+        bind_optional(&mut db, 16, link.last_up.map(|value| value as i64));
+        bind_optional(&mut db, 17, link.last_status_code.map(|value| value as i64));
+        bind_optional(&mut db, 18, link.last_duration.map(|value| value as i64));
+        db.bind(19, link.checks_total as i64).unwrap();
+        db.bind(20, link.checks_up as i64).unwrap();
+        bind_optional(&mut db, 21, link.maintenance_since.map(|value| value as i64));
+        db.bind(22, link.maintenance_checks as i64).unwrap();
+        db.bind(23, link.maintenance_failures as i64).unwrap();
+        bind_optional(&mut db, 24, link.added_at.map(|value| value as i64));
+        bind_optional(&mut db, 25, Some(link.labels.join(" ")).filter(|labels| !labels.is_empty()).as_deref());
+        bind_optional(&mut db, 26, link.escalation_minutes.map(|value| value as i64));
+        bind_optional(&mut db, 27, link.pending_dns_records.as_deref());
+
+        db.next().unwrap();
+
+        for schedule in maintenance_windows {
+            let mut db = connection.prepare("INSERT INTO maintenance_windows (user_id, link, schedule) VALUES (?, ?, ?)").unwrap();
+
+            db.bind(1, link.user_id as i64).unwrap();
+            db.bind(2, link.link.as_str()).unwrap();
+            db.bind(3, schedule.as_str()).unwrap();
+
+            db.next().unwrap();
+        }
+    }
+}
+
+/// Clears all links associated with a user.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat whose links should be cleared.
+///
+/// # Returns
+///
+/// The state after clearing the links.
+///
+/// # Panics
+///
+/// This function will panic if the `DATABASE_URL` environment variable is not set
+/// or if there is a problem connecting to the database.
+pub fn clear_all_links(user_id: i64) -> State {
+    // Specify in the request that we want to delete all histories in which the user ID matches the required one
+    let connection = connect();
+
+    // The subscribers, alerts and maintenance windows of the links are deleted with them, so that they do not return if the links are added again
+    for query in ["DELETE FROM link_subscribers WHERE chat_id = ?", "DELETE FROM alerts WHERE chat_id = ?", "DELETE FROM maintenance_windows WHERE user_id = ?"] {
+        let mut db = connection.prepare(query).unwrap();
+        db.bind(1, user_id).unwrap();
+        db.next().unwrap();
+    }
+
+    let mut db = connection.prepare("DELETE FROM links WHERE user_id = ?").unwrap();
+
+    db.bind(1, user_id.to_string().as_str()).unwrap();
+    
+    // Also, don't forget to save the changes
+    db.next().unwrap()
+}
+
+/// Deletes some links from the database for a given user ID.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the chat that owns the link.
+/// * `links` - A vector of links to be deleted.
+///
+/// # Panics
+///
+/// This function panics if the `DATABASE_URL` environment variable is not set or if there is a failure
+/// to connect to the database.
+///
+/// # Examples
+///
+/// ```rust
+/// let user_id = 123;
+/// let links = vec!["http://example.com", "http://example.org"];
+///
+/// delete_some_links(user_id, links);
+/// ```
+pub fn delete_some_links(user_id: i64, links: Vec<&str>) {
+    let connection = connect();
+
+    for link in links {
+        let mut db = connection.prepare("DELETE FROM links WHERE user_id = ? AND link = ?").unwrap();
+
+        db.bind(1, user_id.to_string().as_str()).unwrap();
+        db.bind(2, link).unwrap();
+
+        db.next().unwrap();
+
+        // The subscribers, alerts and maintenance windows of the link are deleted with it, so that they do not return if the link is added again
+        for query in ["DELETE FROM link_subscribers WHERE chat_id = ? AND link = ?", "DELETE FROM alerts WHERE chat_id = ? AND link = ?", "DELETE FROM maintenance_windows WHERE user_id = ? AND link = ?"] {
+            let mut db = connection.prepare(query).unwrap();
+
+            db.bind(1, user_id).unwrap();
+            db.bind(2, link).unwrap();
+
+            db.next().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod database_test {
+    use super::*;
+
+    #[test]
+    fn test_insert_into_database() {
+        // The links left by an interrupted run are not counted
+        clear_all_links(654352);
+        clear_all_links(3552);
+
+        add_link(654352, "Hello world!");
+        add_link(654352, "No");
+        add_link(654352, "Yes");
+        add_link(654352, "Ggg");
+        add_link(3552, "Lol");
+        add_link(3552, "Go");
+
+        let first = get_all_links_from_user(654352, None).len();
+        let second = get_all_links_from_user(3552, None).len();
+
+        clear_all_links(654352);
+        clear_all_links(3552);
+
+        assert_eq!(first, 4);
+        assert_eq!(second, 2);
+    }
+
+    #[test]
+    fn test_is_link_exists() {
+        add_link(654353, "Ggg");
+
+        let bool1 = is_link_exists(654353, "Ggg");
+        let bool2 = is_link_exists(654353, "Gg");
+
+        clear_all_links(654353);
+
+        assert!(bool1);
+        assert!(!bool2);
+    }
+
+    #[test]
+    fn test_get_histories() {
+        add_link(654354, "Hello world!");
+        add_link(654354, "No");
+
+        let vec = get_all_links_from_user(654354, None);
+
+        clear_all_links(654354);
+
+        assert!(vec.iter().all(|one_link| one_link.user_id as i64 == 654354));
+        assert_eq!(vec.iter().map(|one_link| one_link.link.as_str()).collect::<Vec<&str>>(), vec!["Hello world!", "No"]);
+    }
+
+    #[test]
+    fn test_update_link_settings() {
+        add_link(8841, "https://example.com");
+        update_link_settings(8841, "https://example.com", Some(5), Some("200,401"), Some(2000), true, true);
+
+        let links = get_all_links_from_user(8841, Some("https://example.com"));
+        clear_all_links(8841);
+
+        assert_eq!(links[0].timeout, Some(5));
+        assert_eq!(links[0].expected_codes.as_deref(), Some("200,401"));
+        assert_eq!(links[0].slow_threshold, Some(2000));
+        assert!(links[0].dual_stack);
+        assert!(links[0].accept_invalid_certs);
+    }
+
+    #[test]
+    fn test_link_state() {
+        add_link(8846, "https://example.com");
+        record_check(8846, "https://example.com", 100, true, Some(200), Some(150));
+        record_check(8846, "https://example.com", 200, false, None, None);
+        update_link_name(8846, "https://example.com", Some("Main site"));
+        set_link_paused(8846, "https://example.com", true);
+
+        let links = get_all_links_from_user(8846, None);
+        clear_all_links(8846);
+
+        assert!(links[0].added_at.is_some());
+        assert_eq!(links[0].name.as_deref(), Some("Main site"));
+        assert!(links[0].paused);
+        assert_eq!(links[0].last_checked, Some(200));
+        assert_eq!(links[0].last_up, Some(false));
+        assert_eq!(links[0].last_status_code, None);
+        assert_eq!((links[0].checks_total, links[0].checks_up), (2, 1));
+    }
+
+    #[test]
+    fn test_maintenance() {
+        add_link(8847, "https://example.com");
+        update_maintenance(8847, "https://example.com", Some(100));
+        record_check(8847, "https://example.com", 200, true, Some(200), Some(150));
+        record_check(8847, "https://example.com", 300, false, Some(503), Some(150));
+
+        add_maintenance_window(8847, "https://example.com", "sun 03:00 1h UTC");
+        add_maintenance_window(8847, "https://example.com", "daily 02:00 30m UTC");
+
+        let links = get_all_links_from_user(8847, None);
+        let windows = get_maintenance_windows(8847, "https://example.com");
+
+        delete_maintenance_windows(8847, "https://example.com", Some(windows[0].id));
+        let remaining = get_maintenance_windows(8847, "https://example.com");
+
+        delete_maintenance_windows(8847, "https://example.com", None);
+        clear_all_links(8847);
+
+        assert_eq!(links[0].maintenance_since, Some(100));
+        assert_eq!((links[0].maintenance_checks, links[0].maintenance_failures), (2, 1));
+        assert_eq!(windows.len(), 2);
+        assert_eq!(remaining.iter().map(|window| window.schedule.as_str()).collect::<Vec<&str>>(), vec!["daily 02:00 30m UTC"]);
+        assert!(get_maintenance_windows(8847, "https://example.com").is_empty());
+    }
+
+    #[test]
+    fn test_labels() {
+        add_link(8848, "https://example.com");
+        add_link(8848, "https://example.org");
+        update_link_labels(8848, "https://example.com", &[String::from("client-x"), String::from("prod")]);
+        update_alert_labels(8848, &[String::from("prod")]);
+
+        let links = get_all_links_from_user(8848, None);
+        let alert_labels = get_alert_labels(8848);
+
+        update_alert_labels(8848, &[]);
+        clear_all_links(8848);
+
+        assert_eq!(links[0].labels, vec!["client-x", "prod"]);
+        assert!(links[1].labels.is_empty());
+        assert_eq!(alert_labels, vec!["prod"]);
+        assert!(get_alert_labels(8848).is_empty());
+    }
+
+    #[test]
+    fn test_chat_members() {
+        add_chat_member(-8849, 8849, "Alice");
+        add_chat_member(-8849, 8850, "Bob");
+        update_member_mute(-8849, 8849, Some(200));
+        add_chat_member(-8849, 8849, "Alice Smith");
+
+        let members = get_chat_members(-8849);
+
+        delete_chat_member(-8849, 8849);
+        let remaining = get_chat_members(-8849);
+
+        delete_chat_member(-8849, 8850);
+
+        assert_eq!(members, vec![
+            ChatMember { user_id: 8849, name: String::from("Alice Smith"), muted_until: Some(200) },
+            ChatMember { user_id: 8850, name: String::from("Bob"), muted_until: None }
+        ]);
+        assert!(members[0].is_muted(100));
+        assert!(!members[0].is_muted(200));
+        assert_eq!(remaining.len(), 1);
+    }
+
+    #[test]
+    fn test_escalation() {
+        add_link(8851, "https://example.com");
+        update_escalation(8851, "https://example.com", Some(15));
+        add_link_subscriber(8851, "https://example.com", 8852, "Alice");
+        add_link_subscriber(8851, "https://example.com", 8853, "Bob");
+        add_link_subscriber(8851, "https://example.com", 8852, "Alice Smith");
+
+        let links = get_all_links_from_user(8851, None);
+        let subscribers = get_link_subscribers(8851, "https://example.com");
+
+        delete_link_subscriber(8851, "https://example.com", 8852);
+        let remaining = get_link_subscribers(8851, "https://example.com");
+
+        delete_link_subscriber(8851, "https://example.com", 8853);
+        // The alerts of previous runs are deleted together with the links
+        clear_all_links(8851);
+
+        let sent_at = 1000;
+        let id = add_alert(8851, "https://example.com", "Down", sent_at);
+        update_alert_level(id, 1, sent_at + 60);
+
+        let open = get_open_alert(8851, "https://example.com");
+        let pending: Vec<StoredAlert> = get_unacknowledged_alerts(sent_at + 60).into_iter().filter(|alert| alert.chat_id == 8851).collect();
+        let expired = get_unacknowledged_alerts(sent_at + 61).into_iter().any(|alert| alert.chat_id == 8851);
+
+        acknowledge_alert(id, 8853, "Bob", sent_at + 120);
+        acknowledge_alert(id, 8852, "Alice", sent_at + 180);
+
+        let acknowledged = get_alert(id).unwrap();
+
+        resolve_alerts(8851, "https://example.com", sent_at + 240);
+
+        let resolved = get_link_alerts(8851, "https://example.com", i64::MAX);
+
+        add_link(8851, "https://example.com");
+        add_link_subscriber(8851, "https://example.com", 8853, "Bob");
+        delete_some_links(8851, vec!["https://example.com"]);
+
+        let deleted = (get_link_subscribers(8851, "https://example.com"), get_link_alerts(8851, "https://example.com", i64::MAX));
+
+        restore_alerts(&resolved);
+        prune_alerts(sent_at + 241);
+
+        assert_eq!(links[0].escalation_minutes, Some(15));
+        assert_eq!(subscribers, vec![
+            LinkSubscriber { user_id: 8852, name: String::from("Alice Smith") },
+            LinkSubscriber { user_id: 8853, name: String::from("Bob") }
+        ]);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(pending.iter().map(|alert| (alert.id, alert.level, alert.escalated_at)).collect::<Vec<_>>(), vec![(id, 1, sent_at + 60)]);
+        assert_eq!(open.map(|alert| alert.id), Some(id));
+        assert!(!expired);
+        assert_eq!(acknowledged.acknowledged_by, Some((8853, String::from("Bob"))));
+        assert_eq!(acknowledged.acknowledged_at, Some(sent_at + 120));
+        assert_eq!(resolved[0].resolved_at, Some(sent_at + 240));
+        assert_eq!(deleted, (Vec::new(), Vec::new()));
+        assert!(get_open_alert(8851, "https://example.com").is_none());
+        // The restored alert is pruned, since the link works again
+        assert!(get_link_alerts(8851, "https://example.com", i64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_migrate() {
+        let path = env::temp_dir().join(format!("sparkle_scanner_migration_{}.db", std::process::id()));
+        let connection = sqlite3::open(&path).unwrap();
+
+        connection.execute("CREATE TABLE links (user_id INTEGER NOT NULL, link TEXT NOT NULL); INSERT INTO links VALUES (8855, 'https://example.com');").unwrap();
+
+        // The second run finds nothing to upgrade
+        migrate(&connection);
+        migrate(&connection);
+
+        let mut links: Vec<Links> = Vec::new();
+        add_to_vec_from_database(connection.prepare(format!("SELECT {LINKS_COLUMNS} FROM links")).unwrap(), &mut links);
+
+        drop(connection);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].link, "https://example.com");
+        assert_eq!(links[0].checks_total, 0);
+        assert!(!links[0].watch_content);
+        assert_eq!(links[0].labels, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_open_alert() {
+        add_link(8854, "https://example.com");
+
+        // The site does not answer at two checks in a row
+        let text = "Сайт не ответил за 15 секунд: https://example.com";
+        let (first, is_first_new) = open_alert(8854, "https://example.com", text, 1000);
+        let (second, is_second_new) = open_alert(8854, "https://example.com", text, 4600);
+
+        let alerts = get_link_alerts(8854, "https://example.com", i64::MAX);
+
+        clear_all_links(8854);
+
+        assert!(is_first_new);
+        assert!(!is_second_new);
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.sent_at, 1000);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].resolved_at, None);
+    }
+
+    #[test]
+    fn test_restore_links() {
+        add_link(8845, "https://example.com");
+        update_link_settings(8845, "https://example.com", Some(7), None, None, false, true);
+        add_maintenance_window(8845, "https://example.com", "daily 02:00 30m UTC");
+
+        let links = get_all_links_from_user(8845, None);
+        let windows: Vec<String> = get_maintenance_windows(8845, "https://example.com").into_iter().map(|window| window.schedule).collect();
+        clear_all_links(8845);
+
+        let deleted_windows = get_maintenance_windows(8845, "https://example.com");
+        restore_links(&[(links[0].clone(), windows)]);
+
+        let restored = get_all_links_from_user(8845, None);
+        let restored_windows = get_maintenance_windows(8845, "https://example.com");
+        clear_all_links(8845);
+
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].timeout, Some(7));
+        assert!(restored[0].accept_invalid_certs);
+        assert!(deleted_windows.is_empty());
+        assert_eq!(restored_windows.len(), 1);
+        assert_eq!(restored_windows[0].schedule, "daily 02:00 30m UTC");
+    }
+
+    #[test]
+    fn test_analyses() {
+        for checked_at in 1..=12 {
+            add_analysis(8844, "https://example.com", checked_at, &checked_at.to_string());
+        }
+
+        let analyses = get_analyses(8844, "https://example.com");
+
+        assert_eq!(analyses.len(), MAX_ANALYSES as usize);
+        assert_eq!(analyses[0].checked_at, 12);
+        assert_eq!(analyses[0].data, "12");
+        assert!(get_analyses(8844, "https://example.org").is_empty());
+    }
+
+    #[test]
+    fn test_content_watch() {
+        add_link(8842, "https://example.com");
+        update_content_watch(8842, "https://example.com", true, Some("css:.banner"));
+        update_content_snapshot(8842, "https://example.com", "hash", "Welcome");
+
+        let links = get_all_links_from_user(8842, Some("https://example.com"));
+
+        assert!(links[0].watch_content);
+        assert_eq!(links[0].content_ignore.as_deref(), Some("css:.banner"));
+        assert_eq!(links[0].content_hash.as_deref(), Some("hash"));
+
+        update_content_watch(8842, "https://example.com", false, None);
+
+        let links = get_all_links_from_user(8842, Some("https://example.com"));
+        clear_all_links(8842);
+
+        assert!(!links[0].watch_content);
+        assert_eq!(links[0].content_text, None);
+    }
+
+    #[test]
+    fn test_update_dns_records() {
+        add_link(8843, "https://example.com");
+        update_dns_records(8843, "https://example.com", "A 93.184.216.34", Some("A 203.0.113.10"));
+
+        let links = get_all_links_from_user(8843, Some("https://example.com"));
+        clear_all_links(8843);
+
+        assert_eq!(links[0].dns_records.as_deref(), Some("A 93.184.216.34"));
+        assert_eq!(links[0].pending_dns_records.as_deref(), Some("A 203.0.113.10"));
+    }
+
+    #[test]
+    fn test_clear_all_links() {
+        add_link(654355, "Lol");
+        add_link(654356, "Go");
+
+        clear_all_links(654355);
 
-        println!("Histories for {} is cleared!", 654352f64);
-        println!("Histories for {} is cleared!", 3552f64);
+        assert!(get_all_links_from_user(654355, None).is_empty());
+        assert_eq!(get_all_links_from_user(654356, None).len(), 1);
 
-        assert!(true)
+        clear_all_links(654356);
     }
 }
\ No newline at end of file
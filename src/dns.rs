@@ -0,0 +1,593 @@
+use std::env;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use hickory_resolver::config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts};
+use hickory_resolver::error::{ResolveError, ResolveErrorKind};
+use hickory_resolver::proto::rr::RecordType;
+use hickory_resolver::TokioAsyncResolver;
+use serde::{Deserialize, Serialize};
+
+/// Port of the DNS server if it is not specified in `DNS_SERVER`
+const DNS_PORT: u16 = 53;
+
+/// Represents the DNS records of a host.
+#[derive(Default, Serialize, Deserialize)]
+pub struct DnsInformation {
+    /// Time of resolving all the records in milliseconds
+    pub duration: u128,
+    pub a: Vec<String>,
+    pub aaaa: Vec<String>,
+    pub cname: Vec<String>,
+    pub mx: Vec<String>,
+    pub ns: Vec<String>,
+    pub txt: Vec<String>,
+    /// CAA records of the host or of the closest parent domain that has them
+    pub caa: Vec<String>,
+    /// SPF records of the mail domain
+    pub spf: Vec<String>,
+    /// DMARC records of the mail domain
+    pub dmarc: Vec<String>
+}
+
+/// Represents a problem found in the DNS records.
+#[derive(Debug, PartialEq)]
+pub enum DnsWarning {
+    /// There are no AAAA records, so the site is unavailable over IPv6
+    NoIpv6,
+    /// There are no CAA records, so any certification authority can issue a certificate
+    NoCaa,
+    NoSpf,
+    /// There are several SPF records, which makes SPF invalid
+    MultipleSpf,
+    /// The SPF record allows anyone to send mail with `+all`
+    PermissiveSpf,
+    NoDmarc,
+    /// The DMARC policy is `p=none`, so the mail that fails the checks is still delivered
+    DmarcPolicyNone
+}
+
+/// Represents the records of a host that are monitored for changes.
+#[derive(Debug, Default, PartialEq)]
+pub struct DnsSnapshot {
+    pub a: Vec<String>,
+    pub aaaa: Vec<String>,
+    pub ns: Vec<String>
+}
+
+/// Represents a change of the records of one type.
+pub struct DnsChange {
+    pub record_type: &'static str,
+    pub old: Vec<String>,
+    pub new: Vec<String>
+}
+
+impl DnsSnapshot {
+    /// Parses a snapshot saved with `to_text`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Records, one per line, in the form `<type> <value>`.
+    pub fn parse(text: &str) -> DnsSnapshot {
+        let mut snapshot = DnsSnapshot::default();
+
+        for (record_type, value) in text.lines().filter_map(|line| line.split_once(' ')) {
+            match record_type {
+                "A" => snapshot.a.push(value.to_string()),
+                "AAAA" => snapshot.aaaa.push(value.to_string()),
+                "NS" => snapshot.ns.push(value.to_string()),
+                _ => ()
+            }
+        }
+
+        snapshot
+    }
+
+    /// Converts the snapshot to text for saving in the database.
+    pub fn to_text(&self) -> String {
+        self.records()
+            .iter()
+            .flat_map(|(record_type, values)| values.iter().map(move |value| format!("{record_type} {value}")))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Checks if the host has no addresses, that is, it does not resolve.
+    pub fn is_unresolved(&self) -> bool {
+        self.a.is_empty() && self.aaaa.is_empty()
+    }
+
+    /// Returns the records grouped by their types
+    fn records(&self) -> [(&'static str, &Vec<String>); 3] {
+        [("A", &self.a), ("AAAA", &self.aaaa), ("NS", &self.ns)]
+    }
+}
+
+/// Creates a DNS resolver.
+///
+/// If the `DNS_SERVER` environment variable contains an address like `127.0.0.1` or `127.0.0.1:5353`,
+/// the resolver sends queries to it, otherwise the system configuration is used.
+pub fn create_resolver() -> TokioAsyncResolver {
+    create_resolver_for(env::var("DNS_SERVER").ok().as_deref())
+}
+
+/// Creates a DNS resolver that sends queries to the server with the address in the format of `DNS_SERVER`.
+///
+/// # Arguments
+///
+/// * `server` - Address of the DNS server, the system configuration is used if it is `None` or invalid.
+fn create_resolver_for(server: Option<&str>) -> TokioAsyncResolver {
+    let server = server.and_then(|server| {
+        server.parse::<SocketAddr>().ok()
+            .or_else(|| server.parse::<IpAddr>().ok().map(|ip| SocketAddr::new(ip, DNS_PORT)))
+    });
+
+    match server {
+        Some(server) => {
+            let mut config = ResolverConfig::new();
+            config.add_name_server(NameServerConfig::new(server, Protocol::Udp));
+
+            TokioAsyncResolver::tokio(config, ResolverOpts::default())
+        }
+        None => {
+            TokioAsyncResolver::tokio_from_system_conf()
+                .unwrap_or_else(|_| TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()))
+        }
+    }
+}
+
+/// Resolves the DNS records of the host with the resolver from `create_resolver`.
+///
+/// SPF and DMARC records are requested for the mail domain, that is, the host without the `www.` prefix.
+/// NS records are taken from the zone that contains the host. Record types that the host does not have are left empty.
+///
+/// # Arguments
+///
+/// * `host` - Domain name of the site.
+///
+/// # Returns
+///
+/// The `DnsInformation` of the host.
+pub async fn get_dns_information(host: &str) -> DnsInformation {
+    resolve_dns_information(&create_resolver(), host).await
+}
+
+/// Resolves the DNS records of the host, see `get_dns_information`.
+///
+/// # Arguments
+///
+/// * `resolver` - The DNS resolver.
+/// * `host` - Domain name of the site.
+async fn resolve_dns_information(resolver: &TokioAsyncResolver, host: &str) -> DnsInformation {
+    let mail_domain = host.strip_prefix("www.").unwrap_or(host);
+    let dmarc_domain = format!("_dmarc.{mail_domain}");
+
+    let time_now = std::time::Instant::now();
+
+    let (a, aaaa, cname, mx, ns, txt, caa, mail_txt, dmarc) = tokio::join!(
+        lookup(resolver, host, RecordType::A),
+        lookup(resolver, host, RecordType::AAAA),
+        lookup(resolver, host, RecordType::CNAME),
+        lookup(resolver, host, RecordType::MX),
+        lookup_zone_ns(resolver, host),
+        lookup(resolver, host, RecordType::TXT),
+        lookup_caa(resolver, host),
+        lookup(resolver, mail_domain, RecordType::TXT),
+        lookup(resolver, &dmarc_domain, RecordType::TXT)
+    );
+
+    let duration = time_now.elapsed().as_millis();
+
+    DnsInformation {
+        duration,
+        a,
+        aaaa,
+        cname,
+        mx,
+        ns: ns.unwrap_or_default(),
+        txt,
+        caa,
+        spf: mail_txt.into_iter().filter(|record| record.to_lowercase().starts_with("v=spf1")).collect(),
+        dmarc: dmarc.into_iter().filter(|record| record.to_lowercase().starts_with("v=dmarc1")).collect()
+    }
+}
+
+/// Requests the records of the specified type.
+///
+/// # Arguments
+///
+/// * `resolver` - The DNS resolver.
+/// * `name` - Domain name.
+/// * `record_type` - Type of the records.
+///
+/// # Returns
+///
+/// Text representations of the records, or an empty vector if there are none or the query failed.
+pub async fn lookup(resolver: &TokioAsyncResolver, name: &str, record_type: RecordType) -> Vec<String> {
+    lookup_records(resolver, name, record_type).await.unwrap_or_default()
+}
+
+/// Requests the records of the specified type, distinguishing the absence of records from a failed query.
+///
+/// # Arguments
+///
+/// * `resolver` - The DNS resolver.
+/// * `name` - Domain name.
+/// * `record_type` - Type of the records.
+///
+/// # Returns
+///
+/// Sorted text representations of the records, or a `ResolveError` if the DNS server could not be queried.
+async fn lookup_records(resolver: &TokioAsyncResolver, name: &str, record_type: RecordType) -> Result<Vec<String>, ResolveError> {
+    match resolver.lookup(name, record_type).await {
+        Ok(lookup) => {
+            let mut records: Vec<String> = lookup.record_iter()
+                .filter(|record| record.record_type() == record_type)
+                .filter_map(|record| record.data())
+                .map(|data| data.to_string())
+                .collect();
+
+            records.sort();
+
+            Ok(records)
+        }
+        Err(err) if matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }) => Ok(Vec::new()),
+        Err(err) => Err(err)
+    }
+}
+
+/// Requests the monitored records of the host.
+///
+/// # Arguments
+///
+/// * `resolver` - The DNS resolver.
+/// * `host` - Domain name.
+///
+/// # Returns
+///
+/// The `DnsSnapshot` of the host, or a `ResolveError` if the DNS server could not be queried.
+pub async fn get_dns_snapshot(resolver: &TokioAsyncResolver, host: &str) -> Result<DnsSnapshot, ResolveError> {
+    let (a, aaaa, ns) = tokio::join!(
+        lookup_records(resolver, host, RecordType::A),
+        lookup_records(resolver, host, RecordType::AAAA),
+        lookup_zone_ns(resolver, host)
+    );
+
+    Ok(DnsSnapshot {
+        a: a?,
+        aaaa: aaaa?,
+        ns: ns?
+    })
+}
+
+/// Requests the NS records of the zone of the host, going up to the parent domains until the records
+/// are found, since a subdomain usually has no NS records of its own.
+///
+/// # Arguments
+///
+/// * `resolver` - The DNS resolver.
+/// * `host` - Domain name.
+async fn lookup_zone_ns(resolver: &TokioAsyncResolver, host: &str) -> Result<Vec<String>, ResolveError> {
+    let mut name = host.trim_end_matches('.');
+
+    loop {
+        let records = lookup_records(resolver, name, RecordType::NS).await?;
+
+        match name.split_once('.') {
+            Some((_, parent)) if records.is_empty() && parent.contains('.') => name = parent,
+            _ => return Ok(records)
+        }
+    }
+}
+
+/// Checks if two lists contain the same records regardless of their order and case
+fn is_same_records(first: &[String], second: &[String]) -> bool {
+    let normalize = |records: &[String]| {
+        let mut records: Vec<String> = records.iter().map(|record| record.to_lowercase()).collect();
+        records.sort();
+        records.dedup();
+        records
+    };
+
+    normalize(first) == normalize(second)
+}
+
+/// Merges the current records of the host into the saved ones, accepting only the changes that have
+/// persisted over two checks in a row, so that the rotating addresses of round-robin DNS and CDNs
+/// are not reported as changes.
+///
+/// # Arguments
+///
+/// * `saved` - The saved snapshot.
+/// * `pending` - The snapshot of the previous check whose changes were not accepted yet.
+/// * `new` - The current snapshot.
+///
+/// # Returns
+///
+/// The new saved snapshot and whether the current snapshot has changes that are not accepted yet.
+///
+/// # Example
+///
+/// ```
+/// let (confirmed, is_pending) = confirm_changes(&saved, pending.as_ref(), &new);
+///
+/// for change in compare_snapshots(&saved, &confirmed) {
+///     println!("{}: {:?} -> {:?}", change.record_type, change.old, change.new);
+/// }
+/// ```
+pub fn confirm_changes(saved: &DnsSnapshot, pending: Option<&DnsSnapshot>, new: &DnsSnapshot) -> (DnsSnapshot, bool) {
+    let mut is_pending = false;
+
+    let mut merge = |saved: &Vec<String>, pending: Option<&Vec<String>>, new: &Vec<String>| {
+        if is_same_records(saved, new) {
+            saved.clone()
+        }
+        else if pending.is_some_and(|pending| is_same_records(pending, new)) {
+            new.clone()
+        }
+        else {
+            is_pending = true;
+            saved.clone()
+        }
+    };
+
+    let snapshot = DnsSnapshot {
+        a: merge(&saved.a, pending.map(|pending| &pending.a), &new.a),
+        aaaa: merge(&saved.aaaa, pending.map(|pending| &pending.aaaa), &new.aaaa),
+        ns: merge(&saved.ns, pending.map(|pending| &pending.ns), &new.ns)
+    };
+
+    (snapshot, is_pending)
+}
+
+/// Compares two snapshots of the host records regardless of the order of the records.
+///
+/// # Arguments
+///
+/// * `old` - The previously saved snapshot.
+/// * `new` - The current snapshot.
+///
+/// # Returns
+///
+/// Changes of the record types whose values are different.
+pub fn compare_snapshots(old: &DnsSnapshot, new: &DnsSnapshot) -> Vec<DnsChange> {
+    old.records().into_iter()
+        .zip(new.records())
+        .filter(|((_, old_values), (_, new_values))| !is_same_records(old_values, new_values))
+        .map(|((record_type, old_values), (_, new_values))| DnsChange {
+            record_type,
+            old: old_values.clone(),
+            new: new_values.clone()
+        })
+        .collect()
+}
+
+/// Resolves the IPv4 and IPv6 addresses of the host.
+///
+/// # Arguments
+///
+/// * `host` - Domain name.
+///
+/// # Returns
+///
+/// The IPv4 addresses and the IPv6 addresses of the host.
+pub async fn resolve_addresses(host: &str) -> (Vec<IpAddr>, Vec<IpAddr>) {
+    let resolver = create_resolver();
+
+    let (a, aaaa) = tokio::join!(
+        lookup(&resolver, host, RecordType::A),
+        lookup(&resolver, host, RecordType::AAAA)
+    );
+
+    (
+        a.iter().filter_map(|address| address.parse::<Ipv4Addr>().ok()).map(IpAddr::V4).collect(),
+        aaaa.iter().filter_map(|address| address.parse::<Ipv6Addr>().ok()).map(IpAddr::V6).collect()
+    )
+}
+
+/// Requests the CAA records of the host, going up to the parent domains until the records are found,
+/// since certification authorities check them the same way.
+///
+/// # Arguments
+///
+/// * `resolver` - The DNS resolver.
+/// * `host` - Domain name.
+async fn lookup_caa(resolver: &TokioAsyncResolver, host: &str) -> Vec<String> {
+    let mut name = host.trim_end_matches('.');
+
+    loop {
+        let records = lookup(resolver, name, RecordType::CAA).await;
+
+        match name.split_once('.') {
+            Some((_, parent)) if records.is_empty() && parent.contains('.') => name = parent,
+            _ => return records
+        }
+    }
+}
+
+/// Finds problems in the DNS records.
+///
+/// # Arguments
+///
+/// * `dns` - The DNS records of the host.
+///
+/// # Returns
+///
+/// A vector of the found problems.
+pub fn find_dns_warnings(dns: &DnsInformation) -> Vec<DnsWarning> {
+    let mut warnings: Vec<DnsWarning> = Vec::new();
+
+    if dns.aaaa.is_empty() {
+        warnings.push(DnsWarning::NoIpv6);
+    }
+
+    if dns.caa.is_empty() {
+        warnings.push(DnsWarning::NoCaa);
+    }
+
+    match dns.spf.len() {
+        0 => warnings.push(DnsWarning::NoSpf),
+        1 => {
+            if dns.spf[0].split_whitespace().any(|mechanism| mechanism == "+all" || mechanism == "all") {
+                warnings.push(DnsWarning::PermissiveSpf);
+            }
+        }
+        _ => warnings.push(DnsWarning::MultipleSpf)
+    }
+
+    match dns.dmarc.first() {
+        Some(dmarc) => {
+            let is_policy_none = dmarc.split(';')
+                .map(|tag| tag.split_whitespace().collect::<String>().to_lowercase())
+                .any(|tag| tag == "p=none");
+
+            if is_policy_none {
+                warnings.push(DnsWarning::DmarcPolicyNone);
+            }
+        }
+        None => warnings.push(DnsWarning::NoDmarc)
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod dns_tests {
+    use hickory_resolver::proto::op::{Message, MessageType};
+    use hickory_resolver::proto::rr::{Name, RData, Record};
+    use hickory_resolver::proto::rr::rdata::{A, NS, TXT};
+    use tokio::net::UdpSocket;
+    use super::*;
+
+    /// Starts a DNS server for the `example.test` zone, where `www.example.test` has only an A record
+    /// and the SPF, DMARC and NS records belong to the zone apex
+    async fn start_dns_server() -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buffer = [0; 512];
+
+            loop {
+                let (length, peer) = socket.recv_from(&mut buffer).await.unwrap();
+                let request = Message::from_vec(&buffer[..length]).unwrap();
+
+                let mut response = Message::new();
+                response.set_id(request.id()).set_message_type(MessageType::Response).set_recursion_available(true);
+
+                for query in request.queries() {
+                    response.add_query(query.clone());
+
+                    let records = match (query.name().to_string().as_str(), query.query_type()) {
+                        ("www.example.test.", RecordType::A) => vec![RData::A(A(Ipv4Addr::new(192, 0, 2, 1)))],
+                        ("example.test.", RecordType::NS) => vec![RData::NS(NS(Name::from_ascii("ns1.example.test.").unwrap()))],
+                        ("example.test.", RecordType::TXT) => vec![RData::TXT(TXT::new(vec![String::from("v=spf1 -all")]))],
+                        ("_dmarc.example.test.", RecordType::TXT) => vec![RData::TXT(TXT::new(vec![String::from("v=DMARC1; p=reject")]))],
+                        _ => Vec::new()
+                    };
+
+                    for record in records {
+                        response.add_answer(Record::from_rdata(query.name().clone(), 60, record));
+                    }
+                }
+
+                socket.send_to(&response.to_vec().unwrap(), peer).await.unwrap();
+            }
+        });
+
+        address
+    }
+
+    #[tokio::test]
+    async fn test_resolve_dns_information() {
+        let server = start_dns_server().await;
+        let resolver = create_resolver_for(Some(&server.to_string()));
+
+        let dns = resolve_dns_information(&resolver, "www.example.test").await;
+        let snapshot = get_dns_snapshot(&resolver, "www.example.test").await.unwrap();
+
+        assert_eq!(dns.a, vec!["192.0.2.1"]);
+        assert!(dns.aaaa.is_empty());
+        // The subdomain has no NS records of its own, so the records of the zone are shown
+        assert_eq!(dns.ns, vec!["ns1.example.test."]);
+        assert_eq!(dns.spf, vec!["v=spf1 -all"]);
+        assert_eq!(dns.dmarc, vec!["v=DMARC1; p=reject"]);
+        assert_eq!(find_dns_warnings(&dns), vec![DnsWarning::NoIpv6, DnsWarning::NoCaa]);
+        assert_eq!(snapshot.ns, dns.ns);
+    }
+
+    #[test]
+    fn test_find_dns_warnings() {
+        let dns = DnsInformation {
+            a: vec![String::from("93.184.216.34")],
+            aaaa: vec![String::from("2606:2800:220:1:248:1893:25c8:1946")],
+            caa: vec![String::from("0 issue \"letsencrypt.org\"")],
+            spf: vec![String::from("v=spf1 include:_spf.example.com -all")],
+            dmarc: vec![String::from("v=DMARC1; p=reject")],
+            ..Default::default()
+        };
+
+        assert!(find_dns_warnings(&dns).is_empty());
+    }
+
+    #[test]
+    fn test_dns_snapshot() {
+        let old = DnsSnapshot {
+            a: vec![String::from("93.184.216.34")],
+            aaaa: vec![],
+            ns: vec![String::from("a.iana-servers.net."), String::from("b.iana-servers.net.")]
+        };
+
+        assert_eq!(DnsSnapshot::parse(&old.to_text()), old);
+
+        let new = DnsSnapshot {
+            a: vec![String::from("203.0.113.10")],
+            ..DnsSnapshot::parse(&old.to_text())
+        };
+
+        let changes = compare_snapshots(&old, &new);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].record_type, "A");
+        assert_eq!(changes[0].old, vec!["93.184.216.34"]);
+        assert_eq!(changes[0].new, vec!["203.0.113.10"]);
+        assert!(!new.is_unresolved());
+        assert!(DnsSnapshot::default().is_unresolved());
+
+        let reordered = DnsSnapshot { ns: vec![String::from("B.iana-servers.net."), String::from("a.iana-servers.net.")], ..DnsSnapshot::parse(&old.to_text()) };
+
+        assert!(compare_snapshots(&old, &reordered).is_empty());
+    }
+
+    #[test]
+    fn test_confirm_changes() {
+        let saved = DnsSnapshot { a: vec![String::from("192.0.2.1")], ns: vec![String::from("ns1.example.com.")], ..Default::default() };
+        let rotated = DnsSnapshot { a: vec![String::from("192.0.2.2")], ..DnsSnapshot::parse(&saved.to_text()) };
+        let moved = DnsSnapshot { ns: vec![String::from("ns1.example.net.")], ..DnsSnapshot::parse(&saved.to_text()) };
+
+        // The first check with new records only remembers them
+        assert_eq!(confirm_changes(&saved, None, &moved), (DnsSnapshot::parse(&saved.to_text()), true));
+        // The same records at the next check are accepted
+        assert_eq!(confirm_changes(&saved, Some(&moved), &moved), (DnsSnapshot::parse(&moved.to_text()), false));
+        // Records that differ from the previous check are not accepted, as with rotating addresses
+        assert_eq!(confirm_changes(&saved, Some(&rotated), &moved), (DnsSnapshot::parse(&saved.to_text()), true));
+        assert_eq!(confirm_changes(&saved, Some(&moved), &rotated), (DnsSnapshot::parse(&saved.to_text()), true));
+        assert_eq!(confirm_changes(&saved, Some(&moved), &saved), (DnsSnapshot::parse(&saved.to_text()), false));
+    }
+
+    #[test]
+    fn test_find_dns_warnings_with_problems() {
+        let dns = DnsInformation {
+            spf: vec![String::from("v=spf1 +all")],
+            dmarc: vec![String::from("v=DMARC1; p = none; rua=mailto:dmarc@example.com")],
+            ..Default::default()
+        };
+
+        assert_eq!(find_dns_warnings(&dns), vec![DnsWarning::NoIpv6, DnsWarning::NoCaa, DnsWarning::PermissiveSpf, DnsWarning::DmarcPolicyNone]);
+
+        let dns = DnsInformation {
+            spf: vec![String::from("v=spf1 -all"), String::from("v=spf1 ~all")],
+            ..Default::default()
+        };
+
+        assert_eq!(find_dns_warnings(&dns), vec![DnsWarning::NoIpv6, DnsWarning::NoCaa, DnsWarning::MultipleSpf, DnsWarning::NoDmarc]);
+    }
+}
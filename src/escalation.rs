@@ -0,0 +1,70 @@
+/// Maximum delay before an alert is escalated to the next subscriber, in minutes
+pub const MAX_ESCALATION_MINUTES: u64 = 24 * 60;
+
+/// Selects the subscribers of a link who should be notified about an alert now.
+///
+/// Without an escalation policy all subscribers who have not been notified yet are notified at once.
+/// With a policy only the next one is notified, the others wait until the alert is escalated.
+/// Muted subscribers are skipped.
+///
+/// # Arguments
+///
+/// * `muted` - Whether each subscriber is muted, in the order of the subscribers.
+/// * `level` - The index of the first subscriber who has not been notified yet.
+/// * `escalation_minutes` - The delay before the alert is escalated, `None` if the link has no escalation policy.
+///
+/// # Returns
+///
+/// The indexes of the subscribers to notify and the new level of the alert.
+///
+/// # Example
+///
+/// ```
+/// // The primary is muted, so the second subscriber is notified
+/// assert_eq!(select_subscribers(&[true, false, false], 0, Some(15)), (vec![1], 2));
+/// ```
+pub fn select_subscribers(muted: &[bool], level: usize, escalation_minutes: Option<u64>) -> (Vec<usize>, usize) {
+    let mut selected = (level..muted.len()).filter(|index| !muted[*index]);
+
+    match escalation_minutes {
+        Some(_) => match selected.next() {
+            Some(index) => (vec![index], index + 1),
+            None => (Vec::new(), muted.len().max(level))
+        },
+        None => (selected.collect(), muted.len().max(level))
+    }
+}
+
+/// Checks if an alert that nobody has acknowledged should be escalated to the next subscriber.
+///
+/// # Arguments
+///
+/// * `escalated_at` - Time when the last subscriber was notified as a Unix timestamp.
+/// * `escalation_minutes` - The delay before the alert is escalated.
+/// * `now` - The current time as a Unix timestamp.
+pub fn is_escalation_due(escalated_at: u64, escalation_minutes: u64, now: u64) -> bool {
+    now >= escalated_at.saturating_add(escalation_minutes * 60)
+}
+
+#[cfg(test)]
+mod escalation_tests {
+    use super::*;
+
+    #[test]
+    fn test_select_subscribers() {
+        assert_eq!(select_subscribers(&[false, false, false], 0, Some(15)), (vec![0], 1));
+        assert_eq!(select_subscribers(&[false, true, false], 1, Some(15)), (vec![2], 3));
+        assert_eq!(select_subscribers(&[false, true], 1, Some(15)), (vec![], 2));
+        assert_eq!(select_subscribers(&[false, true, false], 0, None), (vec![0, 2], 3));
+        assert_eq!(select_subscribers(&[], 0, None), (vec![], 0));
+        // A subscriber who unsubscribed after the alert was sent does not reset the level
+        assert_eq!(select_subscribers(&[false], 2, Some(15)), (vec![], 2));
+    }
+
+    #[test]
+    fn test_is_escalation_due() {
+        assert!(!is_escalation_due(1000, 15, 1000 + 14 * 60));
+        assert!(is_escalation_due(1000, 15, 1000 + 15 * 60));
+        assert!(is_escalation_due(1000, 0, 1000));
+    }
+}
@@ -0,0 +1,398 @@
+use sha2::{Digest, Sha256};
+use crate::database::Links;
+
+/// Maximum length of the search query in bytes, so that the view fits in the callback data of a button
+pub const MAX_QUERY_LENGTH: usize = 48;
+
+/// Maximum length of a label in characters, so that a label fits in the callback data of a button
+pub const MAX_LABEL_LENGTH: usize = 20;
+
+/// Maximum number of labels of one link
+pub const MAX_LABELS: usize = 10;
+
+/// Length of the key of a link in hexadecimal characters
+const LINK_KEY_LENGTH: usize = 16;
+
+/// Represents the order of the links in the list.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LinksSort {
+    /// From the oldest link to the newest one
+    #[default]
+    Added,
+    /// Alphabetically by the name of the link, or by the link itself if it has no name
+    Name,
+    /// Links that do not work first, then unchecked, paused and working links
+    Status
+}
+
+impl LinksSort {
+    /// Returns the sort that follows this one when the user switches the sort.
+    pub fn next(&self) -> LinksSort {
+        match self {
+            LinksSort::Added => LinksSort::Name,
+            LinksSort::Name => LinksSort::Status,
+            LinksSort::Status => LinksSort::Added
+        }
+    }
+
+    /// Returns the letter that denotes the sort in the callback data.
+    fn code(&self) -> char {
+        match self {
+            LinksSort::Added => 'a',
+            LinksSort::Name => 'n',
+            LinksSort::Status => 's'
+        }
+    }
+
+    /// Parses the letter that denotes the sort in the callback data.
+    fn from_code(code: &str) -> Option<LinksSort> {
+        match code {
+            "a" => Some(LinksSort::Added),
+            "n" => Some(LinksSort::Name),
+            "s" => Some(LinksSort::Status),
+            _ => None
+        }
+    }
+}
+
+/// Represents the shown page of the list of links with its sort and search query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinksView {
+    /// The shown page, starting from 0
+    pub page: usize,
+    pub sort: LinksSort,
+    /// Text that the link or its name must contain and labels written as `#label`, the list is
+    /// not filtered if it is empty
+    pub query: String
+}
+
+impl LinksView {
+    /// Parses the view written by `to_callback_data` without the action.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice like `2:n:example`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the text is not a valid view.
+    pub fn parse(text: &str) -> Option<LinksView> {
+        let mut parts = text.splitn(3, ':');
+
+        let page = parts.next()?.parse::<usize>().ok()?;
+        let sort = LinksSort::from_code(parts.next()?)?;
+        let query = parts.next().unwrap_or_default().to_string();
+
+        Some(LinksView { page, sort, query })
+    }
+
+    /// Writes the view into the callback data of a button.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action of the button.
+    pub fn to_callback_data(&self, action: &str) -> String {
+        format!("{action}:{}:{}:{}", self.page, self.sort.code(), self.query)
+    }
+}
+
+/// Returns the key that identifies the link in the callback data of the buttons.
+///
+/// Unlike the position of the link in the list, the key does not change when other links are added
+/// or deleted, so a button shown earlier acts on its own link or on none. The link itself is often
+/// too long for the callback data, so the key is the beginning of its SHA-256 hash.
+///
+/// # Arguments
+///
+/// * `link` - The saved link.
+pub fn get_link_key(link: &str) -> String {
+    Sha256::digest(link.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>()[..LINK_KEY_LENGTH]
+        .to_string()
+}
+
+/// Finds the link by the key returned by `get_link_key`.
+///
+/// # Arguments
+///
+/// * `links` - All links of the chat.
+/// * `key` - The key of the link.
+///
+/// # Returns
+///
+/// The index of the link in `links` with the link, `None` if the link is no longer saved.
+pub fn find_link_by_key<'a>(links: &'a [Links], key: &str) -> Option<(usize, &'a Links)> {
+    links.iter().enumerate().find(|(_, link)| get_link_key(&link.link) == key)
+}
+
+/// Parses labels separated by spaces or commas.
+///
+/// Labels are converted to lowercase and may start with `#`. A label consists of letters, digits,
+/// `-` and `_` and is at most `MAX_LABEL_LENGTH` characters long.
+///
+/// # Arguments
+///
+/// * `text` - A string slice like `prod, #client-x`.
+///
+/// # Returns
+///
+/// Unique labels in alphabetical order, or the first invalid label.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(parse_labels("Prod #client-x prod"), Ok(vec![String::from("client-x"), String::from("prod")]));
+/// ```
+pub fn parse_labels(text: &str) -> Result<Vec<String>, String> {
+    let mut labels: Vec<String> = Vec::new();
+
+    for part in text.split(|character: char| character.is_whitespace() || character == ',').filter(|part| !part.is_empty()) {
+        let label = part.strip_prefix('#').unwrap_or(part).to_lowercase();
+
+        let is_valid = (1..=MAX_LABEL_LENGTH).contains(&label.chars().count())
+            && label.chars().all(|character| character.is_alphanumeric() || character == '-' || character == '_');
+
+        if !is_valid {
+            return Err(part.to_string());
+        }
+
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+
+    labels.sort();
+
+    Ok(labels)
+}
+
+/// Splits the search query into the labels written as `#label` and the rest of the text.
+///
+/// # Arguments
+///
+/// * `query` - A string slice like `#prod shop`.
+pub fn parse_query(query: &str) -> (Vec<String>, String) {
+    let (labels, words): (Vec<&str>, Vec<&str>) = query.split_whitespace().partition(|word| word.len() > 1 && word.starts_with('#'));
+
+    let labels = labels.into_iter().map(|label| label[1..].to_lowercase()).collect();
+
+    (labels, words.join(" ").to_lowercase())
+}
+
+/// Selects the links that match the search query of the view and sorts them.
+///
+/// # Arguments
+///
+/// * `links` - All links of the user in the order of their numbers.
+/// * `view` - The sort and the search query.
+///
+/// # Returns
+///
+/// The selected links with their indexes in `links`.
+///
+/// # Example
+///
+/// ```
+/// let view = LinksView { page: 0, sort: LinksSort::Name, query: String::from("example") };
+///
+/// for (index, link) in select_links(&links, &view) {
+///     println!("[{}] {}", index + 1, link.link);
+/// }
+/// ```
+pub fn select_links<'a>(links: &'a [Links], view: &LinksView) -> Vec<(usize, &'a Links)> {
+    let (labels, query) = parse_query(&view.query);
+
+    let mut selected: Vec<(usize, &Links)> = links.iter()
+        .enumerate()
+        .filter(|(_, link)| labels.iter().all(|label| link.labels.contains(label)))
+        .filter(|(_, link)| {
+            link.link.to_lowercase().contains(&query)
+                || link.name.as_ref().is_some_and(|name| name.to_lowercase().contains(&query))
+        })
+        .collect();
+
+    match view.sort {
+        // Links added before the date was saved are considered the oldest
+        LinksSort::Added => selected.sort_by_key(|(_, link)| link.added_at),
+        LinksSort::Name => selected.sort_by_key(|(_, link)| link.name.as_ref().unwrap_or(&link.link).to_lowercase()),
+        LinksSort::Status => selected.sort_by_key(|(_, link)| get_status_rank(link))
+    }
+
+    selected
+}
+
+/// Represents the summary of the state of several links.
+#[derive(Debug, Default, PartialEq)]
+pub struct LinksStats {
+    pub total: usize,
+    pub up: usize,
+    pub down: usize,
+    pub paused: usize,
+    /// Links that have not been checked yet
+    pub unchecked: usize,
+    /// Share of successful checks of all links in percent, `None` if there were no checks
+    pub uptime: Option<f64>
+}
+
+/// Counts the links by their status at the last check and calculates their total uptime.
+///
+/// # Arguments
+///
+/// * `links` - The links.
+pub fn calculate_stats(links: &[&Links]) -> LinksStats {
+    let mut stats = LinksStats { total: links.len(), ..Default::default() };
+
+    for link in links {
+        match get_status_rank(link) {
+            0 => stats.down += 1,
+            1 => stats.unchecked += 1,
+            2 => stats.paused += 1,
+            _ => stats.up += 1
+        }
+    }
+
+    let checks_total: u64 = links.iter().map(|link| link.checks_total).sum();
+    let checks_up: u64 = links.iter().map(|link| link.checks_up).sum();
+
+    if checks_total > 0 {
+        stats.uptime = Some(checks_up as f64 * 100.0 / checks_total as f64);
+    }
+
+    stats
+}
+
+/// Counts the links with each label.
+///
+/// # Arguments
+///
+/// * `links` - The links.
+///
+/// # Returns
+///
+/// Labels in alphabetical order with the number of links.
+pub fn count_labels(links: &[Links]) -> Vec<(String, usize)> {
+    let mut labels: Vec<(String, usize)> = Vec::new();
+
+    for label in links.iter().flat_map(|link| &link.labels) {
+        match labels.iter_mut().find(|(saved, _)| saved == label) {
+            Some((_, count)) => *count += 1,
+            None => labels.push((label.clone(), 1))
+        }
+    }
+
+    labels.sort();
+
+    labels
+}
+
+/// Returns the position of the link in the list sorted by status
+fn get_status_rank(link: &Links) -> u8 {
+    match (link.paused, link.last_up) {
+        (false, Some(false)) => 0,
+        (false, None) => 1,
+        (true, _) => 2,
+        (false, Some(true)) => 3
+    }
+}
+
+#[cfg(test)]
+mod links_list_tests {
+    use super::*;
+
+    fn create_link(link: &str, name: Option<&str>, added_at: Option<u64>, last_up: Option<bool>) -> Links {
+        Links {
+            user_id: 1.0,
+            link: link.to_string(),
+            timeout: None,
+            expected_codes: None,
+            slow_threshold: None,
+            watch_content: false,
+            content_ignore: None,
+            content_hash: None,
+            content_text: None,
+            dns_records: None,
+            pending_dns_records: None,
+            dual_stack: false,
+            accept_invalid_certs: false,
+            name: name.map(str::to_string),
+            paused: false,
+            last_checked: None,
+            last_up,
+            last_status_code: None,
+            last_duration: None,
+            checks_total: 0,
+            checks_up: 0,
+            maintenance_since: None,
+            maintenance_checks: 0,
+            maintenance_failures: 0,
+            added_at,
+            labels: Vec::new(),
+            escalation_minutes: None
+        }
+    }
+
+    #[test]
+    fn test_links_view() {
+        let view = LinksView { page: 2, sort: LinksSort::Name, query: String::from("a:b") };
+
+        assert_eq!(view.to_callback_data("links"), "links:2:n:a:b");
+        assert_eq!(LinksView::parse("2:n:a:b"), Some(view));
+        assert_eq!(LinksView::parse("0:a:"), Some(LinksView::default()));
+        assert_eq!(LinksView::parse("x:a:"), None);
+    }
+
+    #[test]
+    fn test_select_links() {
+        let links = vec![
+            create_link("https://b.example.com", None, Some(200), Some(true)),
+            create_link("https://a.example.com", Some("Zeta"), None, None),
+            create_link("https://example.org", Some("Shop"), Some(100), Some(false))
+        ];
+
+        let indexes = |view: LinksView| select_links(&links, &view).into_iter().map(|(index, _)| index).collect::<Vec<usize>>();
+
+        assert_eq!(indexes(LinksView::default()), vec![1, 2, 0]);
+        assert_eq!(indexes(LinksView { sort: LinksSort::Name, ..Default::default() }), vec![0, 2, 1]);
+        assert_eq!(indexes(LinksView { sort: LinksSort::Status, ..Default::default() }), vec![2, 1, 0]);
+        assert_eq!(indexes(LinksView { query: String::from("EXAMPLE.COM"), ..Default::default() }), vec![1, 0]);
+        assert_eq!(indexes(LinksView { query: String::from("shop"), ..Default::default() }), vec![2]);
+    }
+
+    #[test]
+    fn test_link_key() {
+        let links = vec![create_link("https://example.com", None, None, None), create_link("https://example.org", None, None, None)];
+        let key = get_link_key("https://example.org");
+
+        assert_eq!(key.len(), LINK_KEY_LENGTH);
+        assert_ne!(key, get_link_key("https://example.com"));
+        assert_eq!(find_link_by_key(&links, &key).map(|(index, _)| index), Some(1));
+        assert!(find_link_by_key(&links[..1], &key).is_none());
+    }
+
+    #[test]
+    fn test_labels() {
+        let mut prod = create_link("https://example.com", None, None, Some(true));
+        let mut shop = create_link("https://shop.example.com", None, None, Some(false));
+
+        prod.labels = vec![String::from("prod")];
+        shop.labels = vec![String::from("client-x"), String::from("prod")];
+        prod.checks_total = 3;
+        prod.checks_up = 3;
+        shop.checks_total = 1;
+
+        let links = vec![prod, shop];
+        let indexes = |query: &str| select_links(&links, &LinksView { query: query.to_string(), ..Default::default() }).into_iter().map(|(index, _)| index).collect::<Vec<usize>>();
+
+        assert_eq!(indexes("#prod"), vec![0, 1]);
+        assert_eq!(indexes("#PROD shop"), vec![1]);
+        assert_eq!(indexes("#staging"), Vec::<usize>::new());
+
+        assert_eq!(count_labels(&links), vec![(String::from("client-x"), 1), (String::from("prod"), 2)]);
+        assert_eq!(calculate_stats(&links.iter().collect::<Vec<&Links>>()), LinksStats { total: 2, up: 1, down: 1, uptime: Some(75.0), ..Default::default() });
+
+        assert_eq!(parse_labels("Prod, #client-x prod"), Ok(vec![String::from("client-x"), String::from("prod")]));
+        assert_eq!(parse_labels("prod client:x"), Err(String::from("client:x")));
+    }
+}
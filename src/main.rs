@@ -1,9 +1,15 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use chrono::Utc;
 use dptree::{case, deps};
+use hickory_resolver::TokioAsyncResolver;
 use is_url::is_url;
+use lazy_static::lazy_static;
 use log::LevelFilter;
+use reqwest::Url;
 use teloxide::{
-    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+    types::{ForceReply, InlineKeyboardButton, InlineKeyboardMarkup, ParseMode},
     prelude::*,
     Bot,
     utils::command::BotCommands,
@@ -11,14 +17,37 @@ use teloxide::{
     types::{InputFile}
 };
 use teloxide::dispatching::dialogue::GetChatId;
-
-use crate::database::Links;
-use crate::website::SiteInformation;
+use teloxide::net::Download;
+
+use crate::compare::ComparedField;
+use crate::database::{LinkSubscriber, Links, StoredAlert};
+use crate::dns::{DnsInformation, DnsSnapshot, DnsWarning};
+use crate::links_list::{LinksSort, LinksView};
+use crate::maintenance::MaintenanceWindow;
+use crate::mixed_content::{MixedContent, MixedContentKind};
+use crate::monitor::{MonitorKind, ServiceCheck, ServiceError};
+use crate::performance::{PerformanceInformation, ResourceKind};
+use crate::seo::SeoInformation;
+use crate::transfer::{ExportedLink, ImportError, TransferFormat};
+use crate::website::{ExpectedStatusCodes, FamilyCheck, HealthScore, RequestError, RequestResult, SecurityHeaders, SiteInformation};
 
 extern crate pretty_env_logger;
 #[macro_use] extern crate log;
 
+mod client;
+mod compare;
+mod content;
 mod database;
+mod dns;
+mod escalation;
+mod links_list;
+mod maintenance;
+mod mixed_content;
+mod monitor;
+mod performance;
+mod report;
+mod seo;
+mod transfer;
 mod website;
 
 type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
@@ -26,6 +55,99 @@ type SparkleDialogue = Dialogue<BotState, InMemStorage<BotState>>;
 
 const HOUR_IN_SECONDS: u64 = 3600;
 
+/// Maximum number of changed lines of each kind shown in the content change notification
+const MAX_CHANGED_LINES: usize = 5;
+/// Maximum length of a changed line shown in the content change notification
+const MAX_CHANGED_LINE_LENGTH: usize = 100;
+
+/// Recommended length of the page title in characters
+const RECOMMENDED_TITLE_LENGTH: std::ops::RangeInclusive<usize> = 10..=60;
+/// Recommended length of the meta description in characters
+const RECOMMENDED_DESCRIPTION_LENGTH: std::ops::RangeInclusive<usize> = 50..=160;
+
+/// Number of the heaviest resources shown in the page weight report
+const MAX_HEAVIEST_RESOURCES: usize = 5;
+
+/// Number of links on one page of the keyboard for selecting links
+const LINKS_PER_PAGE: usize = 8;
+
+/// Maximum number of characters of a link shown on a button
+const MAX_BUTTON_LINK_LENGTH: usize = 40;
+
+/// Maximum number of characters of a link shown in the list of links
+const MAX_LIST_LINK_LENGTH: usize = 200;
+
+/// Time in seconds during which the user can restore the deleted links
+const UNDO_DELETE_SECONDS: u64 = 60;
+
+/// Maximum number of links added from one message
+const MAX_LINKS_PER_MESSAGE: usize = 30;
+
+/// Maximum number of characters of a link shown in the result of adding links
+const MAX_RESULT_LINK_LENGTH: usize = 100;
+
+/// Maximum number of links in one message with the results of a manual check of the links with a label
+const MAX_CHECKED_LINKS_PER_MESSAGE: usize = 20;
+
+/// Maximum size of the file with imported links in bytes
+const MAX_IMPORT_FILE_SIZE: u32 = 1024 * 1024;
+
+/// Maximum number of problems with the entries of the imported file shown to the user
+const MAX_IMPORT_ERRORS: usize = 10;
+
+/// Maximum length of the name of a link in characters
+const MAX_LINK_NAME_LENGTH: usize = 64;
+
+/// Text of the message with the list of links
+const LINKS_LIST_TEXT: &str = "Вот ваши сохраненные ссылки, нажмите на ссылку, чтобы открыть ее карточку:";
+
+/// Text of the message with the keyboard for selecting links to delete
+const DELETING_LINKS_TEXT: &str = "Выберите, какие ссылки требуется удалить, и нажмите «Удалить выбранные». Вы можете отменить удаление, введя команду /cancel.";
+
+lazy_static! {
+    /// Links deleted in each chat with the time of deletion, kept to undo the deletion
+    static ref DELETED_LINKS: Mutex<HashMap<i64, (Instant, Vec<DeletedLink>)>> = Mutex::new(HashMap::new());
+}
+
+/// Represents a deleted link with the data that is deleted together with it.
+struct DeletedLink {
+    link: Links,
+    subscribers: Vec<LinkSubscriber>,
+    /// The alerts about the link, the oldest first
+    alerts: Vec<StoredAlert>,
+    /// The schedules of the maintenance windows of the link
+    maintenance_windows: Vec<String>
+}
+
+/// Number of insecure resources shown in the site analysis
+const MAX_MIXED_CONTENT: usize = 10;
+
+/// Number of days before the expiration of the service certificate when the user is warned
+const CERTIFICATE_WARNING_DAYS: i64 = 14;
+
+/// Actions of the buttons that do not change the links and are available to all members of a group
+const VIEWING_ACTIONS: [&str; 9] = ["begin", "check_link", "get_links", "links", "links_search", "link_card", "card_check", "card_settings", "card_back"];
+
+/// Time until which a member is muted when the mute has no duration
+const MUTED_FOREVER: u64 = i64::MAX as u64;
+
+/// Maximum duration of a mute in minutes, a year. A mute without a duration lasts until `/unmute`
+const MAX_MUTE_MINUTES: i64 = 365 * 24 * 60;
+
+/// How often the alerts that nobody has acknowledged are checked for escalation
+const ESCALATION_INTERVAL_SECONDS: u64 = 60;
+
+/// Time in seconds since the last notification of an alert after which it is no longer escalated,
+/// for example after the bot was stopped for a long time. It is longer than the longest delay, so
+/// that each next subscriber is reached whatever the delay is.
+const MAX_ESCALATION_AGE_SECONDS: u64 = 2 * escalation::MAX_ESCALATION_MINUTES * 60;
+
+/// Number of the last alerts shown by the /oncall command
+const MAX_SHOWN_ALERTS: i64 = 5;
+
+/// Time in seconds after which the alerts about links that work again are deleted
+const ALERT_HISTORY_SECONDS: u64 = 30 * 24 * HOUR_IN_SECONDS;
+
 const STICKER_WELCOME_ID: &str = "CAACAgIAAxkBAAEne6RlSyQM7sJfMXWBN3u-dfEgIlxzoAACBQADwDZPE_lqX5qCa011MwQ";
 const STICKER_ERROR_ID: &str = "CAACAgIAAxkBAAEne6JlSyP9VdH3N8Mk2imfp7BgFRu9NwACEAADwDZPE-qBiinxHwLoMwQ";
 
@@ -39,19 +161,102 @@ enum SparkleCommand {
     Menu,
     #[command(description = "Отменяет ввод данных в бот")]
     Cancel,
-    #[command(description = "Добавляет ссылки в базу данных для ежечасной проверки сайта на доступность")]
+    #[command(description = "Добавляет ссылку в базу данных для ежечасной проверки сайта на доступность: /addlink <ссылка> [название]. Также поддерживаются сервисы: tcp://<хост>:<порт>, smtp://<хост> и tls://<хост>:<порт>")]
     AddLink {
         link: String
     },
+    #[command(description = "Показывает сохраненные ссылки: /links [часть ссылки или названия для поиска] [#метка]")]
+    Links {
+        query: String
+    },
     #[command(description = "Анализирует сайт")]
     CheckSite {
         link: String
     },
+    #[command(description = "Подсчитывает вес страницы вместе со стилями, скриптами и изображениями")]
+    PageWeight {
+        link: String
+    },
+    #[command(description = "Сравнивает последний анализ сайта с предыдущим: /compare <ссылка> [номер предыдущего анализа]")]
+    Compare {
+        arguments: String
+    },
+    #[command(description = "Сравнивает два сайта: /comparesites <ссылка> <ссылка>")]
+    CompareSites {
+        arguments: String
+    },
+    #[command(description = "Присылает отчет о сайте файлом: /report <ссылка> [json|html]")]
+    Report {
+        arguments: String
+    },
+    #[command(description = "Настраивает проверку ссылки: /linksettings <номер> timeout=<секунды> codes=<коды> slow=<миллисекунды> dualstack=<on|off> insecure=<on|off>")]
+    LinkSettings {
+        arguments: String
+    },
+    #[command(description = "Следит за изменением содержимого страницы: /watchcontent <номер> [off], правила игнорирования css:<селектор> и regex:<выражение> пишутся с новой строки")]
+    WatchContent {
+        arguments: String
+    },
+    #[command(description = "Настраивает окна обслуживания, во время которых ссылка проверяется без оповещений: /maintenance <номер> [<дата или дни> <время> <длительность> [часовой пояс] | delete <номер окна> | off]")]
+    Maintenance {
+        arguments: String
+    },
+    #[command(description = "Задает метки ссылки: /labels <номер> <метки через пробел | off>, без аргументов показывает все метки")]
+    Labels {
+        arguments: String
+    },
+    #[command(description = "Показывает сводку по сохраненным ссылкам: /stats [метка]")]
+    Stats {
+        label: String
+    },
+    #[command(description = "Присылает оповещения только о ссылках с метками: /alerts <метки через пробел | all>")]
+    Alerts {
+        labels: String
+    },
+    #[command(description = "Упоминать меня в оповещениях группы")]
+    Join,
+    #[command(description = "Больше не упоминать меня в оповещениях группы")]
+    Leave,
+    #[command(description = "Отключает мои оповещения: /mute [длительность, например 8h или 30m], без длительности — до команды /unmute")]
+    Mute {
+        duration: String
+    },
+    #[command(description = "Снова включает мои оповещения")]
+    Unmute,
+    #[command(description = "Присылать мне в личные сообщения оповещения о ссылке группы: /subscribe <номер>")]
+    Subscribe {
+        number: String
+    },
+    #[command(description = "Больше не присылать мне оповещения о ссылке: /unsubscribe <номер>")]
+    Unsubscribe {
+        number: String
+    },
+    #[command(description = "Показывает подписчиков ссылки и последние оповещения или настраивает эскалацию: /oncall <номер> [задержка, например 15m | off]")]
+    OnCall {
+        arguments: String
+    },
+
+    #[command(description = "Присылает сохраненные ссылки с настройками файлом: /export [json|csv]")]
+    Export {
+        format: String
+    },
+    #[command(description = "Добавляет ссылки с настройками из файла CSV или JSON, полученного командой /export")]
+    Import,
 
     #[command(description = "Показывает команды бота")]
     Help
 }
 
+impl SparkleCommand {
+    /// Checks if the command changes the links of the chat or their settings, which in a group only its administrators may do.
+    fn changes_links(&self) -> bool {
+        matches!(self, SparkleCommand::AddLink { .. } | SparkleCommand::Import | SparkleCommand::LinkSettings { .. }
+            | SparkleCommand::WatchContent { .. } | SparkleCommand::Maintenance { .. } | SparkleCommand::Labels { .. }
+            | SparkleCommand::Alerts { .. })
+            || matches!(self, SparkleCommand::OnCall { arguments } if arguments.split_whitespace().count() > 1)
+    }
+}
+
 /// Represents the state of a bot.
 #[derive(Clone, Default)]
 enum BotState {
@@ -61,7 +266,25 @@ enum BotState {
     ReceiveLink,
     ReceiveLinkForChecking,
     ReceiveConfirmRemoveLinks,
-    DeletingSomeLinks
+    /// The user selects links to delete on the inline keyboard
+    DeletingSomeLinks {
+        /// The selected links
+        selected: Vec<String>,
+        /// The shown page of the keyboard, starting from 0
+        page: usize
+    },
+    /// The user enters a text to search for in the links
+    SearchingLinks,
+    /// The user uploads a file with links to import
+    ImportingLinks,
+    /// The user enters a new name for the link
+    RenamingLink {
+        link: String
+    },
+    /// The user enters new labels for the link
+    EditingLabels {
+        link: String
+    }
 }
 
 #[tokio::main]
@@ -70,9 +293,14 @@ async fn main() -> HandlerResult {
 
     let bot = Bot::from_env();
 
+    // The clients are created at startup so that invalid settings are reported immediately
+    client::get_client(false);
+    client::get_client(true);
+
     info!("The bot is up and running and ready to go!");
 
     launch_checkers(bot.clone());
+    launch_escalations(bot.clone());
 
     Dispatcher::builder(bot, schema())
         .dependencies(deps![InMemStorage::<BotState>::new()])
@@ -101,759 +329,3702 @@ fn launch_checkers(bot: Bot) {
 
             info!("Runs a site checker");
 
+            database::prune_alerts(get_timestamp().saturating_sub(ALERT_HISTORY_SECONDS));
+
             let all_links = database::get_all_links();
+            let resolver = dns::create_resolver();
 
-            for one_link in all_links {
-                let user_id: UserId = UserId(one_link.user_id as u64);
-                let status_code = website::get_request_code(&one_link.link).await;
+            for mut one_link in all_links {
+                if one_link.paused {
+                    continue;
+                }
 
-                match status_code {
-                    Ok(status_code_unwrapped) => {
-                        handle_status_code(&bot, one_link.user_id as u64, one_link.link, status_code_unwrapped).await;
-                    }
-                    Err(err) => {
-                        error!("Failed to verify the site for the user: {}. Description: {}", one_link.user_id, err.to_string());
-                        let _ = bot.send_message(user_id, format!("Не удалось проверить сайт по ссылке: {}", one_link.link)).await;
-                    }
+                if let Err(err) = handle_maintenance(&bot, &mut one_link).await {
+                    error!("Failed to notify the user about the end of the maintenance: {}. Description: {}", one_link.user_id, err);
+                }
+
+                match MonitorKind::from_link(&one_link.link) {
+                    MonitorKind::Http => check_website(&bot, &one_link).await,
+                    _ => check_service(&bot, &one_link).await
+                }
+
+                if let Err(err) = handle_dns_change(&bot, &one_link, &resolver).await {
+                    error!("Failed to notify the user about the DNS change: {}. Description: {}", one_link.user_id, err);
                 }
             }
         }
     });
 }
 
-/// Processes the site status code and sends a message to the user if there are any problems
+/// Checks the availability and content of the website and notifies the user about problems
 ///
 /// # Arguments
 ///
 /// * `bot`: A bot instance
-/// * `user_id`: User ID in Telegram
-/// * `link`: Site link
-/// * `status_code`: Server status code
-async fn handle_status_code(bot: &Bot, user_id: u64, link: String, status_code: u16) -> HandlerResult {
-    let mut text = format!("Произошла ошибка при проверки ссылки: {link}");
-
-    match status_code {
-        403 => {
-            text = format!("{text}\n\nКод ошибки: {status_code}\nБот не может получить доступ");
+/// * `link`: The checked link with its settings
+async fn check_website(bot: &Bot, link: &Links) {
+    let timeout = link.timeout.unwrap_or(website::DEFAULT_TIMEOUT_SECONDS);
+    let request_result = website::get_request_code(client::get_client(link.accept_invalid_certs), &link.link, Duration::from_secs(timeout), link.watch_content).await;
+
+    match &request_result {
+        Ok(result) => {
+            let is_up = get_expected_codes(link).contains(result.status_code);
+            save_check_result(link, is_up, Some(result.status_code), Some(result.duration));
         }
-        404 => {
-            text = format!("{text}\n\nКод ошибки: {status_code}\nЭтой страницы не существует");
-        }
-        500 => {
-            text = format!("{text}\n\nКод ошибки: {status_code}\nВнутренняя ошибка сервера");
+        Err(_) => {
+            save_check_result(link, false, None, None);
         }
-        503 => {
-            text = format!("{text}\n\nКод ошибки: {status_code}\nСервис недоступен");
+    }
+
+    match request_result {
+        Ok(request_result_unwrapped) => {
+            if let Err(err) = handle_status_code(bot, link, &request_result_unwrapped).await {
+                error!("Failed to notify the user: {}. Description: {}", link.user_id, err);
+            }
+
+            if link.dual_stack {
+                if let Err(err) = handle_address_families(bot, link, timeout).await {
+                    error!("Failed to notify the user about the IPv4/IPv6 check: {}. Description: {}", link.user_id, err);
+                }
+            }
+
+            // Error pages are not compared with the saved content
+            if let Some(body) = &request_result_unwrapped.body {
+                if get_expected_codes(link).contains(request_result_unwrapped.status_code) {
+                    if let Err(err) = handle_content_change(bot, link, body).await {
+                        error!("Failed to notify the user about the content change: {}. Description: {}", link.user_id, err);
+                    }
+                }
+            }
         }
+        Err(err) => {
+            error!("Failed to verify the site for the user: {}. Description: {}", link.user_id, err);
 
-        200..=299 | 300..=399 => { return Ok(()); }
-        _ => {
-            text = format!("{text}\n\nКод ошибки: {status_code}");
+            let text = if err.is_timeout() {
+                format!("Сайт не ответил за {} секунд: {}", timeout, describe_link(link))
+            }
+            else {
+                format!("Не удалось проверить сайт по ссылке: {}", describe_link(link))
+            };
+
+            // The site does not work, so the alert is opened as for an error status, but without the sticker
+            if let Err(err) = send_alert(bot, link, text, true, false).await {
+                error!("Failed to notify the user: {}. Description: {}", link.user_id, err);
+            }
         }
     }
+}
 
-    bot.send_sticker(UserId(user_id), InputFile::file_id(STICKER_ERROR_ID)).await?;
-    bot.send_message(UserId(user_id), text).await?;
+/// Checks the site over IPv4 and IPv6 separately and notifies the user if it works over only one of them
+///
+/// If the site does not work over both versions, the user has already been notified by the main check.
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The checked link with its settings
+/// * `timeout`: Timeout of the check in seconds
+async fn handle_address_families(bot: &Bot, link: &Links, timeout: u64) -> HandlerResult {
+    let checks = website::check_address_families(&link.link, Duration::from_secs(timeout), link.accept_invalid_certs).await;
+    let expected_codes = get_expected_codes(link);
+
+    let is_working = |check: &&FamilyCheck| {
+        check.result.as_ref().is_ok_and(|result| expected_codes.contains(result.status_code))
+    };
+
+    let (working, failing): (Vec<&FamilyCheck>, Vec<&FamilyCheck>) = checks.iter().partition(is_working);
+
+    if let ([working], [failing]) = (working.as_slice(), failing.as_slice()) {
+        let text = format!("⚠️ Сайт {} не работает по {}, но доступен по {}\n\n{}\n{}",
+                           describe_link(link),
+                           failing.family,
+                           working.family,
+                           describe_family_check(failing),
+                           describe_family_check(working));
+
+        send_alert(bot, link, text, false, false).await?;
+    }
 
     Ok(())
 }
 
-/// This function returns a teloxide Update handler which performs various
-/// actions based on the command input and the specific state of a Telegram Bot.
+/// Describes the result of a request to the site over one version of the IP protocol
 ///
-/// Inside the function, three kinds of handlers are defined:
+/// # Arguments
 ///
-/// - `command_handler`: This handler manages various commands sent by the user.
-/// - `message_handler`: This handler manages regular messages from the user.
-/// - `callback_query_handlers`: This handler manages callback queries generated when users interact with the bot's InlineKeyboardButtons.
+/// * `check`: The result of the request
+fn describe_family_check(check: &FamilyCheck) -> String {
+    match &check.result {
+        Ok(result) => format!("{} ({}): код ответа {}, {} милисекунд", check.family, check.address, result.status_code, result.duration),
+        Err(RequestError::Timeout) => format!("{} ({}): сайт слишком долго отвечал", check.family, check.address),
+        Err(RequestError::Connection) => format!("{} ({}): не удалось подключиться", check.family, check.address)
+    }
+}
+
+/// Checks the TCP, SMTP or TLS service and notifies the user about problems
 ///
-/// For each handler, several states to which the bot can be transitioned are defined
-/// along with an endpoint function that is called when the bot transition to that state.
+/// # Arguments
 ///
-/// # Return
-/// Handler for processing updates and routing them according to the state and command.
+/// * `bot`: A bot instance
+/// * `link`: The checked link with its settings
+async fn check_service(bot: &Bot, link: &Links) {
+    let timeout = link.timeout.unwrap_or(website::DEFAULT_TIMEOUT_SECONDS);
+    let service_check = monitor::check_service(&link.link, Duration::from_secs(timeout)).await;
+
+    let duration = service_check.as_ref().ok().map(|service_check| service_check.duration);
+    save_check_result(link, duration.is_some(), None, duration);
+
+    if let Err(err) = handle_service_check(bot, link, service_check, timeout).await {
+        error!("Failed to notify the user: {}. Description: {}", link.user_id, err);
+    }
+}
+
+/// Processes the result of the service check and sends a message to the user if there are any problems
 ///
-/// Returns an `UpdateHandler` that is used for processing `teloxide::Update`, which
-/// represents updates (`Update`) that Bot API gives your bot.
+/// # Arguments
 ///
-/// # Examples
-/// It could be added to dispatcher like this:
+/// * `bot`: A bot instance
+/// * `link`: The checked link with its settings
+/// * `service_check`: Result of the check
+/// * `timeout`: Timeout of the check in seconds
+async fn handle_service_check(bot: &Bot, link: &Links, service_check: Result<ServiceCheck, ServiceError>, timeout: u64) -> HandlerResult {
+    let service_check = match service_check {
+        Ok(service_check) => service_check,
+        Err(err) => {
+            let description = describe_service_error(&err, timeout);
+
+            send_alert(bot, link, format!("Произошла ошибка при проверке сервиса: {}\n\n{description}", describe_link(link)), true, true).await?;
+
+            return Ok(());
+        }
+    };
+
+    if let Some(days) = service_check.certificate_days {
+        if days < CERTIFICATE_WARNING_DAYS {
+            send_alert(bot, link, format!("🔒 Сертификат сервиса {} истекает через {days} дней", describe_link(link)), false, false).await?;
+        }
+    }
+
+    notify_if_slow(bot, link, service_check.duration).await
+}
+
+/// Describes the error of the service check for the user
 ///
-/// ```
-/// Dispatcher::new(bot)
-///     .messages_handler(schema())
-///     .dispatch()
-///     .await;
-/// ```
+/// # Arguments
 ///
-/// where `Dispatcher` and `bot` are previously defined according to your program needs.
-fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let command_handler = teloxide::filter_command::<SparkleCommand, _>()
-        .branch(case![BotState::Default]
-            .branch(case![SparkleCommand::Start].endpoint(start))
-            .branch(case![SparkleCommand::Menu].endpoint(show_actions))
-            .branch(case![SparkleCommand::Help].endpoint(help))
-            .branch(case![SparkleCommand::AddLink { link }].endpoint(add_link))
-            .branch(case![SparkleCommand::CheckSite { link }].endpoint(check_site_command)))
-        .branch(case![BotState::ReceiveLink]
-            .branch(case![SparkleCommand::Cancel].endpoint(cancel_receive_link)))
-        .branch(case![BotState::DeletingSomeLinks]
-            .branch(case![SparkleCommand::Cancel].endpoint(cancel_deleting_some_links)))
-        .branch(case![BotState::ReceiveLinkForChecking]
-            .branch(case![SparkleCommand::Cancel].endpoint(cancel_receive_link)));
-
-    let message_handler = Update::filter_message()
-        .branch(command_handler)
-        .branch(case![BotState::ReceiveLink].endpoint(receive_link))
-        .branch(case![BotState::DeletingSomeLinks].endpoint(delete_some_links))
-        .branch(case![BotState::ReceiveLinkForChecking].endpoint(check_site));
+/// * `err`: The error of the check
+/// * `timeout`: Timeout of the check in seconds
+fn describe_service_error(err: &ServiceError, timeout: u64) -> String {
+    match err {
+        ServiceError::InvalidLink => String::from("Некорректная ссылка на сервис"),
+        ServiceError::Connection(description) => format!("Не удалось подключиться: {description}"),
+        ServiceError::Timeout => format!("Сервис не ответил за {timeout} секунд"),
+        ServiceError::UnexpectedBanner(banner) => format!("Неожиданное приветствие SMTP-сервера: {banner}"),
+        ServiceError::Tls(description) => format!("Ошибка TLS: {description}")
+    }
+}
 
-    let callback_query_handler = Update::filter_callback_query()
-        .branch(case![BotState::Default].endpoint(menu_choice_callback_handler))
-        .branch(case![BotState::ReceiveConfirmRemoveLinks].endpoint(menu_confirm_remove_links_callback_handler));
+/// Checks the link once at the request of the user and describes the result
+///
+/// Unlike the hourly check, the result is not saved and no alerts are sent, so a manual check does not
+/// change the uptime of the link and does not notify the chat or the subscribers of the link.
+///
+/// # Arguments
+///
+/// * `link`: The checked link with its settings
+async fn check_link_now(link: &Links) -> String {
+    let timeout = link.timeout.unwrap_or(website::DEFAULT_TIMEOUT_SECONDS);
+
+    match MonitorKind::from_link(&link.link) {
+        MonitorKind::Http => match website::get_request_code(client::get_client(link.accept_invalid_certs), &link.link, Duration::from_secs(timeout), false).await {
+            Ok(result) if get_expected_codes(link).contains(result.status_code) => format!("✅ работает (код ответа {}, {} милисекунд)", result.status_code, result.duration),
+            Ok(result) => format!("❌ не работает (код ответа {})", result.status_code),
+            Err(err) if err.is_timeout() => format!("❌ не ответил за {timeout} секунд"),
+            Err(_) => String::from("❌ не удалось проверить")
+        },
+        _ => match monitor::check_service(&link.link, Duration::from_secs(timeout)).await {
+            Ok(service_check) => format!("✅ работает ({} милисекунд)", service_check.duration),
+            Err(err) => format!("❌ {}", describe_service_error(&err, timeout))
+        }
+    }
+}
 
-    dialogue::enter::<Update, InMemStorage<BotState>, BotState, _>()
-        .branch(message_handler)
-        .branch(callback_query_handler)
+/// Returns the current time as a Unix timestamp
+fn get_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
 }
 
-/// Displays a welcome message to the user
+/// Sends a message to the user if the response time exceeds the link's slow threshold
 ///
 /// # Arguments
 ///
-/// * `bot`: Bot instance
-/// * `msg`: Message sent by the user
+/// * `bot`: A bot instance
+/// * `link`: The checked link with its settings
+/// * `duration`: Response time in milliseconds
+async fn notify_if_slow(bot: &Bot, link: &Links, duration: u128) -> HandlerResult {
+    if let Some(slow_threshold) = link.slow_threshold {
+        if duration > slow_threshold as u128 {
+            let text = format!("🐢 Сайт отвечает слишком медленно: {}\n\nВремя ответа: {} миллисекунд\nДопустимое время ответа: {} миллисекунд", describe_link(link), duration, slow_threshold);
+            send_alert(bot, link, text, false, false).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Processes the site status code and sends a message to the user if there are any problems.
 ///
-/// returns: Result<(), Box<dyn Error+Send+Sync, Global>>
-async fn start(bot: Bot, msg: Message) -> HandlerResult {
-    info!("A new user has joined the bot: {}", msg.from().expect("Unable to determine user ID").id);
+/// The status code is compared with the codes expected for the link, and the response time
+/// with the link's slow threshold
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The checked link with its settings
+/// * `request_result`: Server status code and response time
+async fn handle_status_code(bot: &Bot, link: &Links, request_result: &RequestResult) -> HandlerResult {
+    let status_code = request_result.status_code;
 
-    let mut text = format!("🚀 Привет, {}! Я - {}, и я могу проанализировать Ваш сайт, то есть проверить скорость его загрузки и ежечасно приводить отчёт о сбоях в работе указанного Вами сайта.", msg.from().expect("Unable to define a user name").first_name, bot.get_me().await?.first_name);
-    text = format!("{text}\n\nОсновной функционал:\n🔭 Анализ сайта (проверка наличия SSL-сертификата, время ответа, наличие robots.txt и sitemap.xml)\n📟 Ежечасная проверка сайта на стабильность, добавив ссылки в базу данных");
+    if get_expected_codes(link).contains(status_code) {
+        return notify_if_slow(bot, link, request_result.duration).await;
+    }
 
-    let keyboard = create_beginning_menu_keyboard().await;
+    let mut text = format!("Произошла ошибка при проверки ссылки: {}", describe_link(link));
 
-    bot.send_sticker(msg.chat.id, InputFile::file_id(STICKER_WELCOME_ID)).await?;
-    bot.send_message(msg.chat.id, text).reply_markup(keyboard).await?;
+    match status_code {
+        403 => {
+            text = format!("{text}\n\nКод ошибки: {status_code}\nБот не может получить доступ");
+        }
+        404 => {
+            text = format!("{text}\n\nКод ошибки: {status_code}\nЭтой страницы не существует");
+        }
+        500 => {
+            text = format!("{text}\n\nКод ошибки: {status_code}\nВнутренняя ошибка сервера");
+        }
+        503 => {
+            text = format!("{text}\n\nКод ошибки: {status_code}\nСервис недоступен");
+        }
+        _ => {
+            text = format!("{text}\n\nКод ошибки: {status_code}");
+        }
+    }
 
-    Ok(())
+    send_alert(bot, link, text, true, true).await
 }
 
-/// Sends a message to the user that displays all bot commands
+/// Returns the status codes that are considered healthy for the link
 ///
 /// # Arguments
 ///
-/// * `bot`: Bot instance
-/// * `msg`: Message sent by the user
-async fn help(bot: Bot, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, SparkleCommand::descriptions().to_string()).await?;
-    Ok(())
+/// * `link`: The link with its settings
+fn get_expected_codes(link: &Links) -> ExpectedStatusCodes {
+    link.expected_codes.as_deref()
+        .and_then(ExpectedStatusCodes::parse)
+        .unwrap_or_default()
 }
 
-/// Adds a link to the database if it is a valid URL.
+/// Compares the page content with the content saved at the previous check and notifies the
+/// user if it has changed
 ///
-/// # Arguments:
-/// - `bot`: The Telegram bot instance.
-/// - `msg`: The received message.
-/// - `link`: The link to be added.
+/// The first check only saves the content.
 ///
-/// Returns:
-/// The result of the operation.
-async fn add_link(bot: Bot, msg: Message, link: String) -> HandlerResult {
-    let user_id = msg.from().expect("Unable to determine user ID").id;
-    let mut url = link;
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The checked link with its settings
+/// * `body`: HTML code of the page
+async fn handle_content_change(bot: &Bot, link: &Links, body: &str) -> HandlerResult {
+    let user_id = link.user_id as i64;
+    let rules = content::parse_ignore_rules(link.content_ignore.as_deref().unwrap_or_default()).unwrap_or_default();
 
-    if !website::has_http_or_https(&url) {
-        url = format!("https://{}", url);
+    let content_text = content::normalize_content(body, &rules);
+    let content_hash = content::hash_content(&content_text);
+
+    if link.content_hash.as_deref() == Some(content_hash.as_str()) {
+        return Ok(());
     }
 
-    if is_url(&url) {
-        if database::is_link_exists(user_id.0, &url) {
-            bot.send_message(msg.chat.id, "Данная ссылка уже была добавлена. Пожалуйста, введите другую").await?;
+    database::update_content_snapshot(user_id, &link.link, &content_hash, &content_text);
 
-            return Ok(());
+    let Some(old_content_text) = &link.content_text else { return Ok(()) };
+
+    info!("The content of the page has changed for the user: {}", user_id);
+
+    let changes = content::compare_content(old_content_text, &content_text);
+
+    let mut text = format!("📝 Содержимое страницы изменилось: {}\n\nДобавлено строк: {}\nУдалено строк: {}", describe_link(link), changes.added.len(), changes.removed.len());
+
+    for (title, lines) in [("Добавлено", &changes.added), ("Удалено", &changes.removed)] {
+        if lines.is_empty() {
+            continue;
         }
 
-        database::add_link(user_id.0, &url);
+        text = format!("{text}\n\n{title}:");
 
-        info!("Added a new link to the database from the user: {}", user_id);
+        for line in lines.iter().take(MAX_CHANGED_LINES) {
+            let line: String = line.chars().take(MAX_CHANGED_LINE_LENGTH).collect();
+            text = format!("{text}\n• {line}");
+        }
 
-        bot.send_message(msg.chat.id, "Спасибо за ссылку! Теперь я буду проверять эту ссылку каждый час").await?;
-    }
-    else {
-        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+        if lines.len() > MAX_CHANGED_LINES {
+            text = format!("{text}\n...и еще {}", lines.len() - MAX_CHANGED_LINES);
+        }
     }
 
-    Ok(())
+    send_alert(bot, link, text, false, false).await
 }
 
-/// Asynchronously checks the given site link and sends site information to the user.
-///
-/// # Arguments
+/// Compares the A, AAAA and NS records of the link's host with the records saved at the previous
+/// check and notifies the user if they have changed or the host has stopped resolving
 ///
-/// * `bot` - The bot instance used to send messages.
-/// * `msg` - The message object representing the user message.
-/// * `link` - The site link to check.
+/// The first check only saves the records. If the DNS server could not be queried, the check is skipped.
 ///
-/// # Returns
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The checked link
+/// * `resolver`: The DNS resolver
+async fn handle_dns_change(bot: &Bot, link: &Links, resolver: &TokioAsyncResolver) -> HandlerResult {
+    // Links with an IP address instead of a domain name have nothing to resolve
+    let Some(host) = Url::parse(&link.link).ok().and_then(|url| url.domain().map(str::to_string)) else { return Ok(()) };
+
+    let snapshot = match dns::get_dns_snapshot(resolver, &host).await {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            warn!("Failed to resolve the host: {}. Description: {}", host, err);
+            return Ok(());
+        }
+    };
+
+    let user_id = link.user_id as i64;
+
+    let Some(old_dns_records) = &link.dns_records else {
+        database::update_dns_records(user_id, &link.link, &snapshot.to_text(), None);
+        return Ok(());
+    };
+
+    let saved = DnsSnapshot::parse(old_dns_records);
+    let pending = link.pending_dns_records.as_deref().map(DnsSnapshot::parse);
+
+    // A change is reported only when it persists at the next check, since round-robin DNS and CDNs rotate the addresses
+    let (confirmed, is_pending) = dns::confirm_changes(&saved, pending.as_ref(), &snapshot);
+    let changes = dns::compare_snapshots(&saved, &confirmed);
+    let pending_dns_records = is_pending.then(|| snapshot.to_text());
+
+    if !changes.is_empty() || pending_dns_records != link.pending_dns_records {
+        database::update_dns_records(user_id, &link.link, &confirmed.to_text(), pending_dns_records.as_deref());
+    }
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    info!("The DNS records of the host have changed for the user: {}", user_id);
+
+    let mut text = if confirmed.is_unresolved() {
+        format!("🌐 Домен сайта перестал разрешаться: {}", describe_link(link))
+    }
+    else {
+        format!("🌐 DNS-записи сайта изменились: {}", describe_link(link))
+    };
+
+    for change in changes {
+        let old = if change.old.is_empty() { String::from("нет") } else { change.old.join(", ") };
+        let new = if change.new.is_empty() { String::from("нет") } else { change.new.join(", ") };
+
+        text = format!("{text}\n\n{}:\nБыло: {old}\nСтало: {new}", change.record_type);
+    }
+
+    send_alert(bot, link, text, false, false).await
+}
+
+/// Sends an alert about the link to the chat that owns it, unless the link is in a maintenance window
+/// or the chat receives alerts only about links with other labels
+///
+/// In a group the alert mentions the members who receive the alerts of the group, except for the
+/// muted ones. In a private chat the alert is not sent while the user has muted the alerts. The
+/// subscribers of the link also get the alert in private messages, and an alert that the link does
+/// not work can be acknowledged by pressing its button. Such an alert stays open until the link works
+/// again, and the next failures of the link do not notify the subscribers again.
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The checked link
+/// * `text`: Text of the alert
+/// * `is_failure`: Whether the alert reports that the link does not work, such alerts open an alert that can be acknowledged
+/// * `with_sticker`: Whether the alert is preceded by the error sticker
+async fn send_alert(bot: &Bot, link: &Links, text: String, is_failure: bool, with_sticker: bool) -> HandlerResult {
+    if link.maintenance_since.is_some() {
+        info!("An alert is suppressed during the maintenance for the user: {}", link.user_id);
+        return Ok(());
+    }
+
+    let chat_id = ChatId(link.user_id as i64);
+    let alert_labels = database::get_alert_labels(chat_id.0);
+
+    if !alert_labels.is_empty() && !link.labels.iter().any(|label| alert_labels.contains(label)) {
+        info!("An alert is suppressed by the label filter for the chat: {}", chat_id);
+        return Ok(());
+    }
+
+    let now = get_timestamp();
+    let members = database::get_chat_members(chat_id.0);
+    let plain_text = text.clone();
+
+    let text = if chat_id.is_user() {
+        if members.iter().any(|member| member.is_muted(now)) {
+            info!("An alert is muted by the user: {}", chat_id);
+            return Ok(());
+        }
+
+        report::escape_html(&text)
+    }
+    else {
+        let mentions: Vec<String> = members.iter()
+            .filter(|member| !member.is_muted(now))
+            .map(|member| format!("<a href=\"tg://user?id={}\">{}</a>", member.user_id, report::escape_html(&member.name)))
+            .collect();
+
+        if mentions.is_empty() {
+            report::escape_html(&text)
+        }
+        else {
+            format!("{}\n\n{}", report::escape_html(&text), mentions.join(", "))
+        }
+    };
+
+    if !is_failure {
+        bot.send_message(chat_id, text).parse_mode(ParseMode::Html).await?;
+        notify_subscribers(bot, link, &plain_text, None).await;
+
+        return Ok(());
+    }
+
+    if with_sticker {
+        bot.send_sticker(chat_id, InputFile::file_id(STICKER_ERROR_ID)).await?;
+    }
+
+    // Repeated failures of a link that does not work yet belong to the alert that is already open, so
+    // its escalation is not restarted and an acknowledged outage does not page the subscribers again
+    let (alert, is_new) = database::open_alert(chat_id.0, &link.link, &plain_text, now);
+
+    if alert.acknowledged_at.is_some() {
+        bot.send_message(chat_id, text).parse_mode(ParseMode::Html).await?;
+    }
+    else {
+        bot.send_message(chat_id, text).parse_mode(ParseMode::Html).reply_markup(create_acknowledge_keyboard(alert.id)).await?;
+    }
+
+    if is_new {
+        notify_subscribers(bot, link, &plain_text, Some((alert.id, 0))).await;
+    }
+
+    Ok(())
+}
+
+/// Saves the result of a check of the link and resolves its open alert if the link works
+///
+/// # Arguments
+///
+/// * `link`: The checked link
+/// * `is_up`: Whether the link works
+/// * `status_code`: The received status code, `None` for services and failed requests
+/// * `duration`: Response time in milliseconds, `None` for failed requests
+fn save_check_result(link: &Links, is_up: bool, status_code: Option<u16>, duration: Option<u128>) {
+    let user_id = link.user_id as i64;
+    let now = get_timestamp();
+
+    database::record_check(user_id, &link.link, now, is_up, status_code, duration);
+
+    if is_up {
+        database::resolve_alerts(user_id, &link.link, now);
+    }
+}
+
+/// Sends the alert to the subscribers of the link in private messages, skipping the muted ones
+///
+/// An alert that can be acknowledged is sent according to the escalation policy of the link: to the
+/// next subscriber only, or to all the remaining subscribers if the link has no policy.
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The link
+/// * `text`: Text of the alert
+/// * `alert`: The row ID of the alert and the index of the first subscriber who has not been notified yet,
+///   `None` for an alert that cannot be acknowledged, which is sent to all subscribers
+async fn notify_subscribers(bot: &Bot, link: &Links, text: &str, alert: Option<(i64, u64)>) {
+    let chat_id = link.user_id as i64;
+    let now = get_timestamp();
+
+    let members = database::get_chat_members(chat_id);
+    let subscribers = database::get_link_subscribers(chat_id, &link.link);
+
+    let muted: Vec<bool> = subscribers.iter()
+        .map(|subscriber| members.iter().any(|member| member.user_id == subscriber.user_id && member.is_muted(now)))
+        .collect();
+
+    let (level, escalation_minutes) = match alert {
+        Some((_, level)) => (level as usize, link.escalation_minutes),
+        None => (0, None)
+    };
+
+    let (selected, new_level) = escalation::select_subscribers(&muted, level, escalation_minutes);
+    let text = format!("📟 Оповещение о ссылке, на которую вы подписаны:\n\n{text}");
+
+    for index in selected {
+        let mut request = bot.send_message(ChatId(subscribers[index].user_id as i64), text.as_str());
+
+        if let Some((alert_id, _)) = alert {
+            request = request.reply_markup(create_acknowledge_keyboard(alert_id));
+        }
+
+        // A user who has never written to the bot cannot receive private messages, the next subscriber is still notified
+        if let Err(err) = request.await {
+            error!("Failed to notify the subscriber of the link in the chat: {}. Description: {}", chat_id, err);
+        }
+    }
+
+    if let Some((alert_id, _)) = alert.filter(|_| new_level != level) {
+        database::update_alert_level(alert_id, new_level as u64, now);
+    }
+}
+
+/// Creates a keyboard with a button that acknowledges the alert
+///
+/// # Arguments
+///
+/// * `alert_id`: The row ID of the alert
+fn create_acknowledge_keyboard(alert_id: i64) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback("✅ Подтвердить", format!("ack:{alert_id}"))]])
+}
+
+/// Creates a separate standalone thread that sends the alerts that nobody has acknowledged in time
+/// to the next subscribers of their links
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+fn launch_escalations(bot: Bot) {
+    let mut interval = tokio::time::interval(Duration::from_secs(ESCALATION_INTERVAL_SECONDS));
+
+    info!("A thread has been launched to escalate alerts.");
+
+    tokio::spawn(async move {
+        loop {
+            interval.tick().await;
+
+            let now = get_timestamp();
+
+            for alert in database::get_unacknowledged_alerts(now.saturating_sub(MAX_ESCALATION_AGE_SECONDS)) {
+                let Some(link) = database::get_all_links_from_user(alert.chat_id, Some(&alert.link)).pop() else {
+                    continue;
+                };
+
+                // The alerts are resolved when their links work again, so only the alerts of links that still do not work are returned
+                let is_due = link.escalation_minutes.is_some_and(|minutes| escalation::is_escalation_due(alert.escalated_at, minutes, now));
+
+                if is_due {
+                    info!("Escalates the alert in the chat: {}", alert.chat_id);
+
+                    notify_subscribers(&bot, &link, &alert.text, Some((alert.id, alert.level))).await;
+                }
+            }
+        }
+    });
+}
+
+/// Starts or finishes the maintenance of the link according to its maintenance windows
+///
+/// One-off windows that have closed are deleted. When the maintenance is finished, the user gets
+/// a summary of the checks made during it.
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `link`: The link, its maintenance state is updated
+async fn handle_maintenance(bot: &Bot, link: &mut Links) -> HandlerResult {
+    let user_id = link.user_id as i64;
+    let now = Utc::now();
+    let mut is_active = false;
+
+    for stored in database::get_maintenance_windows(user_id, &link.link) {
+        match MaintenanceWindow::parse(&stored.schedule) {
+            Some(window) if window.has_ended(now) => {
+                database::delete_maintenance_windows(user_id, &link.link, Some(stored.id));
+            }
+            Some(window) => is_active |= window.is_active(now),
+            None => warn!("Failed to parse the maintenance window: {}", stored.schedule)
+        }
+    }
+
+    match (is_active, link.maintenance_since) {
+        (true, None) => {
+            let maintenance_since = now.timestamp() as u64;
+            database::update_maintenance(user_id, &link.link, Some(maintenance_since));
+
+            link.maintenance_since = Some(maintenance_since);
+            link.maintenance_checks = 0;
+            link.maintenance_failures = 0;
+
+            info!("The maintenance of the link has started for the user: {}", user_id);
+        }
+        (false, Some(maintenance_since)) => {
+            database::update_maintenance(user_id, &link.link, None);
+
+            link.maintenance_since = None;
+
+            info!("The maintenance of the link has finished for the user: {}", user_id);
+
+            let text = format!("🛠 Обслуживание завершено: {}\n\nНачало: {}\nПроверок во время обслуживания: {}\nИз них неудачных: {}\n\nОповещения снова включены",
+                               describe_link(link),
+                               compare::format_date(maintenance_since as i64),
+                               link.maintenance_checks,
+                               link.maintenance_failures);
+
+            bot.send_message(ChatId(user_id), text).await?;
+        }
+        _ => ()
+    }
+
+    Ok(())
+}
+
+/// Sends a message that asks the user to enter a text
+///
+/// In a group the message asks for a reply to it, since with the default privacy mode of Telegram the bot
+/// receives there only commands and replies to its own messages.
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `chat_id`: The chat in which the text is entered
+/// * `text`: Text of the message
+async fn send_prompt(bot: &Bot, chat_id: ChatId, text: String) -> HandlerResult {
+    if chat_id.is_user() {
+        bot.send_message(chat_id, text).await?;
+    }
+    else {
+        bot.send_message(chat_id, text).reply_markup(ForceReply::new()).await?;
+    }
+
+    Ok(())
+}
+
+/// Checks if the user may change the links of the chat: anyone in a private chat, only the administrators in a group
+///
+/// # Arguments
+///
+/// * `bot`: A bot instance
+/// * `chat_id`: The chat that owns the links
+/// * `user_id`: The user who changes the links
+async fn can_change_links(bot: &Bot, chat_id: ChatId, user_id: UserId) -> bool {
+    if chat_id.is_user() {
+        return true;
+    }
+
+    match bot.get_chat_member(chat_id, user_id).await {
+        Ok(member) => member.is_privileged(),
+        Err(err) => {
+            error!("Failed to get the member of the chat: {}. Description: {}", chat_id, err);
+            false
+        }
+    }
+}
+
+/// Checks if the command changes the links of the chat and its sender may not do it
+async fn is_forbidden_command(bot: Bot, msg: Message, command: SparkleCommand) -> bool {
+    if !command.changes_links() {
+        return false;
+    }
+
+    match msg.from() {
+        Some(user) => !can_change_links(&bot, msg.chat.id, user.id).await,
+        None => true
+    }
+}
+
+/// Checks if the message is entered while the links of the chat are being changed and its sender may not change them
+async fn is_forbidden_message(bot: Bot, msg: Message, state: BotState) -> bool {
+    if matches!(state, BotState::Default | BotState::ReceiveLinkForChecking | BotState::SearchingLinks) {
+        return false;
+    }
+
+    match msg.from() {
+        Some(user) => !can_change_links(&bot, msg.chat.id, user.id).await,
+        None => true
+    }
+}
+
+/// Checks if the button changes the links of the chat and the user who pressed it may not do it
+async fn is_forbidden_callback(bot: Bot, q: CallbackQuery, state: BotState) -> bool {
+    let action = q.data.as_deref().unwrap_or_default().split(':').next().unwrap_or_default();
+
+    if matches!(state, BotState::Default) && VIEWING_ACTIONS.contains(&action) {
+        return false;
+    }
+
+    match q.chat_id() {
+        Some(chat_id) => !can_change_links(&bot, chat_id, q.from.id).await,
+        None => true
+    }
+}
+
+/// Tells the user that only the administrators of the group may change its links
+async fn forbid_changing_links(bot: Bot, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Изменять ссылки группы могут только ее администраторы").await?;
+
+    Ok(())
+}
+
+/// Tells the user who pressed the button that only the administrators of the group may change its links
+async fn forbid_changing_links_callback(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    bot.answer_callback_query(q.id).text("Изменять ссылки группы могут только ее администраторы").await?;
+
+    Ok(())
+}
+
+/// Ignores a message of a member of the group who may not change its links
+async fn ignore_message() -> HandlerResult {
+    Ok(())
+}
+
+/// Checks if the button acknowledges an alert, which any user who got the alert may do
+fn is_acknowledge_callback(q: CallbackQuery) -> bool {
+    q.data.as_deref().is_some_and(|data| data.starts_with("ack:"))
+}
+
+/// Records who acknowledged the alert and when, so that it is no longer escalated, and tells the
+/// chat that owns the link about it
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `q`: Response from the user after pressing the button
+async fn acknowledge_alert(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    let alert = q.data.as_deref()
+        .and_then(|data| data.strip_prefix("ack:"))
+        .and_then(|id| id.parse::<i64>().ok())
+        .and_then(database::get_alert);
+
+    let Some(alert) = alert else {
+        bot.answer_callback_query(q.id).text("Оповещение не найдено").await?;
+        return Ok(());
+    };
+
+    if let (Some((_, name)), Some(acknowledged_at)) = (&alert.acknowledged_by, alert.acknowledged_at) {
+        bot.answer_callback_query(q.id).text(format!("Оповещение уже подтвердил(а) {name} {}", compare::format_date(acknowledged_at as i64))).await?;
+        return Ok(());
+    }
+
+    let name = q.from.full_name();
+    let now = get_timestamp();
+
+    database::acknowledge_alert(alert.id, q.from.id.0, &name, now);
+
+    info!("The alert has been acknowledged in the chat: {}", alert.chat_id);
+
+    bot.answer_callback_query(q.id).text("Оповещение подтверждено").await?;
+
+    if let Some(message) = &q.message {
+        bot.edit_message_reply_markup(message.chat.id, message.id).await?;
+    }
+
+    bot.send_message(ChatId(alert.chat_id), format!("✅ {name} подтвердил(а) оповещение от {}:\n\n{}", compare::format_date(alert.sent_at as i64), alert.text)).await?;
+
+    Ok(())
+}
+
+/// This function returns a teloxide Update handler which performs various
+/// actions based on the command input and the specific state of a Telegram Bot.
+///
+/// Inside the function, three kinds of handlers are defined:
+///
+/// - `command_handler`: This handler manages various commands sent by the user.
+/// - `message_handler`: This handler manages regular messages from the user.
+/// - `callback_query_handlers`: This handler manages callback queries generated when users interact with the bot's InlineKeyboardButtons.
+///
+/// For each handler, several states to which the bot can be transitioned are defined
+/// along with an endpoint function that is called when the bot transition to that state.
+///
+/// # Return
+/// Handler for processing updates and routing them according to the state and command.
+///
+/// Returns an `UpdateHandler` that is used for processing `teloxide::Update`, which
+/// represents updates (`Update`) that Bot API gives your bot.
+///
+/// # Examples
+/// It could be added to dispatcher like this:
+///
+/// ```
+/// Dispatcher::new(bot)
+///     .messages_handler(schema())
+///     .dispatch()
+///     .await;
+/// ```
+///
+/// where `Dispatcher` and `bot` are previously defined according to your program needs.
+fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let command_handler = teloxide::filter_command::<SparkleCommand, _>()
+        .branch(dptree::filter_async(is_forbidden_command).endpoint(forbid_changing_links))
+        .branch(case![BotState::Default]
+            .branch(case![SparkleCommand::Start].endpoint(start))
+            .branch(case![SparkleCommand::Menu].endpoint(show_actions))
+            .branch(case![SparkleCommand::Help].endpoint(help))
+            .branch(case![SparkleCommand::AddLink { link }].endpoint(add_link))
+            .branch(case![SparkleCommand::Links { query }].endpoint(links_command))
+            .branch(case![SparkleCommand::Export { format }].endpoint(export_command))
+            .branch(case![SparkleCommand::Import].endpoint(import_command))
+            .branch(case![SparkleCommand::CheckSite { link }].endpoint(check_site_command))
+            .branch(case![SparkleCommand::PageWeight { link }].endpoint(page_weight_command))
+            .branch(case![SparkleCommand::Report { arguments }].endpoint(report_command))
+            .branch(case![SparkleCommand::Compare { arguments }].endpoint(compare_command))
+            .branch(case![SparkleCommand::CompareSites { arguments }].endpoint(compare_sites_command))
+            .branch(case![SparkleCommand::LinkSettings { arguments }].endpoint(link_settings))
+            .branch(case![SparkleCommand::WatchContent { arguments }].endpoint(watch_content))
+            .branch(case![SparkleCommand::Maintenance { arguments }].endpoint(maintenance_command))
+            .branch(case![SparkleCommand::Labels { arguments }].endpoint(labels_command))
+            .branch(case![SparkleCommand::Stats { label }].endpoint(stats_command))
+            .branch(case![SparkleCommand::Alerts { labels }].endpoint(alerts_command))
+            .branch(case![SparkleCommand::Join].endpoint(join_command))
+            .branch(case![SparkleCommand::Leave].endpoint(leave_command))
+            .branch(case![SparkleCommand::Mute { duration }].endpoint(mute_command))
+            .branch(case![SparkleCommand::Unmute].endpoint(unmute_command))
+            .branch(case![SparkleCommand::Subscribe { number }].endpoint(subscribe_command))
+            .branch(case![SparkleCommand::Unsubscribe { number }].endpoint(unsubscribe_command))
+            .branch(case![SparkleCommand::OnCall { arguments }].endpoint(on_call_command)))
+        .branch(case![BotState::ReceiveLink]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_receive_link)))
+        .branch(case![BotState::DeletingSomeLinks { selected, page }]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_deleting_some_links)))
+        .branch(case![BotState::ReceiveLinkForChecking]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_receive_link)))
+        .branch(case![BotState::RenamingLink { link }]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_renaming_link)))
+        .branch(case![BotState::EditingLabels { link }]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_editing_labels)))
+        .branch(case![BotState::SearchingLinks]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_searching_links)))
+        .branch(case![BotState::ImportingLinks]
+            .branch(case![SparkleCommand::Cancel].endpoint(cancel_importing_links)));
+
+    let message_handler = Update::filter_message()
+        .branch(command_handler)
+        .branch(dptree::filter_async(is_forbidden_message).endpoint(ignore_message))
+        .branch(case![BotState::ReceiveLink].endpoint(receive_link))
+        .branch(case![BotState::DeletingSomeLinks { selected, page }].endpoint(remind_about_deleting_keyboard))
+        .branch(case![BotState::ReceiveLinkForChecking].endpoint(check_site))
+        .branch(case![BotState::RenamingLink { link }].endpoint(receive_link_name))
+        .branch(case![BotState::EditingLabels { link }].endpoint(receive_link_labels))
+        .branch(case![BotState::SearchingLinks].endpoint(receive_links_query))
+        .branch(case![BotState::ImportingLinks].endpoint(receive_import_file));
+
+    let callback_query_handler = Update::filter_callback_query()
+        .branch(dptree::filter(is_acknowledge_callback).endpoint(acknowledge_alert))
+        .branch(dptree::filter_async(is_forbidden_callback).endpoint(forbid_changing_links_callback))
+        .branch(case![BotState::Default].endpoint(menu_choice_callback_handler))
+        .branch(case![BotState::ReceiveLink].endpoint(receive_link_callback_handler))
+        .branch(case![BotState::ReceiveConfirmRemoveLinks].endpoint(menu_confirm_remove_links_callback_handler))
+        .branch(case![BotState::DeletingSomeLinks { selected, page }].endpoint(deleting_links_callback_handler));
+
+    dialogue::enter::<Update, InMemStorage<BotState>, BotState, _>()
+        .branch(message_handler)
+        .branch(callback_query_handler)
+}
+
+/// Displays a welcome message to the user
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `msg`: Message sent by the user
+///
+/// returns: Result<(), Box<dyn Error+Send+Sync, Global>>
+async fn start(bot: Bot, msg: Message) -> HandlerResult {
+    info!("A new user has joined the bot: {}", msg.from().expect("Unable to determine user ID").id);
+
+    let mut text = format!("🚀 Привет, {}! Я - {}, и я могу проанализировать Ваш сайт, то есть проверить скорость его загрузки и ежечасно приводить отчёт о сбоях в работе указанного Вами сайта.", msg.from().expect("Unable to define a user name").first_name, bot.get_me().await?.first_name);
+    text = format!("{text}\n\nОсновной функционал:\n🔭 Анализ сайта (проверка наличия SSL-сертификата, время ответа, наличие robots.txt и sitemap.xml)\n📟 Ежечасная проверка сайта на стабильность, добавив ссылки в базу данных");
+
+    let keyboard = create_beginning_menu_keyboard().await;
+
+    bot.send_sticker(msg.chat.id, InputFile::file_id(STICKER_WELCOME_ID)).await?;
+    bot.send_message(msg.chat.id, text).reply_markup(keyboard).await?;
+
+    Ok(())
+}
+
+/// Sends a message to the user that displays all bot commands
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `msg`: Message sent by the user
+async fn help(bot: Bot, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, SparkleCommand::descriptions().to_string()).await?;
+    Ok(())
+}
+
+/// Adds a link to the database if it is a valid URL.
+///
+/// # Arguments:
+/// - `bot`: The Telegram bot instance.
+/// - `msg`: The received message.
+/// - `link`: The link to be added, optionally followed by its name.
+///
+/// Returns:
+/// The result of the operation.
+async fn add_link(bot: Bot, msg: Message, link: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    // The link may be followed by its name
+    let link = link.trim();
+    let (link, name) = link.split_once(char::is_whitespace).map_or((link, None), |(link, name)| (link, Some(name.trim())));
+
+    if name.is_some_and(|name| name.chars().count() > MAX_LINK_NAME_LENGTH) {
+        bot.send_message(msg.chat.id, format!("Название не должно быть длиннее {MAX_LINK_NAME_LENGTH} символов")).await?;
+        return Ok(());
+    }
+
+    if let Some(url) = normalize_link(link) {
+        if database::is_link_exists(chat_id.0, &url) {
+            bot.send_message(msg.chat.id, "Данная ссылка уже была добавлена. Пожалуйста, введите другую").await?;
+
+            return Ok(());
+        }
+
+        database::add_link(chat_id.0, &url);
+
+        if name.is_some() {
+            database::update_link_name(chat_id.0, &url, name);
+        }
+
+        info!("Added a new link to the database in the chat: {}", chat_id);
+
+        bot.send_message(msg.chat.id, "Спасибо за ссылку! Теперь я буду проверять эту ссылку каждый час").await?;
+    }
+    else {
+        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+    }
+
+    Ok(())
+}
+
+/// Brings the link entered by the user to the form in which it is stored in the database.
+///
+/// Links to websites get the `https://` scheme if it is missing, links to TCP, SMTP and TLS
+/// services get the port.
+///
+/// # Arguments
+///
+/// * `text` - The link entered by the user.
+///
+/// # Returns
+///
+/// The normalized link, or `None` if the text is not a link.
+fn normalize_link(text: &str) -> Option<String> {
+    let text = text.trim();
+
+    if monitor::is_service_link(text) {
+        return monitor::parse_service_link(text);
+    }
+
+    let url = if website::has_http_or_https(text) {
+        text.to_string()
+    }
+    else {
+        format!("https://{}", text)
+    };
+
+    Some(url).filter(|url| is_url(url))
+}
+
+/// Asynchronously checks the given site link and sends site information to the user.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `link` - The site link to check.
+///
+/// # Returns
 ///
 /// Returns a `HandlerResult` indicating the success or failure of the operation.
 ///
-/// # Examples
+/// # Examples
+///
+/// ```
+/// use mybot::Bot;
+/// use mybot::Message;
+/// use mybot::HandlerResult;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let bot = Bot::new();
+///     let msg = Message::new();
+///     let link = "https://example.com".to_string();
+///     let result = check_site(bot, msg, link).await;
+///     assert!(result.is_ok());
+/// }
+/// ```
+async fn check_site_command(bot: Bot, msg: Message, link: String) -> HandlerResult {
+    let mut url = link;
+
+    if !website::has_http_or_https(&url) {
+        url = format!("https://{}", url);
+    }
+
+    if is_url(&url) {
+        let send_message = bot.send_message(msg.chat.id, format!("Пожалуйста, подождите...\nМаксимальное время ответа - {} секунд", website::DEFAULT_TIMEOUT_SECONDS)).await?;
+
+        info!("Site information for the user is requested: {}", msg.from().expect("Unable to determine user ID").id.0);
+
+        let site_information = website::get_site_information(client::get_client(false), &url).await;
+
+        match site_information {
+            Ok(result) => {
+                save_analysis(msg.from().expect("Unable to determine user ID").id.0, &result);
+
+                let text = compile_site_information(result);
+                bot.edit_message_text(msg.chat.id, send_message.id, text).await?;
+            }
+            Err(e) => {
+                if e.is_timeout() {
+                    bot.edit_message_text(msg.chat.id, send_message.id, "Сайт слишком долго отвечал").await?;
+                }
+                else {
+                    bot.edit_message_text(msg.chat.id, send_message.id, "Боту не удалось проверить сайт").await?;
+                }
+            }
+        }
+    }
+    else {
+        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+    }
+
+    Ok(())
+}
+
+/// Saves the site analysis so that it can be compared with later analyses.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the user who requested the analysis.
+/// * `site_information` - The results of the site analysis.
+fn save_analysis(user_id: u64, site_information: &SiteInformation) {
+    match serde_json::to_string(site_information) {
+        Ok(data) => {
+            database::add_analysis(user_id, &site_information.url, site_information.checked_at, &data);
+        }
+        Err(err) => {
+            error!("Failed to save the site analysis for the user: {}. Description: {}", user_id, err);
+        }
+    }
+}
+
+/// Compares the latest stored analysis of the site with one of the previous ones.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The link to the site, optionally followed by the number of the previous analysis, 1 by default.
+async fn compare_command(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let user_id = msg.from().expect("Unable to determine user ID").id;
+    let mut arguments = arguments.split_whitespace();
+
+    let Some(mut url) = arguments.next().map(str::to_string) else {
+        bot.send_message(msg.chat.id, "Укажите ссылку на сайт, например: /compare example.com").await?;
+        return Ok(());
+    };
+
+    if !website::has_http_or_https(&url) {
+        url = format!("https://{}", url);
+    }
+
+    let analyses = database::get_analyses(user_id.0, &url);
+
+    if analyses.len() < 2 {
+        bot.send_message(msg.chat.id, "Для сравнения нужно хотя бы два анализа сайта, проверьте его командой /checksite до и после изменений").await?;
+        return Ok(());
+    }
+
+    let number = match arguments.next().map(|number| number.parse::<usize>()) {
+        None => 1,
+        Some(Ok(number)) if number > 0 && number < analyses.len() => number,
+        Some(_) => {
+            let mut text = String::from("Укажите номер предыдущего анализа:\n");
+
+            for (i, analysis) in analyses.iter().enumerate().skip(1) {
+                text = format!("{text}{i}. {}\n", compare::format_date(analysis.checked_at as i64));
+            }
+
+            bot.send_message(msg.chat.id, text).await?;
+            return Ok(());
+        }
+    };
+
+    let (Ok(after), Ok(before)) = (
+        serde_json::from_str::<SiteInformation>(&analyses[0].data),
+        serde_json::from_str::<SiteInformation>(&analyses[number].data)
+    ) else {
+        bot.send_message(msg.chat.id, "Не удалось прочитать сохраненный анализ, проверьте сайт заново командой /checksite").await?;
+        return Ok(());
+    };
+
+    info!("The user compares site analyses: {}", user_id);
+
+    let changes = compare::compare_analyses(&before, &after);
+
+    let mut text = format!("🔁 Сравнение анализов сайта {}\n{} → {}\n\n",
+                           url,
+                           compare::format_date(before.checked_at as i64),
+                           compare::format_date(after.checked_at as i64));
+
+    if changes.is_empty() {
+        text = format!("{text}Изменений не найдено, время ответа: {} → {} милисекунд", before.duration, after.duration);
+    }
+
+    for change in changes {
+        let name = match change.field {
+            ComparedField::StatusCode => String::from("📝 Код ответа"),
+            ComparedField::Duration => String::from("🕔 Время ответа, милисекунд"),
+            ComparedField::Protocols => String::from("🔌 Протоколы"),
+            ComparedField::Robots => String::from("🤖 robots.txt"),
+            ComparedField::Sitemap => String::from("🗺 sitemap.xml"),
+            ComparedField::Certificate => String::from("📄 Сертификат"),
+            ComparedField::CertificateExpiration => String::from("📄 Окончание сертификата"),
+            ComparedField::Header(name) => format!("🛡 {name}"),
+            ComparedField::MixedContent => String::from("⚠️ Ресурсов по HTTP"),
+            ComparedField::Score => String::from("🏆 Оценка сайта"),
+            ComparedField::SectionScore(section) => format!("🏆 {}", report::describe_section(section))
+        };
+
+        text = format!("{text}{name}: {} → {}\n", change.before, change.after);
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Analyzes two sites at the same time and sends the user a side-by-side table of the results.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The links to the two sites.
+async fn compare_sites_command(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let user_id = msg.from().expect("Unable to determine user ID").id;
+
+    let urls: Vec<String> = arguments.split_whitespace()
+        .map(|link| if website::has_http_or_https(link) { link.to_string() } else { format!("https://{}", link) })
+        .collect();
+
+    if urls.len() != 2 || !urls.iter().all(|url| is_url(url)) {
+        bot.send_message(msg.chat.id, "Укажите ссылки на два сайта, например: /comparesites example.com example.org").await?;
+        return Ok(());
+    }
+
+    let send_message = bot.send_message(msg.chat.id, format!("Пожалуйста, подождите...\nМаксимальное время ответа - {} секунд", website::DEFAULT_TIMEOUT_SECONDS)).await?;
+
+    info!("The user compares two sites: {}", user_id);
+
+    let client = client::get_client(false);
+    let (first, second) = tokio::join!(
+        website::get_site_information(client, &urls[0]),
+        website::get_site_information(client, &urls[1])
+    );
+
+    let (first, second) = match (first, second) {
+        (Ok(first), Ok(second)) => (first, second),
+        (first, _) => {
+            let failed_url = if first.is_err() { &urls[0] } else { &urls[1] };
+
+            bot.edit_message_text(msg.chat.id, send_message.id, format!("Боту не удалось проверить сайт: {failed_url}")).await?;
+            return Ok(());
+        }
+    };
+
+    save_analysis(user_id.0, &first);
+    save_analysis(user_id.0, &second);
+
+    let hosts: Vec<String> = urls.iter()
+        .map(|url| Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string)).unwrap_or_else(|| url.clone()))
+        .collect();
+
+    let rows = compare::compare_sites(&first, &second);
+    let mut table = vec![(String::new(), String::from("1"), String::from("2"))];
+    table.extend(rows.into_iter().map(|row| (row.name.to_string(), row.first, row.second)));
+
+    let name_width = table.iter().map(|(name, _, _)| name.chars().count()).max().unwrap_or(0);
+    let first_width = table.iter().map(|(_, first, _)| first.chars().count()).max().unwrap_or(0);
+
+    let mut text = format!("⚖️ Сравнение сайтов\n1 - {}\n2 - {}\n\n<pre>", report::escape_html(&hosts[0]), report::escape_html(&hosts[1]));
+
+    for (name, first, second) in table {
+        let line = format!("{name:<name_width$} | {first:<first_width$} | {second}");
+        text = format!("{text}{}\n", report::escape_html(line.trim_end()));
+    }
+
+    text = format!("{text}</pre>");
+
+    bot.edit_message_text(msg.chat.id, send_message.id, text).parse_mode(ParseMode::Html).await?;
+
+    Ok(())
+}
+
+/// Analyzes the site and sends the user a report as a JSON or HTML file.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The link to the site, optionally followed by the format `json` or `html` (the default).
+async fn report_command(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let mut arguments = arguments.split_whitespace();
+
+    let Some(mut url) = arguments.next().map(str::to_string) else {
+        bot.send_message(msg.chat.id, "Укажите ссылку на сайт, например: /report example.com html").await?;
+        return Ok(());
+    };
+
+    let format = arguments.next().unwrap_or("html").to_lowercase();
+
+    if format != "json" && format != "html" {
+        bot.send_message(msg.chat.id, "Поддерживаются форматы json и html").await?;
+        return Ok(());
+    }
+
+    if !website::has_http_or_https(&url) {
+        url = format!("https://{}", url);
+    }
+
+    if !is_url(&url) {
+        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+        return Ok(());
+    }
+
+    let send_message = bot.send_message(msg.chat.id, format!("Пожалуйста, подождите...\nМаксимальное время ответа - {} секунд", website::DEFAULT_TIMEOUT_SECONDS)).await?;
+
+    info!("A site report for the user is requested: {}", msg.from().expect("Unable to determine user ID").id.0);
+
+    let site_information = match website::get_site_information(client::get_client(false), &url).await {
+        Ok(site_information) => site_information,
+        Err(_) => {
+            bot.edit_message_text(msg.chat.id, send_message.id, "Боту не удалось проверить сайт").await?;
+            return Ok(());
+        }
+    };
+
+    save_analysis(msg.from().expect("Unable to determine user ID").id.0, &site_information);
+
+    // The report of a saved site is titled with the name that it has been given in the chat
+    let name = normalize_link(&url)
+        .and_then(|url| database::get_all_links_from_user(msg.chat.id.0, Some(&url)).into_iter().next())
+        .and_then(|link| link.name);
+
+    let content = if format == "json" { report::to_json(&site_information, name.as_deref()) } else { report::to_html(&site_information, name.as_deref()) };
+
+    bot.send_document(msg.chat.id, InputFile::memory(content.into_bytes()).file_name(format!("report.{format}"))).await?;
+    bot.delete_message(msg.chat.id, send_message.id).await?;
+
+    Ok(())
+}
+
+/// Downloads the page with all linked resources and sends the user a report on its weight.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `link` - The link to the page.
+async fn page_weight_command(bot: Bot, msg: Message, link: String) -> HandlerResult {
+    let mut url = link;
+
+    if !website::has_http_or_https(&url) {
+        url = format!("https://{}", url);
+    }
+
+    if !is_url(&url) {
+        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+        return Ok(());
+    }
+
+    let send_message = bot.send_message(msg.chat.id, "Пожалуйста, подождите...\nЗагружаю страницу и все ее ресурсы").await?;
+
+    info!("Page weight for the user is requested: {}", msg.from().expect("Unable to determine user ID").id.0);
+
+    let page_weight = match performance::get_page_weight(client::get_client(false), &url).await {
+        Ok(page_weight) => page_weight,
+        Err(_) => {
+            bot.edit_message_text(msg.chat.id, send_message.id, "Боту не удалось загрузить страницу").await?;
+            return Ok(());
+        }
+    };
+
+    let mut text = format!("⚖️ Вес страницы: {}\n\nHTML: {}", format_size(page_weight.total_size()), format_size(page_weight.html_size));
+
+    for (kind, title) in [(ResourceKind::Stylesheet, "Стили"), (ResourceKind::Script, "Скрипты"), (ResourceKind::Image, "Изображения")] {
+        let resources: Vec<usize> = page_weight.resources.iter()
+            .filter(|resource| resource.kind == kind)
+            .filter_map(|resource| resource.size)
+            .collect();
+
+        text = format!("{text}\n{title}: {} шт., {}", resources.len(), format_size(resources.iter().sum()));
+    }
+
+    let failed = page_weight.resources.iter().filter(|resource| resource.size.is_none()).count();
+
+    if failed > 0 {
+        text = format!("{text}\n⚠️ Не удалось загрузить ресурсов: {failed}");
+    }
+
+    let heaviest: Vec<String> = page_weight.resources.iter()
+        .filter_map(|resource| resource.size.map(|size| format!("• {} — {}", resource.url, format_size(size))))
+        .take(MAX_HEAVIEST_RESOURCES)
+        .collect();
+
+    if !heaviest.is_empty() {
+        text = format!("{text}\n\nСамые тяжелые ресурсы:\n{}", heaviest.join("\n"));
+    }
+
+    bot.edit_message_text(msg.chat.id, send_message.id, text).await?;
+
+    Ok(())
+}
+
+/// Shows or changes the check settings of one of the user's links.
+///
+/// The first argument is the number of the link in the list, the rest are `key=value` pairs:
+/// `timeout` in seconds, `codes` with the expected status codes, `slow` with the response
+/// time threshold in milliseconds, `dualstack` and `insecure` to accept invalid certificates.
+/// The value `default` resets the setting.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The text after the command.
+async fn link_settings(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+    let mut arguments = arguments.split_whitespace();
+
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    let link = match arguments.next().and_then(|number| number.parse::<usize>().ok()) {
+        Some(number) if number > 0 && number <= links.len() => &links[number - 1],
+        _ => {
+            bot.send_message(msg.chat.id, "Укажите номер ссылки из списка сохраненных ссылок, например: /linksettings 1 timeout=10 codes=200-299,401 slow=2000").await?;
+            return Ok(());
+        }
+    };
+
+    let mut timeout = link.timeout;
+    let mut expected_codes = link.expected_codes.clone();
+    let mut slow_threshold = link.slow_threshold;
+    let mut dual_stack = link.dual_stack;
+    let mut accept_invalid_certs = link.accept_invalid_certs;
+    let mut is_changed = false;
+
+    for argument in arguments {
+        let (key, value) = argument.split_once('=').unwrap_or((argument, ""));
+        let is_default = value == "default";
+
+        let is_correct = match key {
+            "timeout" => {
+                timeout = if is_default { None } else { value.parse::<u64>().ok().filter(|seconds| *seconds > 0) };
+                is_default || timeout.is_some()
+            }
+            "codes" => {
+                expected_codes = if is_default { None } else { ExpectedStatusCodes::parse(value).map(|codes| codes.to_string()) };
+                is_default || expected_codes.is_some()
+            }
+            "slow" => {
+                slow_threshold = if is_default { None } else { value.parse::<u64>().ok().filter(|milliseconds| *milliseconds > 0) };
+                is_default || slow_threshold.is_some()
+            }
+            "dualstack" => {
+                dual_stack = value == "on";
+                matches!(value, "on" | "off" | "default")
+            }
+            "insecure" => {
+                accept_invalid_certs = value == "on";
+                matches!(value, "on" | "off" | "default")
+            }
+            _ => false
+        };
+
+        if !is_correct {
+            bot.send_message(msg.chat.id, format!("Некорректный параметр: {argument}")).await?;
+            return Ok(());
+        }
+
+        is_changed = true;
+    }
+
+    if is_changed {
+        database::update_link_settings(chat_id.0, &link.link, timeout, expected_codes.as_deref(), slow_threshold, dual_stack, accept_invalid_certs);
+
+        info!("The user has changed the link settings in the chat: {}", chat_id);
+    }
+
+    let link = Links { timeout, expected_codes, slow_threshold, dual_stack, accept_invalid_certs, ..link.clone() };
+
+    bot.send_message(msg.chat.id, describe_link_settings(&link)).await?;
+
+    Ok(())
+}
+
+/// Describes the check settings of the link
+///
+/// # Arguments
+///
+/// * `link`: The link with its settings
+fn describe_link_settings(link: &Links) -> String {
+    format!("⚙️ Настройки проверки ссылки: {}\n\nВремя ожидания: {} секунд\nОжидаемые коды ответа: {}\nПорог медленного ответа: {}\nОтдельная проверка IPv4 и IPv6: {}\nНедействительные сертификаты: {}",
+            link.link,
+            link.timeout.unwrap_or(website::DEFAULT_TIMEOUT_SECONDS),
+            link.expected_codes.clone().unwrap_or_else(|| ExpectedStatusCodes::default().to_string()),
+            link.slow_threshold.map_or(String::from("не задан"), |milliseconds| format!("{milliseconds} миллисекунд")),
+            if link.dual_stack { "включена" } else { "выключена" },
+            if link.accept_invalid_certs { "принимаются" } else { "не принимаются" })
+}
+
+/// Enables or disables content change detection for one of the user's links.
+///
+/// The first argument is the number of the link in the list, optionally followed by `off`.
+/// The following lines contain the rules for ignoring dynamic regions of the page.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The text after the command.
+async fn watch_content(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    let (first_line, rules) = arguments.split_once('\n').unwrap_or((&arguments, ""));
+    let mut first_line = first_line.split_whitespace();
+
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    let link = match first_line.next().and_then(|number| number.parse::<usize>().ok()) {
+        Some(number) if number > 0 && number <= links.len() => &links[number - 1],
+        _ => {
+            bot.send_message(msg.chat.id, "Укажите номер ссылки из списка сохраненных ссылок, например:\n/watchcontent 1\ncss:.banner\nregex:\\d{2}:\\d{2}").await?;
+            return Ok(());
+        }
+    };
+
+    match first_line.next() {
+        Some("off") => {
+            database::update_content_watch(chat_id.0, &link.link, false, None);
+
+            bot.send_message(msg.chat.id, format!("Я больше не слежу за содержимым страницы: {}", link.link)).await?;
+        }
+        Some(argument) => {
+            bot.send_message(msg.chat.id, format!("Некорректный параметр: {argument}")).await?;
+        }
+        None => {
+            if let Err(line) = content::parse_ignore_rules(rules) {
+                bot.send_message(msg.chat.id, format!("Некорректное правило: {line}\nПравила должны начинаться с css: или regex:")).await?;
+                return Ok(());
+            }
+
+            let rules = Some(rules.trim()).filter(|rules| !rules.is_empty());
+            database::update_content_watch(chat_id.0, &link.link, true, rules);
+
+            info!("The user has enabled content change detection in the chat: {}", chat_id);
+
+            bot.send_message(msg.chat.id, format!("Теперь я буду сообщать об изменениях содержимого страницы: {}", link.link)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Shows, adds or deletes the maintenance windows of one of the user's links.
+///
+/// The first argument is the number of the link in the list. It is followed by a window like
+/// `2024-06-01 22:00 2h Europe/Moscow`, `delete <number of the window>` or `off` to delete all windows.
+/// Without other arguments the windows of the link are shown.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The text after the command.
+async fn maintenance_command(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+    let arguments: Vec<&str> = arguments.split_whitespace().collect();
+
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    let link = match arguments.first().and_then(|number| number.parse::<usize>().ok()) {
+        Some(number) if number > 0 && number <= links.len() => &links[number - 1],
+        _ => {
+            bot.send_message(msg.chat.id, "Укажите номер ссылки из списка сохраненных ссылок, например: /maintenance 1 2024-06-01 22:00 2h Europe/Moscow или /maintenance 1 mon-fri 23:30 45m").await?;
+            return Ok(());
+        }
+    };
+
+    let windows = database::get_maintenance_windows(chat_id.0, &link.link);
+
+    let text = match &arguments[1..] {
+        [] => describe_maintenance_windows(link),
+        ["off"] => {
+            database::delete_maintenance_windows(chat_id.0, &link.link, None);
+
+            format!("Все окна обслуживания ссылки {} удалены", link.link)
+        }
+        ["delete", number] => {
+            match number.parse::<usize>().ok().filter(|number| *number > 0).and_then(|number| windows.get(number - 1)) {
+                Some(window) => {
+                    database::delete_maintenance_windows(chat_id.0, &link.link, Some(window.id));
+
+                    format!("Окно обслуживания удалено: {}", window.schedule)
+                }
+                None => String::from("Укажите номер окна обслуживания из списка")
+            }
+        }
+        window => {
+            match MaintenanceWindow::parse(&window.join(" ")) {
+                Some(window) => {
+                    database::add_maintenance_window(chat_id.0, &link.link, &window.to_string());
+
+                    info!("The user has added a maintenance window in the chat: {}", chat_id);
+
+                    format!("Добавлено окно обслуживания ссылки {}: {window}\n\nВо время обслуживания я буду проверять ссылку, но не буду присылать оповещения, а после него пришлю итоги проверок", link.link)
+                }
+                None => String::from("Некорректное окно обслуживания. Укажите дату (2024-06-01) или дни недели (daily, sun, mon-fri), время начала, длительность и часовой пояс, например: 2024-06-01 22:00 2h Europe/Moscow или mon-fri 23:30 1h30m")
+            }
+        }
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Describes the maintenance windows of the link and whether the link is in maintenance now
+///
+/// # Arguments
+///
+/// * `link`: The link with its maintenance state
+fn describe_maintenance_windows(link: &Links) -> String {
+    let windows = database::get_maintenance_windows(link.user_id as i64, &link.link);
+
+    if windows.is_empty() {
+        return format!("У ссылки {} нет окон обслуживания", link.link);
+    }
+
+    let mut text = format!("🛠 Окна обслуживания ссылки {}:\n", link.link);
+
+    for (i, window) in windows.iter().enumerate() {
+        text = format!("{text}{}. {}\n", i + 1, window.schedule);
+    }
+
+    if let Some(maintenance_since) = link.maintenance_since {
+        text = format!("{text}\nОбслуживание идет с {}", compare::format_date(maintenance_since as i64));
+    }
+
+    text
+}
+
+/// Shows all labels of the user or sets the labels of one of the user's links.
+///
+/// The first argument is the number of the link in the list. It is followed by the labels
+/// separated by spaces, or `off` to remove all labels. Without other arguments the labels of the
+/// link are shown, without any arguments all labels of the user are shown.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The text after the command.
+async fn labels_command(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    let arguments = arguments.trim();
+
+    if arguments.is_empty() {
+        bot.send_message(msg.chat.id, describe_labels(chat_id.0, &links)).await?;
+        return Ok(());
+    }
+
+    let (number, labels) = arguments.split_once(char::is_whitespace).unwrap_or((arguments, ""));
+
+    let link = match number.parse::<usize>() {
+        Ok(number) if number > 0 && number <= links.len() => &links[number - 1],
+        _ => {
+            bot.send_message(msg.chat.id, "Укажите номер ссылки из списка сохраненных ссылок, например: /labels 1 prod client-x").await?;
+            return Ok(());
+        }
+    };
+
+    let text = match labels.trim() {
+        "" if link.labels.is_empty() => format!("У ссылки {} нет меток", link.link),
+        "" => format!("🏷 Метки ссылки {}: {}", link.link, link.labels.join(", ")),
+        "off" => {
+            database::update_link_labels(chat_id.0, &link.link, &[]);
+
+            format!("Метки ссылки {} удалены", link.link)
+        }
+        labels => match set_link_labels(chat_id.0, &link.link, labels) {
+            Ok(labels) => format!("🏷 Метки ссылки {}: {}", link.link, labels.join(", ")),
+            Err(text) => text
+        }
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Parses the labels entered by the user and saves them as the labels of the link
+///
+/// # Arguments
+///
+/// * `chat_id`: The ID of the chat
+/// * `link`: The link
+/// * `text`: The labels separated by spaces or commas
+///
+/// # Returns
+///
+/// The saved labels, or the text of the message about the invalid labels.
+fn set_link_labels(chat_id: i64, link: &str, text: &str) -> Result<Vec<String>, String> {
+    let labels = links_list::parse_labels(text)
+        .map_err(|label| format!("Некорректная метка: {label}\nМетка может содержать только буквы, цифры, «-» и «_» и должна быть не длиннее {} символов", links_list::MAX_LABEL_LENGTH))?;
+
+    if labels.len() > links_list::MAX_LABELS {
+        return Err(format!("У ссылки может быть не больше {} меток", links_list::MAX_LABELS));
+    }
+
+    database::update_link_labels(chat_id, link, &labels);
+
+    info!("The user has changed the labels of a link in the chat: {}", chat_id);
+
+    Ok(labels)
+}
+
+/// Describes all labels of the user with the number of links and the labels the user receives alerts about
+///
+/// # Arguments
+///
+/// * `chat_id`: The ID of the chat
+/// * `links`: All links of the user
+fn describe_labels(chat_id: i64, links: &[Links]) -> String {
+    let labels = links_list::count_labels(links);
+
+    let mut text = if labels.is_empty() {
+        String::from("У ваших ссылок нет меток\n")
+    }
+    else {
+        let mut text = String::from("🏷 Ваши метки:\n");
+
+        for (label, count) in labels {
+            text = format!("{text}#{label} — ссылок: {count}\n");
+        }
+
+        text
+    };
+
+    let alert_labels = database::get_alert_labels(chat_id);
+
+    if !alert_labels.is_empty() {
+        text = format!("{text}\nОповещения приходят только о ссылках с метками: {}\n", alert_labels.join(", "));
+    }
+
+    format!("{text}\nЧтобы задать метки ссылки, используйте команду /labels <номер> <метки>, например: /labels 1 prod client-x. Ссылки с меткой можно найти командой /links #<метка> и там же приостановить, проверить или удалить их все сразу")
+}
+
+/// Sends the user a summary of their links: how many of them work, do not work, are paused or
+/// have not been checked yet, and the total uptime
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `label` - The label of the summarized links, all links are summarized if it is empty.
+async fn stats_command(bot: Bot, msg: Message, label: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    let label = label.trim().trim_start_matches('#').to_lowercase();
+
+    let selected: Vec<&Links> = links.iter()
+        .filter(|link| label.is_empty() || link.labels.contains(&label))
+        .collect();
+
+    if selected.is_empty() {
+        let text = if label.is_empty() { String::from("У вас нет сохраненных ссылок") } else { format!("Нет ссылок с меткой {label}") };
+
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let stats = links_list::calculate_stats(&selected);
+
+    let mut text = if label.is_empty() { String::from("📊 Сводка по ссылкам\n\n") } else { format!("📊 Сводка по ссылкам с меткой {label}\n\n") };
+
+    text = format!("{text}Всего ссылок: {}\n✅ Работают: {}\n❌ Не работают: {}\n⏸ Приостановлены: {}\n❔ Еще не проверялись: {}\n",
+                   stats.total, stats.up, stats.down, stats.paused, stats.unchecked);
+
+    if let Some(uptime) = stats.uptime {
+        text = format!("{text}Доступность: {uptime:.1}%\n");
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Sets the labels of the links the user wants to receive alerts about.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `labels` - The labels separated by spaces, `all` to receive alerts about all links. Without
+///   arguments the current labels are shown.
+async fn alerts_command(bot: Bot, msg: Message, labels: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    let text = match labels.trim() {
+        "" => match database::get_alert_labels(chat_id.0) {
+            alert_labels if alert_labels.is_empty() => String::from("Оповещения приходят обо всех ссылках. Чтобы получать оповещения только о ссылках с метками, используйте команду /alerts <метки>, например: /alerts prod"),
+            alert_labels => format!("Оповещения приходят только о ссылках с метками: {}\nЧтобы получать оповещения обо всех ссылках, используйте команду /alerts all", alert_labels.join(", "))
+        },
+        "all" => {
+            database::update_alert_labels(chat_id.0, &[]);
+
+            String::from("Теперь оповещения будут приходить обо всех ссылках")
+        }
+        labels => match links_list::parse_labels(labels) {
+            Ok(labels) => {
+                database::update_alert_labels(chat_id.0, &labels);
+
+                info!("The user has changed the labels of alerts in the chat: {}", chat_id);
+
+                format!("Теперь оповещения будут приходить только о ссылках с метками: {}", labels.join(", "))
+            }
+            Err(label) => format!("Некорректная метка: {label}")
+        }
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Adds the user to the members of the group who are mentioned in its alerts.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+async fn join_command(bot: Bot, msg: Message) -> HandlerResult {
+    let user = msg.from().expect("Unable to determine user ID");
+
+    if msg.chat.id.is_user() {
+        bot.send_message(msg.chat.id, "Оповещения о ваших ссылках и так приходят в этот чат. Команда /join нужна в группах").await?;
+        return Ok(());
+    }
+
+    database::add_chat_member(msg.chat.id.0, user.id.0, &user.full_name());
+
+    info!("The user has joined the alerts of the chat: {}", msg.chat.id);
+
+    bot.send_message(msg.chat.id, format!("{}, теперь я буду упоминать вас в оповещениях группы. Чтобы временно отключить упоминания, используйте команду /mute", user.first_name)).await?;
+
+    Ok(())
+}
+
+/// Removes the user from the members of the group who are mentioned in its alerts.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+async fn leave_command(bot: Bot, msg: Message) -> HandlerResult {
+    let user = msg.from().expect("Unable to determine user ID");
+
+    database::delete_chat_member(msg.chat.id.0, user.id.0);
+
+    info!("The user has left the alerts of the chat: {}", msg.chat.id);
+
+    bot.send_message(msg.chat.id, format!("{}, я больше не буду упоминать вас в оповещениях", user.first_name)).await?;
+
+    Ok(())
+}
+
+/// Mutes the alerts of the chat for the user: in a group the user is no longer mentioned in the
+/// alerts, in a private chat the alerts are not sent.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `duration` - The duration of the mute like `8h` or `30m`, the user is muted until `/unmute` if it is empty.
+async fn mute_command(bot: Bot, msg: Message, duration: String) -> HandlerResult {
+    let user = msg.from().expect("Unable to determine user ID");
+
+    let muted_until = match duration.trim() {
+        "" => MUTED_FOREVER,
+        duration => {
+            let muted_until = maintenance::parse_duration(duration)
+                .filter(|minutes| (1..=MAX_MUTE_MINUTES).contains(minutes))
+                .and_then(|minutes| (minutes as u64).checked_mul(60))
+                .and_then(|seconds| get_timestamp().checked_add(seconds));
+
+            match muted_until {
+                Some(muted_until) => muted_until,
+                None => {
+                    bot.send_message(msg.chat.id, "Некорректная длительность. Укажите ее в часах и минутах, но не больше года, например: /mute 8h или /mute 1h30m. Чтобы отключить оповещения без срока, используйте /mute без длительности").await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    // In a private chat the user is the only member of the chat
+    if msg.chat.id.is_user() {
+        database::add_chat_member(msg.chat.id.0, user.id.0, &user.full_name());
+    }
+    else if !database::get_chat_members(msg.chat.id.0).iter().any(|member| member.user_id == user.id.0) {
+        bot.send_message(msg.chat.id, "Вас не упоминают в оповещениях группы. Чтобы вас упоминали, используйте команду /join").await?;
+        return Ok(());
+    }
+
+    database::update_member_mute(msg.chat.id.0, user.id.0, Some(muted_until));
+
+    info!("The user has muted the alerts of the chat: {}", msg.chat.id);
+
+    let text = if muted_until == MUTED_FOREVER {
+        format!("🔕 {}, ваши оповещения отключены. Чтобы включить их, используйте команду /unmute", user.first_name)
+    }
+    else {
+        format!("🔕 {}, ваши оповещения отключены до {}", user.first_name, compare::format_date(muted_until as i64))
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Unmutes the alerts of the chat for the user.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+async fn unmute_command(bot: Bot, msg: Message) -> HandlerResult {
+    let user = msg.from().expect("Unable to determine user ID");
+
+    database::update_member_mute(msg.chat.id.0, user.id.0, None);
+
+    info!("The user has unmuted the alerts of the chat: {}", msg.chat.id);
+
+    bot.send_message(msg.chat.id, format!("🔔 {}, ваши оповещения снова включены", user.first_name)).await?;
+
+    Ok(())
+}
+
+/// Subscribes the user to the alerts about a link of the group, which the user then gets in private messages.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `number` - The number of the link in the list of saved links.
+async fn subscribe_command(bot: Bot, msg: Message, number: String) -> HandlerResult {
+    let user = msg.from().expect("Unable to determine user ID");
+
+    if msg.chat.id.is_user() {
+        bot.send_message(msg.chat.id, "Оповещения о ваших ссылках и так приходят в этот чат. Команда /subscribe нужна в группах").await?;
+        return Ok(());
+    }
+
+    let links = database::get_all_links_from_user(msg.chat.id.0, None);
+
+    let link = match number.trim().parse::<usize>() {
+        Ok(number) if number > 0 && number <= links.len() => &links[number - 1],
+        _ => {
+            bot.send_message(msg.chat.id, "Укажите номер ссылки из списка сохраненных ссылок, например: /subscribe 1").await?;
+            return Ok(());
+        }
+    };
+
+    database::add_link_subscriber(msg.chat.id.0, &link.link, user.id.0, &user.full_name());
+
+    info!("The user has subscribed to the alerts about a link in the chat: {}", msg.chat.id);
+
+    bot.send_message(msg.chat.id, format!("{}, теперь я буду присылать вам в личные сообщения оповещения о ссылке {}. Если вы еще не писали мне, отправьте мне /start в личных сообщениях, иначе Telegram не позволит мне вам написать", user.first_name, describe_link(link))).await?;
+
+    Ok(())
+}
+
+/// Unsubscribes the user from the alerts about a link.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `number` - The number of the link in the list of saved links.
+async fn unsubscribe_command(bot: Bot, msg: Message, number: String) -> HandlerResult {
+    let user = msg.from().expect("Unable to determine user ID");
+    let links = database::get_all_links_from_user(msg.chat.id.0, None);
+
+    let link = match number.trim().parse::<usize>() {
+        Ok(number) if number > 0 && number <= links.len() => &links[number - 1],
+        _ => {
+            bot.send_message(msg.chat.id, "Укажите номер ссылки из списка сохраненных ссылок, например: /unsubscribe 1").await?;
+            return Ok(());
+        }
+    };
+
+    database::delete_link_subscriber(msg.chat.id.0, &link.link, user.id.0);
+
+    info!("The user has unsubscribed from the alerts about a link in the chat: {}", msg.chat.id);
+
+    bot.send_message(msg.chat.id, format!("{}, я больше не буду присылать вам оповещения о ссылке {}", user.first_name, describe_link(link))).await?;
+
+    Ok(())
+}
+
+/// Shows the subscribers of a link, its escalation policy and who acknowledged its last alerts,
+/// or changes the escalation policy.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `arguments` - The number of the link, optionally followed by the delay before escalation like `15m` or `off`.
+async fn on_call_command(bot: Bot, msg: Message, arguments: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+    let arguments: Vec<&str> = arguments.split_whitespace().collect();
+
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    let (number, link) = match arguments.first().and_then(|number| number.parse::<usize>().ok()) {
+        Some(number) if number > 0 && number <= links.len() => (number, &links[number - 1]),
+        _ => {
+            bot.send_message(chat_id, "Укажите номер ссылки из списка сохраненных ссылок, например: /oncall 1 или /oncall 1 15m").await?;
+            return Ok(());
+        }
+    };
+
+    let text = match &arguments[1..] {
+        [] => describe_on_call(chat_id.0, number, link),
+        ["off"] => {
+            database::update_escalation(chat_id.0, &link.link, None);
+
+            format!("Эскалация отключена: оповещения о ссылке {} сразу получают все подписчики", describe_link(link))
+        }
+        [delay] => {
+            match maintenance::parse_duration(delay).map(|minutes| minutes as u64).filter(|minutes| (1..=escalation::MAX_ESCALATION_MINUTES).contains(minutes)) {
+                Some(minutes) => {
+                    database::update_escalation(chat_id.0, &link.link, Some(minutes));
+
+                    info!("The user has changed the escalation policy in the chat: {}", chat_id);
+
+                    format!("Теперь оповещение о том, что ссылка {} не работает, получает первый подписчик, а если никто не подтвердит его за {minutes} мин., — следующий", describe_link(link))
+                }
+                None => format!("Некорректная задержка. Укажите ее в часах и минутах, не больше {} часов, например: /oncall {number} 15m", escalation::MAX_ESCALATION_MINUTES / 60)
+            }
+        }
+        _ => format!("Укажите задержку эскалации или off, например: /oncall {number} 15m")
+    };
+
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Describes the subscribers of the link in the order they are notified, its escalation policy and its last alerts
+///
+/// # Arguments
+///
+/// * `chat_id`: The chat that owns the link
+/// * `number`: The number of the link in the list of saved links
+/// * `link`: The link
+fn describe_on_call(chat_id: i64, number: usize, link: &Links) -> String {
+    let subscribers = database::get_link_subscribers(chat_id, &link.link);
+
+    let mut text = format!("📟 Дежурство по ссылке {}\n\n", describe_link(link));
+
+    if subscribers.is_empty() {
+        text = format!("{text}Подписчиков нет. Участники группы могут подписаться командой /subscribe {number}\n");
+    }
+    else {
+        text = format!("{text}Подписчики в порядке оповещения:\n");
+
+        for (index, subscriber) in subscribers.iter().enumerate() {
+            text = format!("{text}{}. {}\n", index + 1, subscriber.name);
+        }
+    }
+
+    text = match link.escalation_minutes {
+        Some(minutes) => format!("{text}\nЭскалация: следующий подписчик получает оповещение, если его не подтвердили за {minutes} мин.\n"),
+        None => format!("{text}\nЭскалация выключена: оповещения сразу получают все подписчики\n")
+    };
+
+    let alerts = database::get_link_alerts(chat_id, &link.link, MAX_SHOWN_ALERTS);
+
+    if !alerts.is_empty() {
+        text = format!("{text}\nПоследние оповещения:\n");
+
+        for alert in alerts {
+            let mut state = match (alert.acknowledged_by, alert.acknowledged_at) {
+                (Some((_, name)), Some(acknowledged_at)) => format!("✅ подтвердил(а) {name} {}", compare::format_date(acknowledged_at as i64)),
+                _ => String::from("⏳ не подтверждено")
+            };
+
+            if let Some(resolved_at) = alert.resolved_at {
+                state = format!("{state}, 🟢 ссылка заработала {}", compare::format_date(resolved_at as i64));
+            }
+
+            text = format!("{text}• {} — {state}\n", compare::format_date(alert.sent_at as i64));
+        }
+    }
+
+    text
+}
+
+/// Sends the user a file with their links and the settings of each link.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance used to send messages.
+/// * `msg` - The message object representing the user message.
+/// * `format` - The format of the file, `json` (the default) or `csv`.
+async fn export_command(bot: Bot, msg: Message, format: String) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    let format = match format.trim().to_lowercase().as_str() {
+        "" | "json" => TransferFormat::Json,
+        "csv" => TransferFormat::Csv,
+        _ => {
+            bot.send_message(msg.chat.id, "Поддерживаются форматы json и csv").await?;
+            return Ok(());
+        }
+    };
+
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    if links.is_empty() {
+        bot.send_message(msg.chat.id, "У вас нет сохраненных ссылок").await?;
+        return Ok(());
+    }
+
+    let exported: Vec<ExportedLink> = links.iter()
+        .map(|link| {
+            let windows: Vec<String> = database::get_maintenance_windows(chat_id.0, &link.link).into_iter().map(|window| window.schedule).collect();
+
+            ExportedLink::new(link, &windows)
+        })
+        .collect();
+
+    info!("The user has exported the links in the chat: {}", chat_id);
+
+    let content = transfer::export_links(&exported, format);
+
+    bot.send_document(msg.chat.id, InputFile::memory(content.into_bytes()).file_name(format!("links.{}", format.extension()))).await?;
+
+    Ok(())
+}
+
+/// Sends a message to the user telling them to upload a file with links and goes to the ImportingLinks state
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn import_command(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    let text = "Отправьте файл CSV или JSON со ссылками, например, полученный командой /export. В файле CSV обязателен только столбец link. Для отмены введите команду /cancel";
+
+    send_prompt(&bot, msg.chat.id, String::from(text)).await?;
+    dialogue.update(BotState::ImportingLinks).await?;
+
+    Ok(())
+}
+
+/// Receives a file with links from the user, validates each entry and adds the valid links with
+/// their settings, then sends a summary of the import
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn receive_import_file(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    let Some(document) = msg.document() else {
+        send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, отправьте файл. Для отмены введите команду /cancel")).await?;
+        return Ok(());
+    };
+
+    if document.file.size > MAX_IMPORT_FILE_SIZE {
+        send_prompt(&bot, msg.chat.id, format!("Файл слишком большой, максимальный размер - {}", format_size(MAX_IMPORT_FILE_SIZE as usize))).await?;
+        return Ok(());
+    }
+
+    let file = bot.get_file(&document.file.id).await?;
+    let mut content: Vec<u8> = Vec::new();
+
+    bot.download_file(&file.path, &mut content).await?;
+
+    let Ok(content) = String::from_utf8(content) else {
+        send_prompt(&bot, msg.chat.id, String::from("Файл должен быть в кодировке UTF-8")).await?;
+        return Ok(());
+    };
+
+    let content = content.trim_start_matches('\u{feff}');
+    let format = TransferFormat::detect(document.file_name.as_deref(), content);
+
+    let entries = match transfer::import_links(content, format) {
+        Ok(entries) => entries,
+        Err(err) => {
+            send_prompt(&bot, msg.chat.id, format!("Не удалось прочитать файл: {}", describe_import_error(&err))).await?;
+            return Ok(());
+        }
+    };
+
+    let added_at = get_timestamp();
+    let mut added: Vec<String> = Vec::new();
+    let mut duplicates = 0;
+    let mut errors: Vec<String> = Vec::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let result = entry.as_ref()
+            .map_err(describe_import_error)
+            .and_then(|entry| entry.validate().map(|_| entry).map_err(|err| describe_import_error(&err)))
+            .and_then(|entry| {
+                if entry.name.as_ref().is_some_and(|name| name.chars().count() > MAX_LINK_NAME_LENGTH) {
+                    return Err(describe_import_error(&ImportError::NameTooLong));
+                }
+
+                normalize_link(&entry.link)
+                    .map(|link| (entry, link))
+                    .ok_or_else(|| describe_import_error(&ImportError::InvalidLink))
+            });
+
+        match result {
+            Ok((_, link)) if added.contains(&link) || database::is_link_exists(chat_id.0, &link) => duplicates += 1,
+            Ok((entry, link)) => {
+                let windows = entry.get_maintenance_windows().into_iter()
+                    .filter_map(MaintenanceWindow::parse)
+                    .map(|window| window.to_string())
+                    .collect();
+
+                database::restore_links(&[(entry.to_links(chat_id.0, &link, added_at), windows)]);
+
+                added.push(link);
+            }
+            Err(description) => errors.push(format!("Запись {}: {description}", i + 1))
+        }
+    }
+
+    info!("The user has imported {} links in the chat: {}", added.len(), chat_id);
+
+    let mut text = format!("📥 Импорт завершен\n\nДобавлено: {}\nУже были добавлены: {}\nС ошибками: {}", added.len(), duplicates, errors.len());
+
+    if !errors.is_empty() {
+        text = format!("{text}\n");
+
+        for error in errors.iter().take(MAX_IMPORT_ERRORS) {
+            text = format!("{text}\n{}", shorten(error, MAX_LIST_LINK_LENGTH));
+        }
+
+        if errors.len() > MAX_IMPORT_ERRORS {
+            text = format!("{text}\n...и еще {}", errors.len() - MAX_IMPORT_ERRORS);
+        }
+    }
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    dialogue.update(BotState::Default).await?;
+
+    Ok(())
+}
+
+/// Describes the problem with the imported file or its entry
+///
+/// # Arguments
+///
+/// * `err`: The problem
+fn describe_import_error(err: &ImportError) -> String {
+    match err {
+        ImportError::Format(description) => format!("некорректный формат ({description})"),
+        ImportError::InvalidLink => String::from("некорректная ссылка"),
+        ImportError::InvalidTimeout => String::from("некорректное время ожидания"),
+        ImportError::InvalidExpectedCodes => String::from("некорректные коды ответа"),
+        ImportError::InvalidSlowThreshold => String::from("некорректный порог медленного ответа"),
+        ImportError::InvalidIgnoreRule(rule) => format!("некорректное правило игнорирования: {rule}"),
+        ImportError::InvalidMaintenanceWindow(window) => format!("некорректное окно обслуживания: {window}"),
+        ImportError::NameTooLong => format!("название длиннее {MAX_LINK_NAME_LENGTH} символов"),
+        ImportError::InvalidLabel(label) => format!("некорректная метка: {label}"),
+        ImportError::TooManyLabels => format!("больше {} меток", links_list::MAX_LABELS),
+        ImportError::InvalidEscalation => format!("задержка эскалации должна быть от 1 до {} минут", escalation::MAX_ESCALATION_MINUTES)
+    }
+}
+
+/// Cancels the import of links
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn cancel_importing_links(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Вы отменили импорт ссылок").await?;
+
+    dialogue.update(BotState::Default).await?;
+
+    Ok(())
+}
+
+/// Handles the callback for menu choice.
+///
+/// # Arguments
+///
+/// * `bot` - The bot instance.
+/// * `dialogue` - The dialogue instance.
+/// * `q` - The callback query.
+///
+/// # Returns
+///
+/// A `HandlerResult` indicating the success of the operation.
+///
+/// # Examples
+///
+/// ```rust
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let bot = Bot::new();
+///     let dialogue = MyDialogue::new();
+///     let q = CallbackQuery::new();
+///
+///     let result = menu_choice_callback_handler(bot, dialogue, q).await;
+/// }
+/// ```
+async fn menu_choice_callback_handler(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
+    if let Some(data) = &q.data {
+        if let Some(message) = q.clone().message {
+            let (action, argument) = data.split_once(':').unwrap_or((data.as_str(), ""));
+
+            match action {
+                "begin" => show_actions(bot, message, q).await?,
+                "check_link" => start_check_link(bot, dialogue, message, q).await?,
+                "get_links" => get_all_links_from_user(bot, q).await?,
+                "links" => {
+                    let view = LinksView::parse(argument).unwrap_or_default();
+
+                    edit_links_list(&bot, &message, &view).await?;
+                    bot.answer_callback_query(q.id).await?;
+                }
+                "links_search" => start_searching_links(bot, dialogue, message, q).await?,
+                "clear_all_links" => ask_about_clear_links(bot, dialogue, q).await?,
+                "delete_some_links" => start_deleting_some_links(bot, dialogue, q).await?,
+                "undo_delete" => undo_deleting_links(bot, q).await?,
+
+                "enter_links" => start_enter_links(bot, dialogue, message, q).await?,
+
+                "link_card" | "card_check" | "card_pause" | "card_settings" | "card_rename" | "card_labels" | "card_delete" | "card_delete_confirm" | "card_back" => {
+                    let action = action.to_string();
+                    let key = argument.to_string();
+
+                    link_card_callback_handler(bot, dialogue, message, q, &action, &key).await?
+                }
+                "label_pause" | "label_resume" | "label_check" | "label_delete" | "label_delete_confirm" => {
+                    let action = action.to_string();
+                    let label = argument.to_string();
+
+                    label_callback_handler(bot, message, q, &action, &label).await?
+                }
+
+                _ => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Event handler after the user clicks the button in the ReceiveConfirmRemoveHistories state,
+/// which determines whether to delete the history or not
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `q`: Response from the user after pressing the button
+async fn menu_confirm_remove_links_callback_handler(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
+    if let Some(data) = &q.data {
+        if let Some(message) = &q.message {
+            match data.as_str() {
+                "confirm" => clear_links(bot, dialogue, q).await?,
+                "cancel" => {
+                    bot.edit_message_text(message.chat.id, message.id, "Процесс очистки ссылок отменен.").await?;
+                    dialogue.update(BotState::Default).await?;
+
+                    show_main_menu(&bot, message).await?;
+
+                    bot.answer_callback_query(q.id).await?;
+                },
+
+                _ => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends a message to the user that displays the bot's action menu
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `msg`: Message sent by the user
+async fn show_actions(bot: Bot, msg: Message, q: CallbackQuery) -> HandlerResult {
+    show_main_menu(&bot, &msg).await?;
+    bot.answer_callback_query(q.id).await?;
+
+    Ok(())
+}
+
+/// Sends a message to the user that displays the main menu
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `msg`: Message sent by the user
+async fn show_main_menu(bot: &Bot, msg: &Message) -> HandlerResult {
+    let text = format!("{} к вашим услугам!\nЧто вы хотите сделать?", bot.get_me().await?.first_name);
+    let keyboard = create_main_menu_keyboard().await;
+
+    bot.send_message(msg.chat.id, text).reply_markup(keyboard).await?;
+
+    Ok(())
+}
+
+/// Sends a message to the user asking for confirmation to clear the history
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn ask_about_clear_links(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
+    let chat_id = q.chat_id().unwrap();
+    let histories = database::get_all_links_from_user(chat_id.0, None);
+
+    if histories.is_empty() {
+        bot.send_message(chat_id, "У вас нет ссылок для удаления").await?;
+        return Ok(());
+    }
+
+    let text = "❓ Вы действительно хотите очистить все сохраненные вами ссылки? ❓";
+    let keyboard = create_confirmation_menu_keyboard().await;
+
+    bot.send_message(q.chat_id().unwrap(), text).reply_markup(keyboard).await?;
+
+    dialogue.update(BotState::ReceiveConfirmRemoveLinks).await?;
+
+    bot.answer_callback_query(q.id).await?;
+
+    Ok(())
+}
+
+/// Sends a message to the user telling them to enter links and also goes to the ReceiveLink state
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn start_enter_links(bot: Bot, dialogue: SparkleDialogue, msg: Message, q: CallbackQuery) -> HandlerResult {
+    let text = "Пожалуйста, введите ссылки. Можно отправить несколько ссылок сразу, разделив их пробелами или переносами строк. Когда закончите, нажмите «Готово»";
+
+    // In a group the keyboard is sent with the result of the first links, since the prompt asks for a reply
+    if msg.chat.id.is_user() {
+        bot.send_message(msg.chat.id, text).reply_markup(create_done_keyboard()).await?;
+    }
+    else {
+        send_prompt(&bot, msg.chat.id, String::from(text)).await?;
+    }
+
+    dialogue.update(BotState::ReceiveLink).await?;
+
+    bot.answer_callback_query(q.id).await?;
+
+    Ok(())
+}
+
+/// Sends a message to the user that a link needs to be entered, and enters the
+/// ReceiveLinkForChecking state
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn start_check_link(bot: Bot, dialogue: SparkleDialogue, msg: Message, q: CallbackQuery) -> HandlerResult {
+    send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, введите ссылку. Для отмены ввода ссылки введите команду /cancel")).await?;
+    dialogue.update(BotState::ReceiveLinkForChecking).await?;
+
+    bot.answer_callback_query(q.id).await?;
+
+    Ok(())
+}
+
+/// Receives links from a user, validates them, and adds the valid ones to the database.
+///
+/// The links are separated by spaces or new lines. The user gets the result for each link and
+/// stays in the ReceiveLink state until they press the "Done" button.
+///
+/// # Arguments
+///
+/// * `bot` - A `Bot` object representing the Telegram bot.
+/// * `msg` - A `Message` object representing the received message.
+///
+/// # Returns
+///
+/// A `HandlerResult`, indicating the success or failure of the operation.
+async fn receive_link(bot: Bot, msg: Message) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    let texts: Vec<&str> = msg.text().unwrap_or_default().split_whitespace().collect();
+
+    if texts.is_empty() {
+        send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, введите ссылку.")).await?;
+        return Ok(());
+    }
+
+    let mut added: Vec<String> = Vec::new();
+    let mut text = String::from("Результат:\n");
+
+    for link in texts.iter().take(MAX_LINKS_PER_MESSAGE) {
+        let result = match normalize_link(link) {
+            Some(url) if added.contains(&url) || database::is_link_exists(chat_id.0, &url) => format!("🔁 {} — уже добавлена", shorten(&url, MAX_RESULT_LINK_LENGTH)),
+            Some(url) => {
+                database::add_link(chat_id.0, &url);
+
+                let result = format!("✅ {} — добавлена", shorten(&url, MAX_RESULT_LINK_LENGTH));
+                added.push(url);
+
+                result
+            }
+            None => format!("❌ {} — не является ссылкой", shorten(link, MAX_RESULT_LINK_LENGTH))
+        };
+
+        text = format!("{text}\n{result}");
+    }
+
+    if !added.is_empty() {
+        info!("Added {} new links to the database in the chat: {}", added.len(), chat_id);
+
+        text = format!("{text}\n\nТеперь я буду проверять добавленные ссылки каждый час");
+    }
+
+    if texts.len() > MAX_LINKS_PER_MESSAGE {
+        text = format!("{text}\n\nЗа один раз добавляется не больше {MAX_LINKS_PER_MESSAGE} ссылок, отправьте остальные следующим сообщением");
+    }
+
+    text = if chat_id.is_user() {
+        format!("{text}\n\nОтправьте еще ссылки или нажмите «Готово»")
+    }
+    else {
+        format!("{text}\n\nОтправьте еще ссылки ответом на это сообщение или нажмите «Готово»")
+    };
+
+    bot.send_message(msg.chat.id, text).reply_markup(create_done_keyboard()).await?;
+
+    Ok(())
+}
+
+/// Creates a keyboard with the button that finishes adding links
+fn create_done_keyboard() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback("Готово", "enter_links_done")]])
+}
+
+/// Finishes adding links after the user presses the "Done" button
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `q`: Response from the user after pressing the button
+async fn receive_link_callback_handler(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
+    if let (Some("enter_links_done"), Some(message)) = (q.data.as_deref(), &q.message) {
+        let _ = bot.edit_message_reply_markup(message.chat.id, message.id).await;
+
+        dialogue.update(BotState::Default).await?;
+
+        show_main_menu(&bot, message).await?;
+    }
+
+    bot.answer_callback_query(q.id).await?;
+
+    Ok(())
+}
+
+/// Checks the site received from the user and sends the information to the user
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn check_site(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    let mut url: String;
+
+    match msg.text() {
+        Some(text) => {
+            url = text.to_string();
+        }
+        None => {
+            send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, введите ссылку.")).await?;
+            return Ok(());
+        }
+    }
+
+    if !website::has_http_or_https(&url) {
+        url = format!("https://{}", url);
+    }
+
+    if is_url(&url) {
+        let send_message = bot.send_message(msg.chat.id, format!("Пожалуйста, подождите...\nМаксимальное время ответа - {} секунд", website::DEFAULT_TIMEOUT_SECONDS)).await?;
+
+        info!("Site information for the user is requested: {}", msg.from().expect("Unable to determine user ID").id.0);
+
+        let site_information = website::get_site_information(client::get_client(false), &url).await;
+
+        match site_information {
+            Ok(result) => {
+                save_analysis(msg.from().expect("Unable to determine user ID").id.0, &result);
+
+                let text = compile_site_information(result);
+                bot.edit_message_text(msg.chat.id, send_message.id, text).await?;
+            }
+            Err(e) => {
+                if e.is_timeout() {
+                    bot.edit_message_text(msg.chat.id, send_message.id, "Сайт слишком долго отвечал").await?;
+                }
+                else {
+                    bot.edit_message_text(msg.chat.id, send_message.id, "Боту не удалось проверить сайт").await?;
+                }
+            }
+        }
+
+        dialogue.update(BotState::Default).await?;
+
+        show_main_menu(&bot, &msg).await?;
+    }
+    else {
+        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+    }
+
+    Ok(())
+}
+
+/// Compiles the site information into a formatted string.
+///
+/// # Arguments
+///
+/// * `site_information` - The site information to compile.
+///
+/// # Returns
+///
+/// A string containing the compiled site information.
+///
+/// # Examples
+///
+/// ```
+/// use crate::SiteInformation;
+///
+/// let info = SiteInformation {
+///     url: String::from("https://example.com"),
+///     checked_at: 0,
+///     status_code: 200,
+///     duration: 100,
+///     robots: FileStatus::Found,
+///     sitemap: FileStatus::Missing { status_code: 404 },
+///     certificate: None,
+///     seo: SeoInformation::default(),
+///     performance: PerformanceInformation::default(),
+///     protocols: ProtocolSupport { http1: true, http2: false, http3: false },
+///     dns: DnsInformation::default(),
+///     address_families: vec![],
+///     mixed_content: Some(vec![]),
+///     security_headers: SecurityHeaders::default()
+/// };
+///
+/// let result = compile_site_information(info);
+/// ```
+fn compile_site_information(site_information: SiteInformation) -> String {
+    let mut text = String::from("❔ Информация о введеном вами сайте ❔\n\n");
+    let health_score = website::calculate_health_score(&site_information);
+
+    text = format!("{text}{}", compile_health_score(&health_score));
+
+    text = format!("{text}📝 Код ответа: {}\n", site_information.status_code);
+    text = format!("{text}🕔 Время ответа: {} милисекунд\n", site_information.duration);
+
+    let protocols = &site_information.protocols;
+    let supported: Vec<&str> = [(protocols.http1, "HTTP/1.1"), (protocols.http2, "HTTP/2"), (protocols.http3, "HTTP/3")].iter()
+        .filter(|(is_supported, _)| *is_supported)
+        .map(|(_, name)| *name)
+        .collect();
+
+    text = format!("{text}🔌 Протоколы: {}\n", if supported.is_empty() { String::from("не определены") } else { supported.join(", ") });
+
+    if !protocols.http2 && !protocols.http3 {
+        text = format!("{text}⚠️ Рекомендуется включить поддержку HTTP/2 или HTTP/3\n");
+    }
+
+    text = format!("{text}🤖 Наличие robots.txt: {}\n", report::describe_file_status(site_information.robots));
+    text = format!("{text}🗺 Наличие sitemap.xml: {}\n\n", report::describe_file_status(site_information.sitemap));
+
+    match &site_information.certificate {
+        Some(cert) => {
+            text = format!("{text}📄 Сертификат:\
+                \nОбщее название: {}\
+                \nОрганизация: {}\
+                \nСтрана: {}\
+                \nИздатель: {}\
+                \nДействителен: {}\
+                \nДней до окончания: {}\n\n", cert.common_name,
+                           cert.organization,
+                           cert.country,
+                           cert.issuer,
+                           if cert.is_valid { "да" } else { "нет" },
+                           cert.days_left());
+        }
+        None => {
+            text = format!("{text}📄 Сертификат: не найден\n\n")
+        }
+    }
+
+    if !site_information.address_families.is_empty() {
+        text = format!("{text}📶 IPv4 и IPv6:\n");
+
+        for check in &site_information.address_families {
+            text = format!("{text}{}\n", describe_family_check(check));
+        }
+
+        text = format!("{text}\n");
+    }
+
+    if let Some(mixed_content) = &site_information.mixed_content {
+        text = format!("{text}{}", compile_mixed_content(mixed_content));
+    }
+
+    text = format!("{text}{}", compile_security_headers(&site_information.security_headers));
+    text = format!("{text}{}", compile_dns_information(&site_information.dns));
+    text = format!("{text}{}", compile_performance_information(&site_information.performance));
+    text = format!("{text}{}", compile_seo_information(&site_information.seo));
+
+    if !health_score.recommendations.is_empty() {
+        text = format!("{text}\n🛠 Что исправить в первую очередь:\n");
+
+        for (i, recommendation) in health_score.recommendations.iter().enumerate() {
+            text = format!("{text}{}. {}\n", i + 1, report::describe_recommendation(*recommendation));
+        }
+    }
+
+    text
+}
+
+/// Compiles the overall score of the site and the grades of its sections into a formatted string.
+///
+/// # Arguments
 ///
-/// ```
-/// use mybot::Bot;
-/// use mybot::Message;
-/// use mybot::HandlerResult;
+/// * `health_score` - The health score of the site.
 ///
-/// #[tokio::main]
-/// async fn main() {
-///     let bot = Bot::new();
-///     let msg = Message::new();
-///     let link = "https://example.com".to_string();
-///     let result = check_site(bot, msg, link).await;
-///     assert!(result.is_ok());
-/// }
-/// ```
-async fn check_site_command(bot: Bot, msg: Message, link: String) -> HandlerResult {
-    let mut url = link;
+/// # Returns
+///
+/// A string containing the compiled score.
+fn compile_health_score(health_score: &HealthScore) -> String {
+    let mut text = format!("🏆 Оценка сайта: {} ({}/100)\n", health_score.grade, health_score.score);
 
-    if !website::has_http_or_https(&url) {
-        url = format!("https://{}", url);
+    for section in &health_score.sections {
+        text = format!("{text}{}: {} ({})\n", report::describe_section(section.section), section.grade, section.score);
     }
 
-    if is_url(&url) {
-        let send_message = bot.send_message(msg.chat.id, "Пожалуйста, подождите...\nМаксимальное время ответа - 15 секунд").await?;
+    format!("{text}\n")
+}
 
-        info!("Site information for the user is requested: {}", msg.from().expect("Unable to determine user ID").id.0);
+/// Compiles the security headers of the main page into a formatted string.
+///
+/// # Arguments
+///
+/// * `headers` - The security headers of the main page response.
+///
+/// # Returns
+///
+/// A string containing the compiled security headers.
+fn compile_security_headers(headers: &SecurityHeaders) -> String {
+    let mut text = String::from("🛡 Заголовки безопасности:\n");
+
+    for (name, value) in [
+        ("Strict-Transport-Security", &headers.strict_transport_security),
+        ("Content-Security-Policy", &headers.content_security_policy),
+        ("X-Content-Type-Options", &headers.x_content_type_options),
+        ("X-Frame-Options", &headers.x_frame_options),
+        ("Referrer-Policy", &headers.referrer_policy),
+        ("Permissions-Policy", &headers.permissions_policy)
+    ] {
+        text = format!("{text}{} {name}\n", if value.is_some() { "✅" } else { "❌" });
+    }
 
-        let site_information = website::get_site_information(&url).await;
+    format!("{text}\n")
+}
 
-        match site_information {
-            Ok(result) => {
-                let text = compile_site_information(result);
-                bot.edit_message_text(msg.chat.id, send_message.id, text).await?;
+/// Compiles the resources loaded over HTTP on an HTTPS page into a formatted string.
+///
+/// # Arguments
+///
+/// * `mixed_content` - The insecure resources found on the page.
+///
+/// # Returns
+///
+/// A string containing the compiled list of insecure resources.
+fn compile_mixed_content(mixed_content: &[MixedContent]) -> String {
+    if mixed_content.is_empty() {
+        return String::from("🔒 Смешанное содержимое: не найдено\n\n");
+    }
+
+    let mut text = format!("⚠️ Смешанное содержимое: {} ресурсов загружаются по HTTP\n", mixed_content.len());
+
+    for content in mixed_content.iter().take(MAX_MIXED_CONTENT) {
+        let kind = match content.kind {
+            MixedContentKind::Script => "скрипт",
+            MixedContentKind::Stylesheet => "стили",
+            MixedContentKind::Iframe => "iframe",
+            MixedContentKind::Image => "изображение",
+            MixedContentKind::Form => "отправка формы"
+        };
+
+        text = format!("{text}{}{kind}: {}\n", if content.kind.is_blocked() { "⛔️ " } else { "" }, content.url);
+    }
+
+    if mixed_content.len() > MAX_MIXED_CONTENT {
+        text = format!("{text}...и еще {}\n", mixed_content.len() - MAX_MIXED_CONTENT);
+    }
+
+    if mixed_content.iter().any(|content| content.kind.is_blocked()) {
+        text = format!("{text}⛔️ - браузеры блокируют такие ресурсы, перейдите на HTTPS\n");
+    }
+
+    format!("{text}\n")
+}
+
+/// Compiles the DNS records of the host into a formatted string with warnings about problems.
+///
+/// # Arguments
+///
+/// * `dns` - The DNS records of the host.
+///
+/// # Returns
+///
+/// A string containing the compiled DNS records.
+fn compile_dns_information(dns: &DnsInformation) -> String {
+    let mut text = format!("🌐 DNS (время ответа: {} милисекунд):\n", dns.duration);
+
+    let records = [("A", &dns.a), ("AAAA", &dns.aaaa), ("CNAME", &dns.cname), ("MX", &dns.mx), ("NS", &dns.ns), ("TXT", &dns.txt), ("CAA", &dns.caa)];
+
+    for (record_type, values) in records {
+        if !values.is_empty() {
+            text = format!("{text}{record_type}: {}\n", values.join(", "));
+        }
+    }
+
+    for warning in dns::find_dns_warnings(dns) {
+        let description = match warning {
+            DnsWarning::NoIpv6 => "Нет записей AAAA, сайт недоступен по IPv6",
+            DnsWarning::NoCaa => "Нет записей CAA, сертификат для домена может выпустить любой удостоверяющий центр",
+            DnsWarning::NoSpf => "Нет записи SPF, письма от имени домена может отправлять кто угодно",
+            DnsWarning::MultipleSpf => "Найдено несколько записей SPF, из-за этого SPF недействителен",
+            DnsWarning::PermissiveSpf => "Запись SPF разрешает отправку писем с любых серверов (+all)",
+            DnsWarning::NoDmarc => "Нет записи DMARC",
+            DnsWarning::DmarcPolicyNone => "Политика DMARC p=none не защищает от подделки писем"
+        };
+
+        text = format!("{text}⚠️ {description}\n");
+    }
+
+    format!("{text}\n")
+}
+
+/// Compiles the size, compression and caching information of the page into a formatted string.
+///
+/// # Arguments
+///
+/// * `performance` - The performance information of the page.
+///
+/// # Returns
+///
+/// A string containing the compiled performance information.
+fn compile_performance_information(performance: &PerformanceInformation) -> String {
+    let mut text = String::from("⚖️ Производительность:\n");
+
+    text = format!("{text}Размер HTML: {}\n", format_size(performance.html_size));
+
+    if performance.compression.is_empty() {
+        text = format!("{text}⚠️ Сжатие: не поддерживается (рекомендуется gzip, brotli или zstd)\n");
+    }
+    else {
+        let compression: Vec<String> = performance.compression.iter()
+            .map(|compressed| format!("{} ({})", compressed.encoding, format_size(compressed.size)))
+            .collect();
+
+        text = format!("{text}Сжатие: {}\n", compression.join(", "));
+    }
+
+    text = format!("{text}Cache-Control: {}\n", performance.cache_control.as_deref().unwrap_or("нет"));
+    text = format!("{text}ETag: {}\n", if performance.etag.is_some() { "есть" } else { "нет" });
+    text = format!("{text}Last-Modified: {}\n", performance.last_modified.as_deref().unwrap_or("нет"));
+
+    if performance.cache_control.is_none() && performance.etag.is_none() && performance.last_modified.is_none() {
+        text = format!("{text}⚠️ Сервер не передает заголовки кэширования\n");
+    }
+
+    format!("{text}\n")
+}
+
+/// Formats the size in bytes into a human-readable string
+///
+/// # Arguments
+///
+/// * `size`: Size in bytes
+fn format_size(size: usize) -> String {
+    match size {
+        0..=1023 => format!("{size} Б"),
+        1024..=1048575 => format!("{:.1} КБ", size as f64 / 1024.0),
+        _ => format!("{:.1} МБ", size as f64 / 1048576.0)
+    }
+}
+
+/// Compiles the SEO metadata of the page into a formatted string with warnings about problems.
+///
+/// # Arguments
+///
+/// * `seo` - The SEO metadata of the page.
+///
+/// # Returns
+///
+/// A string containing the compiled SEO metadata.
+fn compile_seo_information(seo: &SeoInformation) -> String {
+    let mut text = String::from("🔎 SEO:\n");
+
+    match &seo.title {
+        Some(title) => {
+            let length = title.chars().count();
+            text = format!("{text}Заголовок: {title} ({length} символов)\n");
+
+            if !RECOMMENDED_TITLE_LENGTH.contains(&length) {
+                text = format!("{text}⚠️ Рекомендуемая длина заголовка: {}-{} символов\n", RECOMMENDED_TITLE_LENGTH.start(), RECOMMENDED_TITLE_LENGTH.end());
             }
-            Err(e) => {
-                if e.is_timeout() {
-                    bot.edit_message_text(msg.chat.id, send_message.id, "Сайт слишком долго отвечал").await?;
-                }
-                else {
-                    bot.edit_message_text(msg.chat.id, send_message.id, "Боту не удалось проверить сайт").await?;
-                }
+        }
+        None => {
+            text = format!("{text}⚠️ Заголовок: нет\n");
+        }
+    }
+
+    match &seo.description {
+        Some(description) => {
+            let length = description.chars().count();
+            text = format!("{text}Описание (meta description): {length} символов\n");
+
+            if !RECOMMENDED_DESCRIPTION_LENGTH.contains(&length) {
+                text = format!("{text}⚠️ Рекомендуемая длина описания: {}-{} символов\n", RECOMMENDED_DESCRIPTION_LENGTH.start(), RECOMMENDED_DESCRIPTION_LENGTH.end());
             }
         }
+        None => {
+            text = format!("{text}⚠️ Описание (meta description): нет\n");
+        }
     }
-    else {
-        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+
+    text = format!("{text}Канонический URL: {}\n", seo.canonical.as_deref().unwrap_or("нет"));
+
+    if !seo.hreflang.is_empty() {
+        text = format!("{text}Языковые версии (hreflang): {}\n", seo.hreflang.join(", "));
     }
 
-    Ok(())
+    text = format!("{text}OpenGraph: {}\n", if seo.open_graph.is_empty() { String::from("нет") } else { seo.open_graph.join(", ") });
+    text = format!("{text}Twitter Card: {}\n", if seo.twitter_card.is_empty() { String::from("нет") } else { seo.twitter_card.join(", ") });
+
+    let headings: Vec<String> = seo.headings.iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(level, count)| format!("H{}: {count}", level + 1))
+        .collect();
+
+    text = format!("{text}Заголовки: {}\n", if headings.is_empty() { String::from("нет") } else { headings.join(", ") });
+
+    match seo.headings[0] {
+        0 => text = format!("{text}⚠️ На странице нет заголовка H1\n"),
+        1 => (),
+        count => text = format!("{text}⚠️ На странице {count} заголовков H1, рекомендуется один\n")
+    }
+
+    if seo.noindex {
+        text = format!("{text}⚠️ Страница закрыта от индексации (noindex)\n");
+    }
+
+    if seo.images_without_alt > 0 {
+        text = format!("{text}⚠️ Изображений без атрибута alt: {} из {}\n", seo.images_without_alt, seo.images);
+    }
+
+    format!("{text}\n")
 }
 
-/// Handles the callback for menu choice.
+/// Function to cancel receiving a link in a Telegram chat.
 ///
 /// # Arguments
 ///
-/// * `bot` - The bot instance.
-/// * `dialogue` - The dialogue instance.
-/// * `q` - The callback query.
+/// * `bot` - An instance of the `Bot` struct providing methods to interact with the Telegram Bot API.
+/// * `dialogue` - An instance of the `MyDialogue` struct representing the conversation state.
+/// * `msg` - The `Message` struct representing the incoming message in the chat.
 ///
 /// # Returns
 ///
-/// A `HandlerResult` indicating the success of the operation.
+/// An `HandlerResult` which is an alias for `Result<(), Error>` indicating the success or failure of the operation.
 ///
 /// # Examples
 ///
+/// ```rust,no_run
+/// use your_crate::{Bot, MyDialogue, Message, HandlerResult};
+/// use tokio::runtime::Runtime;
+///
+/// let bot = Bot::new("your_bot_token");
+/// let dialogue = MyDialogue::create();
+/// let msg = Message::new("your_chat_id", "your_message");
+///
+/// let mut rt = Runtime::new().unwrap();
+///
+/// let result = rt.block_on(cancel_receive_link(bot, dialogue, msg));
+///
+/// assert!(result.is_ok());
+/// ```
+async fn cancel_receive_link(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Вы отменили ввод ссылки").await?;
+
+    dialogue.update(BotState::Default).await?;
+
+    Ok(())
+}
+
+/// Cancels the process of deleting some links.
+///
+/// # Arguments
+///
+/// * `bot` - The `Bot` instance to send a message.
+/// * `dialogue` - The `MyDialogue` instance to update the state.
+/// * `msg` - The `Message` object that triggered the cancellation.
+async fn cancel_deleting_some_links(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Вы отменили удаление ссылок").await?;
+
+    dialogue.update(BotState::Default).await?;
+
+    Ok(())
+}
+
+// Creating bot menus
+
+/// Creates the beginning menu keyboard.
+///
+/// This async function creates an instance of `InlineKeyboardMarkup` that represents
+/// a menu keyboard with a single button labeled "Приступим!" and a callback value of "begin".
+///
+/// # Example
 /// ```rust
-/// use tokio::sync::mpsc;
+/// use telegram_bot::InlineKeyboardMarkup;
+/// use telegram_bot::InlineKeyboardButton;
 ///
-/// #[tokio::main]
-/// async fn main() {
-///     let bot = Bot::new();
-///     let dialogue = MyDialogue::new();
-///     let q = CallbackQuery::new();
+/// async fn create_beginning_menu_keyboard() -> InlineKeyboardMarkup {
+///     let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
 ///
-///     let result = menu_choice_callback_handler(bot, dialogue, q).await;
+///     let begin = InlineKeyboardButton::callback("Приступим!", "begin");
+///
+///     keyboard.push(vec![begin]);
+///
+///     InlineKeyboardMarkup::new(keyboard)
 /// }
 /// ```
-async fn menu_choice_callback_handler(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
-    if let Some(data) = &q.data {
-        if let Some(message) = q.clone().message {
-            match data.as_str() {
-                "begin" => show_actions(bot, message, q).await?,
-                "check_link" => start_check_link(bot, dialogue, message, q).await?,
-                "get_links" => get_all_links_from_user(bot, q).await?,
-                "clear_all_links" => ask_about_clear_links(bot, dialogue, q).await?,
-                "delete_some_links" => start_deleting_some_links(bot, dialogue, q).await?,
+async fn create_beginning_menu_keyboard() -> InlineKeyboardMarkup {
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
 
-                "enter_links" => start_enter_links(bot, dialogue, message, q).await?,
+    let begin = InlineKeyboardButton::callback("Приступим!", "begin");
 
-                _ => (),
-            }
-        }
-    }
+    keyboard.push(vec![begin]);
+
+    InlineKeyboardMarkup::new(keyboard)
+}
+
+/// Creates the main menu keyboard with inline buttons.
+///
+/// # Returns
+///
+/// Returns an `InlineKeyboardMarkup` object representing the main menu keyboard.
+async fn create_main_menu_keyboard() -> InlineKeyboardMarkup {
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
+
+    let check_link = InlineKeyboardButton::callback("🔭 Проанализировать сайт 🔭", "check_link");
+    let enter_links = InlineKeyboardButton::callback("✏️ Добавить ссылки ✏️", "enter_links");
+    let links = InlineKeyboardButton::callback("📒 Мои ссылки 📒", "get_links");
+
+    keyboard.push(vec![check_link]);
+    keyboard.push(vec![enter_links]);
+    keyboard.push(vec![links]);
+
+    InlineKeyboardMarkup::new(keyboard)
+}
+
+/// Creates an inline keyboard markup for a confirmation menu.
+///
+/// The resulting inline keyboard will have two buttons: "Очистить" (clear) and "Отмена" (cancel).
+///
+/// # Returns
+///
+/// The resulting inline keyboard markup.
+async fn create_confirmation_menu_keyboard() -> InlineKeyboardMarkup {
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
+
+    let confirmation = InlineKeyboardButton::callback("Очистить", "confirm");
+    let cancel = InlineKeyboardButton::callback("Отмена", "cancel");
+
+    keyboard.push(vec![confirmation]);
+    keyboard.push(vec![cancel]);
 
-    Ok(())
+    InlineKeyboardMarkup::new(keyboard)
 }
+//
 
-/// Event handler after the user clicks the button in the ReceiveConfirmRemoveHistories state,
-/// which determines whether to delete the history or not
+/// Retrieves all saved links by user ID and sends a message with the result
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
-/// * `dialogue`: A handle for controlling dialogue state
 /// * `q`: Response from the user after pressing the button
-async fn menu_confirm_remove_links_callback_handler(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
-    if let Some(data) = &q.data {
-        if let Some(message) = &q.message {
-            match data.as_str() {
-                "confirm" => clear_links(bot, dialogue, q).await?,
-                "cancel" => {
-                    bot.edit_message_text(message.chat.id, q.message.clone().unwrap().id, "Процесс очистки ссылок отменен.").await?;
-                    dialogue.update(BotState::Default).await?;
-
-                    show_main_menu(&bot, &message).await?;
+async fn get_all_links_from_user(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    info!("Receiving a request for all links from the user: {}", q.from.id);
 
-                    bot.answer_callback_query(q.id).await?;
-                },
+    send_links_list(&bot, q.chat_id().unwrap(), &LinksView::default()).await?;
 
-                _ => (),
-            }
-        }
-    }
+    bot.answer_callback_query(q.id).await?;
 
     Ok(())
 }
 
-/// Sends a message to the user that displays the bot's action menu
+/// Sends the user the first page of their links found by the query
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
 /// * `msg`: Message sent by the user
-async fn show_actions(bot: Bot, msg: Message, q: CallbackQuery) -> HandlerResult {
-    show_main_menu(&bot, &msg).await?;
-    bot.answer_callback_query(q.id).await?;
+/// * `query`: Text that the link or its name must contain, the list is not filtered if it is empty
+async fn links_command(bot: Bot, msg: Message, query: String) -> HandlerResult {
+    let query = query.trim();
 
-    Ok(())
+    if query.len() > links_list::MAX_QUERY_LENGTH {
+        bot.send_message(msg.chat.id, "Слишком длинный запрос, сократите его").await?;
+        return Ok(());
+    }
+
+    let view = LinksView { query: query.to_string(), ..Default::default() };
+
+    send_links_list(&bot, msg.chat.id, &view).await
 }
 
-/// Sends a message to the user that displays the main menu
+/// Sends a new message with a page of the links of the chat
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
-/// * `msg`: Message sent by the user
-async fn show_main_menu(bot: &Bot, msg: &Message) -> HandlerResult {
-    let text = format!("{} к вашим услугам!\nЧто вы хотите сделать?", bot.get_me().await?.first_name);
-    let keyboard = create_main_menu_keyboard().await;
+/// * `chat_id`: The chat that owns the links
+/// * `view`: The shown page, the sort and the search query
+async fn send_links_list(bot: &Bot, chat_id: ChatId, view: &LinksView) -> HandlerResult {
+    let links = database::get_all_links_from_user(chat_id.0, None);
 
-    bot.send_message(msg.chat.id, text).reply_markup(keyboard).await?;
+    if links.is_empty() {
+        bot.send_message(chat_id, "У вас нет сохраненных ссылок").await?;
+    }
+    else {
+        let (text, keyboard) = create_links_list(&links, view);
+        bot.send_message(chat_id, text).reply_markup(keyboard).await?;
+    }
 
     Ok(())
 }
 
-/// Sends a message to the user asking for confirmation to clear the history
+/// Shows another page of the links of the chat in the message with the list or the card
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
-/// * `dialogue`: A handle for controlling dialogue state
-/// * `msg`: Message sent by the user
-async fn ask_about_clear_links(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
-    let user_id = q.from.id;
-    let histories = database::get_all_links_from_user(user_id.0, None);
+/// * `message`: The message with the list or the card
+/// * `view`: The shown page, the sort and the search query
+async fn edit_links_list(bot: &Bot, message: &Message, view: &LinksView) -> HandlerResult {
+    let links = database::get_all_links_from_user(message.chat.id.0, None);
 
-    if histories.iter().count() == 0 {
-        bot.send_message(user_id, "У вас нет ссылок для удаления").await?;
-        return Ok(());
+    if links.is_empty() {
+        bot.edit_message_text(message.chat.id, message.id, "У вас нет сохраненных ссылок").await?;
     }
+    else {
+        let (text, keyboard) = create_links_list(&links, view);
 
-    let text = "❓ Вы действительно хотите очистить все сохраненные вами ссылки? ❓";
-    let keyboard = create_confirmation_menu_keyboard().await;
+        // The message is not modified when the user presses the button of the current page
+        let _ = bot.edit_message_text(message.chat.id, message.id, text).reply_markup(keyboard).await;
+    }
 
-    bot.send_message(q.chat_id().unwrap(), text).reply_markup(keyboard).await?;
+    Ok(())
+}
 
-    dialogue.update(BotState::ReceiveConfirmRemoveLinks).await?;
+/// Creates the text and the keyboard of a page of the list of links
+///
+/// Each link keeps its number in the full list, which is used in the commands. The callback data
+/// of the link button contains the key of the link, so that it opens the same link even if the
+/// list has changed since it was shown.
+///
+/// # Arguments
+///
+/// * `links`: All links of the user
+/// * `view`: The shown page, the sort and the search query
+fn create_links_list(links: &[Links], view: &LinksView) -> (String, InlineKeyboardMarkup) {
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
 
-    bot.answer_callback_query(q.id).await?;
+    let selected = links_list::select_links(links, view);
+    let pages = selected.len().div_ceil(LINKS_PER_PAGE).max(1);
+    let page = view.page.min(pages - 1);
 
-    Ok(())
+    let mut text = if view.query.is_empty() {
+        String::from(LINKS_LIST_TEXT)
+    }
+    else {
+        format!("🔎 Найдено ссылок по запросу «{}»: {}", view.query, selected.len())
+    };
+
+    if !selected.is_empty() {
+        text = format!("{text}\n");
+    }
+
+    for (index, link) in selected.iter().skip(page * LINKS_PER_PAGE).take(LINKS_PER_PAGE) {
+        let mark = get_status_mark(link);
+
+        let title = match &link.name {
+            Some(name) => format!("{name} ({})", shorten(&link.link, MAX_LIST_LINK_LENGTH)),
+            None => shorten(&link.link, MAX_LIST_LINK_LENGTH)
+        };
+
+        text = format!("{text}\n[{}] {mark} {title}", index + 1);
+
+        for label in &link.labels {
+            text = format!("{text} #{label}");
+        }
+
+        keyboard.push(vec![InlineKeyboardButton::callback(format!("{mark} {}", shorten(get_link_title(link), MAX_BUTTON_LINK_LENGTH)), format!("link_card:{}", links_list::get_link_key(&link.link)))]);
+    }
+
+    if pages > 1 {
+        let switch = |page: usize| LinksView { page, ..view.clone() }.to_callback_data("links");
+
+        let previous = InlineKeyboardButton::callback("◀️", switch((page + pages - 1) % pages));
+        let current = InlineKeyboardButton::callback(format!("{}/{}", page + 1, pages), switch(page));
+        let next = InlineKeyboardButton::callback("▶️", switch((page + 1) % pages));
+
+        keyboard.push(vec![previous, current, next]);
+    }
+
+    let sort = match view.sort {
+        LinksSort::Added => "по дате добавления",
+        LinksSort::Name => "по названию",
+        LinksSort::Status => "по статусу"
+    };
+
+    keyboard.push(vec![
+        InlineKeyboardButton::callback("🔎 Поиск", "links_search"),
+        InlineKeyboardButton::callback(format!("↕️ {sort}"), LinksView { page: 0, sort: view.sort.next(), query: view.query.clone() }.to_callback_data("links"))
+    ]);
+
+    if !view.query.is_empty() {
+        keyboard.push(vec![InlineKeyboardButton::callback("✖️ Сбросить поиск", LinksView { sort: view.sort, ..Default::default() }.to_callback_data("links"))]);
+    }
+
+    // Actions with all links with the label are offered when the list is filtered only by one label
+    let (labels, query) = links_list::parse_query(&view.query);
+
+    if let [label] = labels.as_slice() {
+        if query.is_empty() && !selected.is_empty() {
+            keyboard.push(vec![
+                InlineKeyboardButton::callback("⏸ Приостановить все", format!("label_pause:{label}")),
+                InlineKeyboardButton::callback("▶️ Возобновить все", format!("label_resume:{label}"))
+            ]);
+            keyboard.push(vec![
+                InlineKeyboardButton::callback("🔄 Проверить все", format!("label_check:{label}")),
+                InlineKeyboardButton::callback("🗑 Удалить все", format!("label_delete:{label}"))
+            ]);
+        }
+    }
+
+    keyboard.push(vec![InlineKeyboardButton::callback("✂️ Удалить несколько ссылок", "delete_some_links")]);
+    keyboard.push(vec![InlineKeyboardButton::callback("❌ Очистить все ссылки", "clear_all_links")]);
+
+    (text, InlineKeyboardMarkup::new(keyboard))
+}
+
+/// Returns the mark of the link status at the last check
+fn get_status_mark(link: &Links) -> &'static str {
+    match (link.paused, link.last_up) {
+        (true, _) => "⏸",
+        (false, Some(true)) => "✅",
+        (false, Some(false)) => "❌",
+        (false, None) => "❔"
+    }
 }
 
-/// Sends a message to the user telling them to enter a link and also goes to the ReceiveLink state
+/// Sends a message to the user telling them to enter a text to search for in the links and
+/// goes to the SearchingLinks state
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
 /// * `dialogue`: A handle for controlling dialogue state
-/// * `msg`: Message sent by the user
-async fn start_enter_links(bot: Bot, dialogue: SparkleDialogue, msg: Message, q: CallbackQuery) -> HandlerResult {
-    bot.send_message(msg.chat.id, "Пожалуйста, введите ссылку. Для отмены ввода ссылки введите команду /cancel").await?;
-    dialogue.update(BotState::ReceiveLink).await?;
+/// * `msg`: Message with the list of links
+/// * `q`: Response from the user after pressing the button
+async fn start_searching_links(bot: Bot, dialogue: SparkleDialogue, msg: Message, q: CallbackQuery) -> HandlerResult {
+    send_prompt(&bot, msg.chat.id, String::from("Введите часть ссылки или ее названия. Для отмены поиска введите команду /cancel")).await?;
+    dialogue.update(BotState::SearchingLinks).await?;
 
     bot.answer_callback_query(q.id).await?;
 
     Ok(())
 }
 
-/// Sends a message to the user that a link needs to be entered, and enters the
-/// ReceiveLinkForChecking state
+/// Receives a text to search for from the user and sends the found links
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
 /// * `dialogue`: A handle for controlling dialogue state
 /// * `msg`: Message sent by the user
-async fn start_check_link(bot: Bot, dialogue: SparkleDialogue, msg: Message, q: CallbackQuery) -> HandlerResult {
-    bot.send_message(msg.chat.id, "Пожалуйста, введите ссылку. Для отмены ввода ссылки введите команду /cancel").await?;
-    dialogue.update(BotState::ReceiveLinkForChecking).await?;
+async fn receive_links_query(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    let Some(query) = msg.text().map(str::trim).filter(|query| !query.is_empty()) else {
+        send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, введите текст для поиска.")).await?;
+        return Ok(());
+    };
 
-    bot.answer_callback_query(q.id).await?;
+    if query.len() > links_list::MAX_QUERY_LENGTH {
+        send_prompt(&bot, msg.chat.id, String::from("Слишком длинный запрос, сократите его")).await?;
+        return Ok(());
+    }
 
-    Ok(())
+    dialogue.update(BotState::Default).await?;
+
+    let view = LinksView { query: query.to_string(), ..Default::default() };
+
+    send_links_list(&bot, msg.chat.id, &view).await
 }
 
-/// Receives a link from a user, validates it, and adds it to the database if it is a valid URL.
+/// Cancels the search for links
 ///
 /// # Arguments
 ///
-/// * `bot` - A `Bot` object representing the Telegram bot.
-/// * `dialogue` - A `MyDialogue` object for handling the conversation flow.
-/// * `msg` - A `Message` object representing the received message.
-///
-/// # Returns
-///
-/// A `HandlerResult`, indicating the success or failure of the operation.
-///
-/// # Examples
-///
-/// ```
-/// # use my_bot::{Bot, MyDialogue, Message, HandlerResult};
-/// # use my_bot::website;
-/// # use my_bot::database;
-/// # use log::info;
-/// # use std::error::Error;
-/// async fn receive_link(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
-///     // Code implementation
-///     Ok(())
-/// }
-/// ```
-async fn receive_link(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
-    let user_id = msg.from().expect("Unable to determine user ID").id;
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn cancel_searching_links(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Вы отменили поиск ссылок").await?;
 
-    let mut url: String;
+    dialogue.update(BotState::Default).await?;
 
-    match msg.text() {
-        Some(text) => {
-            url = text.to_string();
-        }
-        None => {
-            bot.send_message(msg.chat.id, "Пожалуйста, введите ссылку.").await?;
-            return Ok(());
-        }
-    }
+    Ok(())
+}
 
-    if !website::has_http_or_https(&url) {
-        url = format!("https://{}", url);
-    }
+/// Returns the name of the link, or the link itself if it has no name
+fn get_link_title(link: &Links) -> &str {
+    link.name.as_deref().unwrap_or(&link.link)
+}
 
-    if is_url(&url) {
-        if database::is_link_exists(user_id.0, &url) {
-            bot.send_message(msg.chat.id, "Данная ссылка уже была добавлена. Пожалуйста, введите другую").await?;
+/// Returns the name of the link followed by the link itself, or only the link if it has no name
+fn describe_link(link: &Links) -> String {
+    match &link.name {
+        Some(name) => format!("{name} ({})", link.link),
+        None => link.link.clone()
+    }
+}
 
-            return Ok(());
-        }
+/// Shortens the text to the maximum number of characters
+fn shorten(text: &str, max_length: usize) -> String {
+    if text.chars().count() > max_length {
+        format!("{}...", text.chars().take(max_length).collect::<String>())
+    }
+    else {
+        text.to_string()
+    }
+}
 
-        database::add_link(user_id.0, &url);
+/// Describes the state of the link: its status at the last check, the time of that check and uptime
+///
+/// # Arguments
+///
+/// * `link`: The link with its saved state
+fn create_link_card(link: &Links) -> String {
+    let mut text = match &link.name {
+        Some(name) => format!("🔗 {name}\n{}\n\n", link.link),
+        None => format!("🔗 {}\n\n", link.link)
+    };
+
+    let status = match (link.last_up, link.last_status_code, link.last_duration) {
+        (Some(true), Some(status_code), Some(duration)) => format!("✅ работает (код ответа {status_code}, {duration} милисекунд)"),
+        (Some(true), None, Some(duration)) => format!("✅ работает ({duration} милисекунд)"),
+        (Some(true), _, _) => String::from("✅ работает"),
+        (Some(false), Some(status_code), _) => format!("❌ не работает (код ответа {status_code})"),
+        (Some(false), None, _) => String::from("❌ не работает"),
+        (None, _, _) => String::from("❔ еще не проверялась")
+    };
+
+    text = format!("{text}Статус: {status}\n");
+
+    if !link.labels.is_empty() {
+        text = format!("{text}🏷 Метки: {}\n", link.labels.join(", "));
+    }
 
-        info!("Added a new link to the database from the user: {}", user_id);
+    if link.paused {
+        text = format!("{text}⏸ Проверка приостановлена\n");
+    }
 
-        bot.send_message(msg.chat.id, "Спасибо за ссылку! Теперь я буду проверять эту ссылку каждый час").await?;
+    if let Some(maintenance_since) = link.maintenance_since {
+        text = format!("{text}🛠 Идет обслуживание с {}, оповещения отключены\n", compare::format_date(maintenance_since as i64));
+    }
 
-        dialogue.update(BotState::Default).await?;
+    text = format!("{text}Последняя проверка: {}\n", link.last_checked.map_or(String::from("нет"), |checked_at| compare::format_date(checked_at as i64)));
 
-        show_main_menu(&bot, &msg).await?;
-    }
-    else {
-        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+    if link.checks_total > 0 {
+        let uptime = link.checks_up as f64 * 100.0 / link.checks_total as f64;
+        text = format!("{text}Доступность: {uptime:.1}% ({} из {} проверок)\n", link.checks_up, link.checks_total);
     }
 
-    Ok(())
+    text
 }
 
-/// Checks the site received from the user and sends the information to the user
+/// Creates a keyboard with the actions for one link
+///
+/// The callback data of the buttons contains the key of the link, so that a card shown before the
+/// list of links changed still acts on its own link.
+///
+/// # Arguments
+///
+/// * `link`: The link
+fn create_link_card_keyboard(link: &Links) -> InlineKeyboardMarkup {
+    let pause = if link.paused { "▶️ Возобновить" } else { "⏸ Приостановить" };
+    let key = links_list::get_link_key(&link.link);
+
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("🔄 Проверить сейчас", format!("card_check:{key}")),
+            InlineKeyboardButton::callback(pause, format!("card_pause:{key}"))
+        ],
+        vec![
+            InlineKeyboardButton::callback("⚙️ Настройки", format!("card_settings:{key}")),
+            InlineKeyboardButton::callback("✏️ Переименовать", format!("card_rename:{key}"))
+        ],
+        vec![
+            InlineKeyboardButton::callback("🏷 Метки", format!("card_labels:{key}")),
+            InlineKeyboardButton::callback("🗑 Удалить", format!("card_delete:{key}"))
+        ],
+        vec![InlineKeyboardButton::callback("◀️ К списку ссылок", format!("card_back:{key}"))]
+    ])
+}
+
+/// Handles the buttons of the list of links and of the link card
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
 /// * `dialogue`: A handle for controlling dialogue state
-/// * `msg`: Message sent by the user
-async fn check_site(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
-    let mut url: String;
+/// * `message`: The message with the list or the card
+/// * `q`: Response from the user after pressing the button
+/// * `action`: The pressed button
+/// * `key`: The key of the link returned by `links_list::get_link_key`
+async fn link_card_callback_handler(bot: Bot, dialogue: SparkleDialogue, message: Message, q: CallbackQuery, action: &str, key: &str) -> HandlerResult {
+    let chat_id = q.chat_id().unwrap();
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    if action == "card_back" {
+        // The list is opened on the page with the link
+        let position = links_list::select_links(&links, &LinksView::default()).iter().position(|(_, link)| links_list::get_link_key(&link.link) == key);
+        let view = LinksView { page: position.unwrap_or(0) / LINKS_PER_PAGE, ..Default::default() };
+
+        edit_links_list(&bot, &message, &view).await?;
+
+        bot.answer_callback_query(q.id).await?;
+        return Ok(());
+    }
+
+    // The link may have been deleted since the card was shown
+    let Some((index, link)) = links_list::find_link_by_key(&links, key) else {
+        bot.answer_callback_query(q.id).text("Ссылка не найдена, откройте список ссылок заново").await?;
+        return Ok(());
+    };
+
+    match action {
+        "card_check" => {
+            bot.answer_callback_query(q.id).text("Проверяю ссылку...").await?;
+
+            info!("The user has requested a check of the link in the chat: {}", chat_id);
+
+            let result = check_link_now(link).await;
+            let text = format!("{}\n🔄 Проверка сейчас: {result}\nРучные проверки не учитываются в доступности и не отправляют оповещений", create_link_card(link));
+
+            let _ = bot.edit_message_text(message.chat.id, message.id, text)
+                .reply_markup(create_link_card_keyboard(link)).await;
 
-    match msg.text() {
-        Some(text) => {
-            url = text.to_string();
-        }
-        None => {
-            bot.send_message(msg.chat.id, "Пожалуйста, введите ссылку.").await?;
             return Ok(());
         }
-    }
+        "card_pause" => {
+            database::set_link_paused(chat_id.0, &link.link, !link.paused);
 
-    if !website::has_http_or_https(&url) {
-        url = format!("https://{}", url);
-    }
+            info!("The user has {} the monitoring of the link in the chat: {}", if link.paused { "resumed" } else { "paused" }, chat_id);
 
-    if is_url(&url) {
-        let send_message = bot.send_message(msg.chat.id, "Пожалуйста, подождите...\nМаксимальное время ответа - 15 секунд").await?;
+            let link = Links { paused: !link.paused, ..link.clone() };
 
-        info!("Site information for the user is requested: {}", msg.from().expect("Unable to determine user ID").id.0);
+            bot.edit_message_text(message.chat.id, message.id, create_link_card(&link))
+                .reply_markup(create_link_card_keyboard(&link)).await?;
+        }
+        "card_settings" => {
+            let text = format!("{}\n\nЧтобы изменить настройки, используйте команду /linksettings {} <параметр>=<значение>, например: /linksettings {} timeout=10 codes=200-299,401 slow=2000\n\n{}\nОкна обслуживания настраиваются командой /maintenance {}",
+                               describe_link_settings(link), index + 1, index + 1, describe_maintenance_windows(link), index + 1);
+            let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback("◀️ Назад", format!("link_card:{key}"))]]);
 
-        let site_information = website::get_site_information(&url).await;
+            bot.edit_message_text(message.chat.id, message.id, text).reply_markup(keyboard).await?;
+        }
+        "card_rename" => {
+            send_prompt(&bot, message.chat.id, format!("Введите новое название для ссылки {} или «-», чтобы убрать название. Для отмены введите команду /cancel", link.link)).await?;
+            dialogue.update(BotState::RenamingLink { link: link.link.clone() }).await?;
+        }
+        "card_labels" => {
+            let current = if link.labels.is_empty() { String::from("нет") } else { link.labels.join(", ") };
 
-        match site_information {
-            Ok(result) => {
-                let text = compile_site_information(result);
-                bot.edit_message_text(msg.chat.id, send_message.id, text).await?;
-            }
-            Err(e) => {
-                if e.is_timeout() {
-                    bot.edit_message_text(msg.chat.id, send_message.id, "Сайт слишком долго отвечал").await?;
-                }
-                else {
-                    bot.edit_message_text(msg.chat.id, send_message.id, "Боту не удалось проверить сайт").await?;
-                }
-            }
+            send_prompt(&bot, message.chat.id, format!("Текущие метки ссылки {}: {current}\n\nВведите новые метки через пробел (например: prod client-x) или «-», чтобы убрать все метки. Для отмены введите команду /cancel", link.link)).await?;
+            dialogue.update(BotState::EditingLabels { link: link.link.clone() }).await?;
         }
+        "card_delete" => {
+            let text = format!("❓ Удалить ссылку {}? ❓", describe_link(link));
+            let keyboard = InlineKeyboardMarkup::new(vec![
+                vec![InlineKeyboardButton::callback("Удалить", format!("card_delete_confirm:{key}"))],
+                vec![InlineKeyboardButton::callback("Назад", format!("link_card:{key}"))]
+            ]);
+
+            bot.edit_message_text(message.chat.id, message.id, text).reply_markup(keyboard).await?;
+        }
+        "card_delete_confirm" => {
+            let deleted = delete_links(chat_id.0, vec![link.clone()]);
 
-        dialogue.update(BotState::Default).await?;
+            info!("The user has deleted a link in the chat: {}", chat_id);
 
-        show_main_menu(&bot, &msg).await?;
-    }
-    else {
-        bot.send_message(msg.chat.id, "Данный текст не является ссылкой!").await?;
+            offer_undo_deleting(&bot, &message, chat_id.0, deleted).await?;
+        }
+        _ => {
+            bot.edit_message_text(message.chat.id, message.id, create_link_card(link))
+                .reply_markup(create_link_card_keyboard(link)).await?;
+        }
     }
 
+    bot.answer_callback_query(q.id).await?;
+
     Ok(())
 }
 
-/// Compiles the site information into a formatted string.
+/// Receives a new name for the link from the user and shows the card of the renamed link
 ///
 /// # Arguments
 ///
-/// * `site_information` - The site information to compile.
-///
-/// # Returns
-///
-/// A string containing the compiled site information.
-///
-/// # Examples
-///
-/// ```
-/// use crate::SiteInformation;
-///
-/// let info = SiteInformation {
-///     status_code: 200,
-///     duration: 100,
-///     has_robots: 200,
-///     has_sitemap: 200,
-///     certificate: None,
-/// };
-///
-/// let result = compile_site_information(info);
-/// ```
-fn compile_site_information(site_information: SiteInformation) -> String {
-    let mut text = format!("❔ Информация о введеном вами сайте ❔\n\n");
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `link`: The renamed link
+/// * `msg`: Message sent by the user
+async fn receive_link_name(bot: Bot, dialogue: SparkleDialogue, link: String, msg: Message) -> HandlerResult {
+    let chat_id = msg.chat.id;
 
-    text = format!("{text}📝 Код ответа: {}\n", site_information.status_code);
-    text = format!("{text}🕔 Время ответа: {} милисекунд\n", site_information.duration);
+    let Some(name) = msg.text().map(str::trim).filter(|name| !name.is_empty()) else {
+        send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, введите название.")).await?;
+        return Ok(());
+    };
 
-    match site_information.has_robots {
-        200 => {
-            text = format!("{text}🤖 Наличие robots.txt: есть\n")
-        }
-        _ => {
-            text = format!("{text}🤖 Наличие robots.txt: нет (код ответа: {})\n", site_information.has_robots);
-        }
-    }
-    match site_information.has_sitemap {
-        200 => {
-            text = format!("{text}🗺 Наличие sitemap.xml: есть\n\n")
-        }
-        _ => {
-            text = format!("{text}🗺 Наличие sitemap.xml: нет (код ответа: {})\n\n", site_information.has_sitemap);
-        }
+    if name.chars().count() > MAX_LINK_NAME_LENGTH {
+        send_prompt(&bot, msg.chat.id, format!("Название не должно быть длиннее {MAX_LINK_NAME_LENGTH} символов")).await?;
+        return Ok(());
     }
 
-    match site_information.certificate {
-        Some(cert) => {
-            text = format!("{text}📄 Сертификат:\
-                \nОбщее название: {}\
-                \nОрганизация: {}\
-                \nСтрана: {}\
-                \nИздатель: {}\
-                \nВремя окончания: {}\n\n", cert.intermediate.common_name,
-                           cert.intermediate.organization,
-                           cert.intermediate.country,
-                           cert.intermediate.issuer,
-                           cert.intermediate.time_to_expiration);
+    database::update_link_name(chat_id.0, &link, Some(name).filter(|name| *name != "-"));
+
+    info!("The user has renamed a link in the chat: {}", chat_id);
+
+    dialogue.update(BotState::Default).await?;
+
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    match links.iter().find(|saved| saved.link == link) {
+        Some(link) => {
+            bot.send_message(msg.chat.id, create_link_card(link)).reply_markup(create_link_card_keyboard(link)).await?;
         }
         None => {
-            text = format!("{text}📄 Сертификат: не найден\n\n")
+            bot.send_message(msg.chat.id, "Ссылка не найдена, возможно, она была удалена").await?;
         }
     }
 
-    text
+    Ok(())
 }
 
-/// Function to cancel receiving a link in a Telegram chat.
+/// Cancels renaming the link
 ///
 /// # Arguments
 ///
-/// * `bot` - An instance of the `Bot` struct providing methods to interact with the Telegram Bot API.
-/// * `dialogue` - An instance of the `MyDialogue` struct representing the conversation state.
-/// * `msg` - The `Message` struct representing the incoming message in the chat.
-///
-/// # Returns
-///
-/// An `HandlerResult` which is an alias for `Result<(), Error>` indicating the success or failure of the operation.
-///
-/// # Examples
-///
-/// ```rust,no_run
-/// use your_crate::{Bot, MyDialogue, Message, HandlerResult};
-/// use tokio::runtime::Runtime;
-///
-/// let bot = Bot::new("your_bot_token");
-/// let dialogue = MyDialogue::create();
-/// let msg = Message::new("your_chat_id", "your_message");
-///
-/// let mut rt = Runtime::new().unwrap();
-///
-/// let result = rt.block_on(cancel_receive_link(bot, dialogue, msg));
-///
-/// assert!(result.is_ok());
-/// ```
-async fn cancel_receive_link(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, "Вы отменили ввод ссылки").await?;
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn cancel_renaming_link(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Вы отменили переименование ссылки").await?;
 
     dialogue.update(BotState::Default).await?;
 
     Ok(())
 }
 
-/// Cancels the process of deleting some links.
+/// Receives new labels for the link from the user and shows the card of the link
 ///
 /// # Arguments
 ///
-/// * `bot` - The `Bot` instance to send a message.
-/// * `dialogue` - The `MyDialogue` instance to update the state.
-/// * `msg` - The `Message` object that triggered the cancellation.
-async fn cancel_deleting_some_links(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
-    bot.send_message(msg.chat.id, "Вы отменили удаление ссылок").await?;
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `link`: The link whose labels are changed
+/// * `msg`: Message sent by the user
+async fn receive_link_labels(bot: Bot, dialogue: SparkleDialogue, link: String, msg: Message) -> HandlerResult {
+    let chat_id = msg.chat.id;
+
+    let Some(text) = msg.text().map(str::trim).filter(|text| !text.is_empty()) else {
+        send_prompt(&bot, msg.chat.id, String::from("Пожалуйста, введите метки.")).await?;
+        return Ok(());
+    };
+
+    if let Err(text) = set_link_labels(chat_id.0, &link, if text == "-" { "" } else { text }) {
+        send_prompt(&bot, msg.chat.id, text).await?;
+        return Ok(());
+    }
 
     dialogue.update(BotState::Default).await?;
 
+    let links = database::get_all_links_from_user(chat_id.0, None);
+
+    match links.iter().find(|saved| saved.link == link) {
+        Some(link) => {
+            bot.send_message(msg.chat.id, create_link_card(link)).reply_markup(create_link_card_keyboard(link)).await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, "Ссылка не найдена, возможно, она была удалена").await?;
+        }
+    }
+
     Ok(())
 }
 
-// Creating bot menus
-
-/// Creates the beginning menu keyboard.
-///
-/// This async function creates an instance of `InlineKeyboardMarkup` that represents
-/// a menu keyboard with a single button labeled "Приступим!" and a callback value of "begin".
-///
-/// # Example
-/// ```rust
-/// use telegram_bot::InlineKeyboardMarkup;
-/// use telegram_bot::InlineKeyboardButton;
-///
-/// async fn create_beginning_menu_keyboard() -> InlineKeyboardMarkup {
-///     let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
-///
-///     let begin = InlineKeyboardButton::callback("Приступим!", "begin");
+/// Cancels editing the labels of the link
 ///
-///     keyboard.push(vec![begin]);
+/// # Arguments
 ///
-///     InlineKeyboardMarkup::new(keyboard)
-/// }
-/// ```
-async fn create_beginning_menu_keyboard() -> InlineKeyboardMarkup {
-    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
-
-    let begin = InlineKeyboardButton::callback("Приступим!", "begin");
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `msg`: Message sent by the user
+async fn cancel_editing_labels(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Вы отменили изменение меток").await?;
 
-    keyboard.push(vec![begin]);
+    dialogue.update(BotState::Default).await?;
 
-    InlineKeyboardMarkup::new(keyboard)
+    Ok(())
 }
 
-/// Creates the main menu keyboard with inline buttons.
+/// Handles the buttons of the list of links filtered by a label, which pause, resume, check or
+/// delete all links with the label
 ///
-/// # Returns
+/// # Arguments
 ///
-/// Returns an `InlineKeyboardMarkup` object representing the main menu keyboard.
-async fn create_main_menu_keyboard() -> InlineKeyboardMarkup {
-    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
+/// * `bot`: Bot instance
+/// * `message`: The message with the list
+/// * `q`: Response from the user after pressing the button
+/// * `action`: The pressed button
+/// * `label`: The label of the links
+async fn label_callback_handler(bot: Bot, message: Message, q: CallbackQuery, action: &str, label: &str) -> HandlerResult {
+    let chat_id = q.chat_id().unwrap();
+    let view = LinksView { query: format!("#{label}"), ..Default::default() };
+
+    let links: Vec<Links> = database::get_all_links_from_user(chat_id.0, None).into_iter()
+        .filter(|link| link.labels.iter().any(|saved| saved == label))
+        .collect();
+
+    if links.is_empty() {
+        bot.answer_callback_query(q.id).text(format!("Нет ссылок с меткой {label}")).await?;
+        return Ok(());
+    }
 
-    let check_link = InlineKeyboardButton::callback("🔭 Проанализировать сайт 🔭", "check_link");
-    let enter_links = InlineKeyboardButton::callback("✏️ Добавить ссылки ✏️", "enter_links");
-    let links = InlineKeyboardButton::callback("📒 Получить все ссылки 📒", "get_links");
-    let delete_some_histories = InlineKeyboardButton::callback("✂️ Удалить несколько ссылок ✂️", "delete_some_links");
-    let clear_all_links = InlineKeyboardButton::callback("❌ Очистить все ссылки ❌", "clear_all_links");
+    match action {
+        "label_pause" | "label_resume" => {
+            let paused = action == "label_pause";
 
-    keyboard.push(vec![check_link]);
-    keyboard.push(vec![enter_links]);
-    keyboard.push(vec![links]);
-    keyboard.push(vec![delete_some_histories]);
-    keyboard.push(vec![clear_all_links]);
+            for link in &links {
+                database::set_link_paused(chat_id.0, &link.link, paused);
+            }
 
-    InlineKeyboardMarkup::new(keyboard)
-}
+            info!("The user has {} the monitoring of the links with a label in the chat: {}", if paused { "paused" } else { "resumed" }, chat_id);
 
-/// Creates an inline keyboard markup for a confirmation menu.
-///
-/// The resulting inline keyboard will have two buttons: "Очистить" (clear) and "Отмена" (cancel).
-///
-/// # Returns
-///
-/// The resulting inline keyboard markup.
-async fn create_confirmation_menu_keyboard() -> InlineKeyboardMarkup {
-    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
+            edit_links_list(&bot, &message, &view).await?;
+        }
+        "label_check" => {
+            bot.answer_callback_query(q.id).text(format!("Проверяю ссылки: {}...", links.len())).await?;
 
-    let confirmation = InlineKeyboardButton::callback("Очистить", "confirm");
-    let cancel = InlineKeyboardButton::callback("Отмена", "cancel");
+            info!("The user has requested a check of the links with a label in the chat: {}", chat_id);
 
-    keyboard.push(vec![confirmation]);
-    keyboard.push(vec![cancel]);
+            // The results are split into several messages, so that a long list does not exceed the message length limit
+            for chunk in links.chunks(MAX_CHECKED_LINKS_PER_MESSAGE) {
+                let mut text = format!("🔄 Результат проверки ссылок с меткой {label}:\n");
 
-    InlineKeyboardMarkup::new(keyboard)
-}
-//
+                for link in chunk {
+                    text = format!("{text}\n{} — {}", shorten(&describe_link(link), MAX_RESULT_LINK_LENGTH), check_link_now(link).await);
+                }
 
-/// Retrieves all saved links by user ID and sends a message with the result
-///
-/// # Arguments
-///
-/// * `bot`: Bot instance
-/// * `q`: Response from the user after pressing the button
-async fn get_all_links_from_user(bot: Bot, q: CallbackQuery) -> HandlerResult {
-    let user_id = q.from.id;
-    let histories = database::get_all_links_from_user(user_id.0, None);
+                text = format!("{text}\n\nРучные проверки не учитываются в доступности и не отправляют оповещений");
 
-    info!("Receiving a request for all links from the user: {}", user_id);
+                bot.send_message(message.chat.id, text).await?;
+            }
 
-    if histories.iter().count() == 0 {
-        bot.send_message(user_id, "У вас нет сохраненных ссылок").await?;
-    }
-    else {
-        let str = create_links_list("Вот ваши сохраненные ссылки:\n", histories);
-        bot.send_message(user_id, str).await?;
+            return Ok(());
+        }
+        "label_delete" => {
+            let text = format!("❓ Удалить все ссылки с меткой {label} ({})? ❓", links.len());
+            let keyboard = InlineKeyboardMarkup::new(vec![
+                vec![InlineKeyboardButton::callback("Удалить", format!("label_delete_confirm:{label}"))],
+                vec![InlineKeyboardButton::callback("Назад", view.to_callback_data("links"))]
+            ]);
+
+            bot.edit_message_text(message.chat.id, message.id, text).reply_markup(keyboard).await?;
+        }
+        _ => {
+            let deleted = delete_links(chat_id.0, links);
+
+            info!("The user has deleted the links with a label in the chat: {}", chat_id);
+
+            offer_undo_deleting(&bot, &message, chat_id.0, deleted).await?;
+        }
     }
 
     bot.answer_callback_query(q.id).await?;
@@ -863,49 +4034,24 @@ async fn get_all_links_from_user(bot: Bot, q: CallbackQuery) -> HandlerResult {
 
 /// Starts the process of deleting some links.
 ///
-/// This function takes a `Bot`, `MyDialogue`, and `CallbackQuery` as inputs.
-/// It gets the `user_id` from the `CallbackQuery` and calls the function `get_all_links_from_user` from the `database` module
-/// to retrieve all the links associated with the user.
-///
-/// If there are no links for the user, it sends a message to the user.
-/// Otherwise, it creates a string by calling the `create_links_list` function (passing a text
-/// message and the `histories` parameter) and sends it as a message to the user.
-///
-/// Finally, it updates the `dialogue` with the `BotState::DeletingSomeLinks`.
+/// Sends the user a keyboard with a toggle button for each saved link and
+/// goes to the `BotState::DeletingSomeLinks` state with nothing selected.
 ///
 /// # Arguments
 ///
 /// * `bot` - The Bot instance.
-/// * `dialogue` - The MyDialogue instance.
+/// * `dialogue` - A handle for controlling dialogue state.
 /// * `q` - The CallbackQuery instance.
-///
-/// # Returns
-///
-/// The HandlerResult.
-///
-/// # Examples
-///
-/// ```rust
-/// use crate::{Bot, MyDialogue, CallbackQuery};
-///
-/// let bot = Bot::new();
-/// let dialogue = MyDialogue::new();
-/// let q = CallbackQuery::new();
-///
-/// start_deleting_some_links(bot, dialogue, q).await;
-/// ```
 async fn start_deleting_some_links(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
-    let user_id = q.from.id;
-    let histories = database::get_all_links_from_user(user_id.0, None);
+    let chat_id = q.chat_id().unwrap();
+    let links = database::get_all_links_from_user(chat_id.0, None);
 
-    if histories.iter().count() == 0 {
-        bot.send_message(user_id, "У вас нет ссылок для удаления").await?;
+    if links.is_empty() {
+        bot.send_message(chat_id, "У вас нет ссылок для удаления").await?;
     }
     else {
-        let str = create_links_list("Выберите, какие элементы требуется удалить. Напишите номера элемента через пробел. Вы можете отменить удаление, введя команду /cancel.\n\nИстория запросов:\n", histories);
-
-        bot.send_message(user_id, str).await?;
-        dialogue.update(BotState::DeletingSomeLinks).await?;
+        bot.send_message(chat_id, DELETING_LINKS_TEXT).reply_markup(create_deleting_links_keyboard(&links, &[], 0)).await?;
+        dialogue.update(BotState::DeletingSomeLinks { selected: Vec::new(), page: 0 }).await?;
     }
 
     bot.answer_callback_query(q.id).await?;
@@ -913,87 +4059,242 @@ async fn start_deleting_some_links(bot: Bot, dialogue: SparkleDialogue, q: Callb
     Ok(())
 }
 
-/// Creates a formatted list of links
+/// Creates a keyboard with a toggle button for each link on the page, page navigation and actions.
 ///
-/// # Arguments
+/// The callback data of the toggle button contains the key of the link, so that a keyboard shown
+/// before the list of links changed still selects the link written on the button.
 ///
-/// * `str`: Message to user
-/// * `histories`: List of links
+/// # Arguments
 ///
-/// returns: Message to user with a formatted list of links
-fn create_links_list(str: &str, links: Vec<Links>) -> String {
-    let mut str = str.to_string();
+/// * `links` - All links of the user.
+/// * `selected` - The selected links.
+/// * `page` - The shown page, starting from 0.
+fn create_deleting_links_keyboard(links: &[Links], selected: &[String], page: usize) -> InlineKeyboardMarkup {
+    let mut keyboard: Vec<Vec<InlineKeyboardButton>> = vec![];
+
+    let pages = links.len().div_ceil(LINKS_PER_PAGE).max(1);
+    let page = page.min(pages - 1);
+
+    for link in links.iter().skip(page * LINKS_PER_PAGE).take(LINKS_PER_PAGE) {
+        let mark = if selected.contains(&link.link) { "✅" } else { "⬜️" };
+
+        keyboard.push(vec![InlineKeyboardButton::callback(format!("{mark} {}", shorten(get_link_title(link), MAX_BUTTON_LINK_LENGTH)), format!("delete_toggle:{}", links_list::get_link_key(&link.link)))]);
+    }
+
+    if pages > 1 {
+        let previous = InlineKeyboardButton::callback("◀️", format!("delete_page:{}", (page + pages - 1) % pages));
+        let current = InlineKeyboardButton::callback(format!("{}/{}", page + 1, pages), format!("delete_page:{page}"));
+        let next = InlineKeyboardButton::callback("▶️", format!("delete_page:{}", (page + 1) % pages));
 
-    for i in 0..links.iter().count() {
-        let link = format!("\n[{}] {}", i + 1, links[i].link);
-        str.push_str(&link);
+        keyboard.push(vec![previous, current, next]);
     }
-    str
+
+    keyboard.push(vec![InlineKeyboardButton::callback(format!("🗑 Удалить выбранные ({})", selected.len()), "delete_selected")]);
+    keyboard.push(vec![InlineKeyboardButton::callback("Отмена", "delete_cancel")]);
+
+    InlineKeyboardMarkup::new(keyboard)
 }
 
-/// Removes some references from the database that the user enters and returns the result of
-/// the operation
+/// Reminds the user who is selecting links to delete to use the keyboard instead of typing
 ///
 /// # Arguments
 ///
 /// * `bot`: Bot instance
-/// * `dialogue`: A handle for controlling dialogue state
 /// * `msg`: Message sent by the user
-async fn delete_some_links(bot: Bot, dialogue: SparkleDialogue, msg: Message) -> HandlerResult {
-    let numbers_string: String;
-    let user_id = msg.from().expect("Unable to determine user ID").id.0;
+async fn remind_about_deleting_keyboard(bot: Bot, msg: Message) -> HandlerResult {
+    bot.send_message(msg.chat.id, "Выберите ссылки кнопками под сообщением или отмените удаление командой /cancel").await?;
 
-    match msg.text() {
-        Some(text) => {
-            numbers_string = text.to_string();
-        }
-        None => {
-            bot.send_message(msg.chat.id, "Пожалуйста, введите номера элементов.").await?;
-            return Ok(());
-        }
-    }
+    Ok(())
+}
+
+/// Handles the buttons of the keyboard for selecting links to delete: toggling a link,
+/// switching pages, confirming and cancelling the deletion
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `dialogue`: A handle for controlling dialogue state
+/// * `(selected, page)`: The selected links and the shown page
+/// * `q`: Response from the user after pressing the button
+async fn deleting_links_callback_handler(bot: Bot, dialogue: SparkleDialogue, (mut selected, mut page): (Vec<String>, usize), q: CallbackQuery) -> HandlerResult {
+    let (Some(data), Some(message)) = (q.data.clone(), q.message.clone()) else { return Ok(()) };
 
-    let mut numbers: Vec<usize> = Vec::new();
+    let chat_id = q.chat_id().unwrap();
+    let links = database::get_all_links_from_user(chat_id.0, None);
 
-    for number_string in numbers_string.split(" ") {
-        let res = number_string.parse::<i32>();
+    // Links that were deleted in another way are no longer selected
+    selected.retain(|link| links.iter().any(|saved| saved.link == *link));
 
-        match res {
-            Ok(number) => {
-                if number - 1 < 0 {
-                    bot.send_message(msg.chat.id, "Введенный текст не является корректным!").await?;
-                    return Ok(());
-                }
+    let (action, argument) = data.split_once(':').unwrap_or((data.as_str(), ""));
 
-                numbers.push((number - 1) as usize);
+    match action {
+        "delete_toggle" => {
+            if let Some((_, link)) = links_list::find_link_by_key(&links, argument) {
+                match selected.iter().position(|selected_link| *selected_link == link.link) {
+                    Some(position) => { selected.remove(position); }
+                    None => selected.push(link.link.clone())
+                }
             }
-            Err(_) => {
-                bot.send_message(msg.chat.id, "Введенный текст не является корректным!").await?;
+        }
+        "delete_page" => {
+            page = argument.parse::<usize>().unwrap_or(0);
+        }
+        "delete_selected" => {
+            if selected.is_empty() {
+                bot.answer_callback_query(q.id).text("Сначала выберите ссылки").await?;
                 return Ok(());
             }
+
+            let text = format!("❓ Удалить выбранные ссылки ({})? ❓\n\n{}", selected.len(), selected.join("\n"));
+            let keyboard = InlineKeyboardMarkup::new(vec![
+                vec![InlineKeyboardButton::callback("Удалить", "delete_confirm")],
+                vec![InlineKeyboardButton::callback("Назад", "delete_back")]
+            ]);
+
+            bot.edit_message_text(message.chat.id, message.id, text).reply_markup(keyboard).await?;
+            dialogue.update(BotState::DeletingSomeLinks { selected, page }).await?;
+            bot.answer_callback_query(q.id).await?;
+
+            return Ok(());
         }
-    }
+        "delete_confirm" => {
+            let deleted = delete_links(chat_id.0, links.into_iter().filter(|link| selected.contains(&link.link)).collect());
 
-    let histories = database::get_all_links_from_user(user_id, None);
-    let mut links: Vec<&str> = Vec::new();
+            info!("The user has deleted some links in the chat: {}", chat_id);
 
-    for number in numbers {
-        if let Some(link) = histories.get(number) {
-            links.push(&link.link);
+            offer_undo_deleting(&bot, &message, chat_id.0, deleted).await?;
+
+            dialogue.update(BotState::Default).await?;
+            show_main_menu(&bot, &message).await?;
+            bot.answer_callback_query(q.id).await?;
+
+            return Ok(());
         }
-        else {
-            bot.send_message(msg.chat.id, "Некоторые элементы не существуют в списке!").await?;
+        "delete_cancel" => {
+            bot.edit_message_text(message.chat.id, message.id, "Вы отменили удаление ссылок").await?;
+            dialogue.update(BotState::Default).await?;
+
+            show_main_menu(&bot, &message).await?;
+            bot.answer_callback_query(q.id).await?;
 
             return Ok(());
         }
+        _ => ()
+    }
+
+    if links.is_empty() {
+        bot.edit_message_text(message.chat.id, message.id, "У вас нет ссылок для удаления").await?;
+        dialogue.update(BotState::Default).await?;
     }
+    else {
+        let keyboard = create_deleting_links_keyboard(&links, &selected, page);
 
-    database::delete_some_links(user_id, links);
+        // The text is edited too, because the user may return from the confirmation
+        let _ = bot.edit_message_text(message.chat.id, message.id, DELETING_LINKS_TEXT).reply_markup(keyboard).await;
+        dialogue.update(BotState::DeletingSomeLinks { selected, page }).await?;
+    }
 
-    bot.send_message(msg.chat.id, "Выбранные вами элементы были удалены").await?;
-    dialogue.update(BotState::Default).await?;
+    bot.answer_callback_query(q.id).await?;
 
-    show_main_menu(&bot, &msg).await?;
+    Ok(())
+}
+
+/// Deletes the links of the chat together with their subscribers, alerts and maintenance windows
+///
+/// # Arguments
+///
+/// * `chat_id`: The ID of the chat
+/// * `links`: The deleted links
+///
+/// # Returns
+///
+/// The deleted links with their subscribers, alerts and maintenance windows, which are needed to undo the deletion
+fn delete_links(chat_id: i64, links: Vec<Links>) -> Vec<DeletedLink> {
+    let deleted: Vec<DeletedLink> = links.into_iter()
+        .map(|link| {
+            let subscribers = database::get_link_subscribers(chat_id, &link.link);
+            let mut alerts = database::get_link_alerts(chat_id, &link.link, i64::MAX);
+            alerts.reverse();
+
+            let maintenance_windows = database::get_maintenance_windows(chat_id, &link.link).into_iter()
+                .map(|window| window.schedule)
+                .collect();
+
+            DeletedLink { link, subscribers, alerts, maintenance_windows }
+        })
+        .collect();
+
+    database::delete_some_links(chat_id, deleted.iter().map(|deleted| deleted.link.link.as_str()).collect());
+
+    deleted
+}
+
+/// Reports the deleted links in the message and offers to restore them for `UNDO_DELETE_SECONDS`
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `message`: The message in which the deletion is reported
+/// * `chat_id`: The ID of the chat
+/// * `deleted`: The deleted links with their settings, subscribers, alerts and maintenance windows
+async fn offer_undo_deleting(bot: &Bot, message: &Message, chat_id: i64, deleted: Vec<DeletedLink>) -> HandlerResult {
+    let text = format!("Удалено ссылок: {}. Удаление можно отменить в течение {} секунд", deleted.len(), UNDO_DELETE_SECONDS);
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback("↩️ Отменить удаление", "undo_delete")]]);
+
+    DELETED_LINKS.lock().unwrap().insert(chat_id, (Instant::now(), deleted));
+
+    bot.edit_message_text(message.chat.id, message.id, text).reply_markup(keyboard).await?;
+
+    // The undo button is removed when the time to restore the links is over
+    let undo_bot = bot.clone();
+    let undo_message = message.clone();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(UNDO_DELETE_SECONDS)).await;
+        let _ = undo_bot.edit_message_reply_markup(undo_message.chat.id, undo_message.id).await;
+    });
+
+    Ok(())
+}
+
+/// Restores the links deleted by the user, if the time to undo the deletion is not over
+///
+/// # Arguments
+///
+/// * `bot`: Bot instance
+/// * `q`: Response from the user after pressing the button
+async fn undo_deleting_links(bot: Bot, q: CallbackQuery) -> HandlerResult {
+    let chat_id = q.chat_id().unwrap();
+    let deleted = DELETED_LINKS.lock().unwrap().remove(&chat_id.0);
+
+    let text = match deleted {
+        Some((deleted_at, links)) if deleted_at.elapsed() <= Duration::from_secs(UNDO_DELETE_SECONDS) => {
+            // Links that the user has added again are not duplicated
+            let links: Vec<DeletedLink> = links.into_iter()
+                .filter(|deleted| !database::is_link_exists(chat_id.0, &deleted.link.link))
+                .collect();
+
+            for deleted in &links {
+                database::restore_links(&[(deleted.link.clone(), deleted.maintenance_windows.clone())]);
+                database::restore_alerts(&deleted.alerts);
+
+                for subscriber in &deleted.subscribers {
+                    database::add_link_subscriber(chat_id.0, &deleted.link.link, subscriber.user_id, &subscriber.name);
+                }
+            }
+
+            info!("The user has restored the deleted links in the chat: {}", chat_id);
+
+            format!("Восстановлено ссылок: {}", links.len())
+        }
+        _ => String::from("Время для отмены удаления истекло")
+    };
+
+    if let Some(message) = &q.message {
+        bot.edit_message_text(message.chat.id, message.id, text).await?;
+    }
+
+    bot.answer_callback_query(q.id).await?;
 
     Ok(())
 }
@@ -1006,14 +4307,14 @@ async fn delete_some_links(bot: Bot, dialogue: SparkleDialogue, msg: Message) ->
 /// * `dialogue`: A handle for controlling dialogue state
 /// * `q`: Response from the user after pressing the button
 async fn clear_links(bot: Bot, dialogue: SparkleDialogue, q: CallbackQuery) -> HandlerResult {
-    let user_id = q.from.id;
+    let chat_id = q.chat_id().unwrap();
     let msg = q.message.expect("Failed to retrieve message ");
 
-    database::clear_all_links(user_id.0);
+    database::clear_all_links(chat_id.0);
 
-    info!("Completely deleted the user's history: {}", user_id);
+    info!("Completely deleted the links of the chat: {}", chat_id);
 
-    bot.edit_message_text(user_id, msg.id, "Ваша история запросов успешно очищена!").await?;
+    bot.edit_message_text(chat_id, msg.id, "Ваша история запросов успешно очищена!").await?;
 
     dialogue.update(BotState::Default).await?;
 
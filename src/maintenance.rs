@@ -0,0 +1,237 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+/// Maximum length of a maintenance window in minutes
+const MAX_DURATION_MINUTES: i64 = 7 * 24 * 60;
+
+/// Days of the week in the order they are written
+const WEEKDAYS: [Weekday; 7] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+
+/// Represents the days on which a maintenance window starts.
+#[derive(Debug, PartialEq)]
+pub enum WindowDays {
+    /// A one-off window on the date
+    Once(NaiveDate),
+    /// A window that repeats every week on the days
+    Weekly(Vec<Weekday>)
+}
+
+/// A period of planned work on a site, during which the site is checked but the user is not alerted.
+///
+/// It is written by the user as `<date or days> <time> <duration> [timezone]`, for example
+/// `2024-06-01 22:00 2h Europe/Moscow`, `sun 03:00 1h30m` or `mon-fri 23:30 45m UTC`.
+#[derive(Debug, PartialEq)]
+pub struct MaintenanceWindow {
+    pub days: WindowDays,
+    /// Local time of the start of the window
+    pub time: NaiveTime,
+    /// Length of the window in minutes
+    pub duration: i64,
+    pub timezone: Tz
+}
+
+impl MaintenanceWindow {
+    /// Parses a maintenance window.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice like `2024-06-01 22:00 2h Europe/Moscow`. The days are a date,
+    ///   `daily`, or a comma-separated list of days and ranges like `mon-fri,sun`. The timezone is UTC by default.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the text is not a valid maintenance window.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let window = MaintenanceWindow::parse("sun 03:00 1h30m").unwrap();
+    ///
+    /// assert_eq!(window.duration, 90);
+    /// ```
+    pub fn parse(text: &str) -> Option<MaintenanceWindow> {
+        let parts: Vec<&str> = text.split_whitespace().collect();
+
+        let (days, time, duration, timezone) = match parts.as_slice() {
+            [days, time, duration] => (*days, *time, *duration, "UTC"),
+            [days, time, duration, timezone] => (*days, *time, *duration, *timezone),
+            _ => return None
+        };
+
+        let days = match NaiveDate::parse_from_str(days, "%Y-%m-%d") {
+            Ok(date) => WindowDays::Once(date),
+            Err(_) => WindowDays::Weekly(parse_weekdays(days)?)
+        };
+
+        let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+        let duration = parse_duration(duration).filter(|minutes| (1..=MAX_DURATION_MINUTES).contains(minutes))?;
+        let timezone = timezone.parse::<Tz>().ok()?;
+
+        Some(MaintenanceWindow { days, time, duration, timezone })
+    }
+
+    /// Checks if the window is open at the moment.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.get_starts(now).into_iter().any(|start| start <= now && now < start + Duration::minutes(self.duration))
+    }
+
+    /// Checks if the window is one-off and it has already closed.
+    pub fn has_ended(&self, now: DateTime<Utc>) -> bool {
+        match self.days {
+            WindowDays::Once(_) => self.get_starts(now).into_iter().all(|start| start + Duration::minutes(self.duration) <= now),
+            WindowDays::Weekly(_) => false
+        }
+    }
+
+    /// Returns the starts of the window that can cover the moment.
+    ///
+    /// A start that falls into a gap of the daylight saving time transition is skipped.
+    fn get_starts(&self, now: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let dates = match &self.days {
+            WindowDays::Once(date) => vec![*date],
+            WindowDays::Weekly(weekdays) => {
+                let today = now.with_timezone(&self.timezone).date_naive();
+
+                // A window that started on one of the previous days may still be open
+                (0..=self.duration / (24 * 60) + 1)
+                    .filter_map(|days| today.checked_sub_signed(Duration::days(days)))
+                    .filter(|date| weekdays.contains(&date.weekday()))
+                    .collect()
+            }
+        };
+
+        dates.into_iter()
+            .filter_map(|date| self.timezone.from_local_datetime(&date.and_time(self.time)).earliest())
+            .map(|start| start.with_timezone(&Utc))
+            .collect()
+    }
+}
+
+impl Display for MaintenanceWindow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let days = match &self.days {
+            WindowDays::Once(date) => date.format("%Y-%m-%d").to_string(),
+            WindowDays::Weekly(weekdays) if weekdays.len() == WEEKDAYS.len() => String::from("daily"),
+            WindowDays::Weekly(weekdays) => {
+                weekdays.iter().map(|weekday| weekday.to_string().to_lowercase()).collect::<Vec<String>>().join(",")
+            }
+        };
+
+        let duration = match (self.duration / 60, self.duration % 60) {
+            (0, minutes) => format!("{minutes}m"),
+            (hours, 0) => format!("{hours}h"),
+            (hours, minutes) => format!("{hours}h{minutes}m")
+        };
+
+        write!(f, "{} {} {} {}", days, self.time.format("%H:%M"), duration, self.timezone)
+    }
+}
+
+/// Parses `daily` or a comma-separated list of days and ranges like `mon-fri,sun` into days in the week order
+fn parse_weekdays(text: &str) -> Option<Vec<Weekday>> {
+    if text.eq_ignore_ascii_case("daily") {
+        return Some(WEEKDAYS.to_vec());
+    }
+
+    let mut weekdays: Vec<Weekday> = Vec::new();
+
+    for part in text.split(',') {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.parse::<Weekday>().ok()?, end.parse::<Weekday>().ok()?),
+            None => (part.parse::<Weekday>().ok()?, part.parse::<Weekday>().ok()?)
+        };
+
+        let mut weekday = start;
+
+        loop {
+            if !weekdays.contains(&weekday) {
+                weekdays.push(weekday);
+            }
+
+            if weekday == end {
+                break;
+            }
+
+            weekday = weekday.succ();
+        }
+    }
+
+    weekdays.sort_by_key(|weekday| weekday.num_days_from_monday());
+
+    Some(weekdays)
+}
+
+/// Parses a duration like `2h`, `45m` or `1h30m` into minutes
+pub fn parse_duration(text: &str) -> Option<i64> {
+    let mut minutes: i64 = 0;
+    let mut number = String::new();
+
+    for character in text.chars() {
+        match character {
+            '0'..='9' => number.push(character),
+            'h' | 'm' if !number.is_empty() => {
+                let value = number.parse::<i64>().ok()?;
+                minutes = minutes.checked_add(if character == 'h' { value.checked_mul(60)? } else { value })?;
+                number.clear();
+            }
+            _ => return None
+        }
+    }
+
+    if number.is_empty() { Some(minutes) } else { None }
+}
+
+#[cfg(test)]
+mod maintenance_tests {
+    use super::*;
+
+    fn create_date(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_maintenance_window() {
+        let window = MaintenanceWindow::parse("mon-wed,fri 23:30 1h30m Europe/Moscow").unwrap();
+
+        assert_eq!(window.days, WindowDays::Weekly(vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Fri]));
+        assert_eq!(window.duration, 90);
+        assert_eq!(window.to_string(), "mon,tue,wed,fri 23:30 1h30m Europe/Moscow");
+
+        assert_eq!(MaintenanceWindow::parse("daily 02:00 45m").unwrap().to_string(), "daily 02:00 45m UTC");
+        assert_eq!(MaintenanceWindow::parse("2024-06-01 22:00 2h").unwrap().days, WindowDays::Once(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()));
+
+        assert!(MaintenanceWindow::parse("sun 03:00 0m").is_none());
+        assert!(MaintenanceWindow::parse("sun 25:00 1h").is_none());
+        assert!(MaintenanceWindow::parse("sun 03:00 1h Mars/Base").is_none());
+        assert!(MaintenanceWindow::parse("someday 03:00 1h").is_none());
+
+        assert_eq!(parse_duration("1h30m"), Some(90));
+        assert_eq!(parse_duration("h"), None);
+    }
+
+    #[test]
+    fn test_one_off_window() {
+        let window = MaintenanceWindow::parse("2024-06-01 22:00 2h Europe/Moscow").unwrap();
+
+        assert!(!window.is_active(create_date("2024-06-01T18:59:00Z")));
+        assert!(window.is_active(create_date("2024-06-01T19:00:00Z")));
+        assert!(window.is_active(create_date("2024-06-01T20:59:00Z")));
+        assert!(!window.is_active(create_date("2024-06-01T21:00:00Z")));
+
+        assert!(!window.has_ended(create_date("2024-06-01T20:00:00Z")));
+        assert!(window.has_ended(create_date("2024-06-01T21:00:00Z")));
+    }
+
+    #[test]
+    fn test_weekly_window() {
+        // 2024-06-02 is a Sunday, the window lasts until 01:00 on Monday
+        let window = MaintenanceWindow::parse("sun 23:00 2h").unwrap();
+
+        assert!(window.is_active(create_date("2024-06-02T23:30:00Z")));
+        assert!(window.is_active(create_date("2024-06-03T00:30:00Z")));
+        assert!(!window.is_active(create_date("2024-06-03T01:00:00Z")));
+        assert!(!window.is_active(create_date("2024-06-04T23:30:00Z")));
+        assert!(!window.has_ended(create_date("2024-06-04T23:30:00Z")));
+    }
+}
@@ -0,0 +1,118 @@
+use lazy_static::lazy_static;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    static ref SCRIPTS: Selector = Selector::parse("script[src]").unwrap();
+    static ref STYLESHEETS: Selector = Selector::parse("link[rel~=\"stylesheet\"][href]").unwrap();
+    static ref IFRAMES: Selector = Selector::parse("iframe[src]").unwrap();
+    static ref IMAGES: Selector = Selector::parse("img[src]").unwrap();
+    static ref FORMS: Selector = Selector::parse("form[action]").unwrap();
+}
+
+/// Represents the type of an element that loads an insecure resource.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MixedContentKind {
+    Script,
+    Stylesheet,
+    Iframe,
+    Image,
+    /// A form that sends its data over HTTP
+    Form
+}
+
+impl MixedContentKind {
+    /// Checks if browsers block this content on HTTPS pages.
+    ///
+    /// Scripts, stylesheets, iframes and forms are blocked, images are loaded with a warning or upgraded to HTTPS.
+    pub fn is_blocked(&self) -> bool {
+        *self != MixedContentKind::Image
+    }
+}
+
+/// Represents an insecure resource found on an HTTPS page.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MixedContent {
+    pub kind: MixedContentKind,
+    pub url: String
+}
+
+/// Finds scripts, stylesheets, iframes, images and form actions loaded over HTTP.
+///
+/// Relative links are not checked, because on an HTTPS page they are loaded over HTTPS.
+///
+/// # Arguments
+///
+/// * `body` - HTML code of the HTTPS page.
+///
+/// # Returns
+///
+/// Unique insecure resources in the order of the element types.
+///
+/// # Example
+///
+/// ```
+/// let mixed_content = find_mixed_content("<script src=\"http://example.com/app.js\"></script>");
+///
+/// assert_eq!(mixed_content[0].kind, MixedContentKind::Script);
+/// ```
+pub fn find_mixed_content(body: &str) -> Vec<MixedContent> {
+    let html = Html::parse_document(body);
+    let mut mixed_content: Vec<MixedContent> = Vec::new();
+
+    let elements = [
+        (&*SCRIPTS, "src", MixedContentKind::Script),
+        (&*STYLESHEETS, "href", MixedContentKind::Stylesheet),
+        (&*IFRAMES, "src", MixedContentKind::Iframe),
+        (&*IMAGES, "src", MixedContentKind::Image),
+        (&*FORMS, "action", MixedContentKind::Form)
+    ];
+
+    for (selector, attribute, kind) in elements {
+        for element in html.select(selector) {
+            let Some(url) = element.value().attr(attribute).map(str::trim) else { continue };
+
+            let is_insecure = url.get(..7).is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"));
+
+            if is_insecure && !mixed_content.iter().any(|content| content.kind == kind && content.url == url) {
+                mixed_content.push(MixedContent { kind, url: url.to_string() });
+            }
+        }
+    }
+
+    mixed_content
+}
+
+#[cfg(test)]
+mod mixed_content_tests {
+    use super::*;
+
+    #[test]
+    fn test_find_mixed_content() {
+        let body = r#"<html><head>
+            <link rel="stylesheet" href="HTTP://cdn.example.com/style.css">
+            <script src="/app.js"></script><script src="http://cdn.example.com/app.js"></script>
+        </head><body>
+            <iframe src="https://video.example.com/embed"></iframe><iframe src=" http://ads.example.com/ "></iframe>
+            <img src="http://example.com/logo.png"><img src="http://example.com/logo.png"><img src="//example.com/icon.png">
+            <form action="http://example.com/login"></form><form action="/search"></form>
+        </body></html>"#;
+
+        let content = |kind, url: &str| MixedContent { kind, url: url.to_string() };
+
+        assert_eq!(find_mixed_content(body), vec![
+            content(MixedContentKind::Script, "http://cdn.example.com/app.js"),
+            content(MixedContentKind::Stylesheet, "HTTP://cdn.example.com/style.css"),
+            content(MixedContentKind::Iframe, "http://ads.example.com/"),
+            content(MixedContentKind::Image, "http://example.com/logo.png"),
+            content(MixedContentKind::Form, "http://example.com/login")
+        ]);
+    }
+
+    #[test]
+    fn test_is_blocked() {
+        assert!(MixedContentKind::Script.is_blocked());
+        assert!(!MixedContentKind::Image.is_blocked());
+    }
+}
@@ -0,0 +1,295 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use reqwest::Url;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_native_tls::{native_tls, TlsConnector};
+use url::Host;
+use crate::client;
+
+/// Default port of the SMTP server
+const SMTP_PORT: u16 = 25;
+/// Default port of the TLS server
+const TLS_PORT: u16 = 443;
+
+const SECONDS_IN_DAY: i64 = 86400;
+
+/// Represents the type of the monitored service, which is determined by the scheme of the link.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MonitorKind {
+    /// A website, `http://` or `https://`
+    Http,
+    /// Successful TCP connection to the port, `tcp://host:port`
+    Tcp,
+    /// SMTP server greeting with the 220 code, `smtp://host[:port]`
+    Smtp,
+    /// TLS handshake and certificate on an arbitrary port, `tls://host[:port]`
+    Tls
+}
+
+/// Represents the result of a successful service check.
+pub struct ServiceCheck {
+    /// Time to connect to the service in milliseconds
+    pub duration: u128,
+    /// The greeting line of the SMTP server
+    pub banner: Option<String>,
+    /// Number of days until the TLS certificate expires
+    pub certificate_days: Option<i64>
+}
+
+/// Represents the reason why the service check failed.
+#[derive(Debug)]
+pub enum ServiceError {
+    /// The link does not contain a host and port
+    InvalidLink,
+    Connection(String),
+    Timeout,
+    /// The SMTP server did not respond with the 220 code
+    UnexpectedBanner(String),
+    /// The TLS handshake failed, for example, because of an invalid certificate
+    Tls(String)
+}
+
+impl MonitorKind {
+    /// Determines the type of the monitored service by the scheme of the link.
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - A saved link.
+    pub fn from_link(link: &str) -> MonitorKind {
+        match link.split_once("://").map(|(scheme, _)| scheme.to_lowercase()).as_deref() {
+            Some("tcp") => MonitorKind::Tcp,
+            Some("smtp") => MonitorKind::Smtp,
+            Some("tls") => MonitorKind::Tls,
+            _ => MonitorKind::Http
+        }
+    }
+}
+
+/// Checks if the text is a link to a non-HTTP service, that is, it starts with `tcp://`, `smtp://` or `tls://`.
+///
+/// # Arguments
+///
+/// * `text` - A string slice entered by the user.
+pub fn is_service_link(text: &str) -> bool {
+    MonitorKind::from_link(text.trim()) != MonitorKind::Http
+}
+
+/// Validates the link to a non-HTTP service and brings it to the form `scheme://host:port`.
+///
+/// The port is required for TCP, for SMTP and TLS the default ports 25 and 443 are used.
+///
+/// # Arguments
+///
+/// * `text` - A string slice entered by the user.
+///
+/// # Returns
+///
+/// The normalized link, or `None` if the link is invalid.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(parse_service_link("smtp://mail.example.com"), Some(String::from("smtp://mail.example.com:25")));
+/// assert_eq!(parse_service_link("tcp://db.example.com"), None);
+/// ```
+pub fn parse_service_link(text: &str) -> Option<String> {
+    let kind = MonitorKind::from_link(text.trim());
+    let url = Url::parse(text.trim()).ok()?;
+    let host = url.host_str().filter(|host| !host.is_empty())?;
+
+    if !url.path().trim_matches('/').is_empty() || url.query().is_some() {
+        return None;
+    }
+
+    let port = match (kind, url.port()) {
+        (_, Some(port)) => port,
+        (MonitorKind::Smtp, None) => SMTP_PORT,
+        (MonitorKind::Tls, None) => TLS_PORT,
+        _ => return None
+    };
+
+    Some(format!("{}://{}:{}", url.scheme(), host.to_lowercase(), port))
+}
+
+/// Checks the non-HTTP service by its link.
+///
+/// # Arguments
+///
+/// * `link` - A normalized link to the service.
+/// * `time_limit` - Maximum time to wait for the service.
+///
+/// # Returns
+///
+/// A `ServiceCheck` if the service works, or a `ServiceError` describing the failure.
+pub async fn check_service(link: &str, time_limit: Duration) -> Result<ServiceCheck, ServiceError> {
+    let url = Url::parse(link).map_err(|_| ServiceError::InvalidLink)?;
+    let host = url.host().ok_or(ServiceError::InvalidLink)?;
+    let port = url.port().ok_or(ServiceError::InvalidLink)?;
+
+    // IP addresses are connected to directly, since the host of an IPv6 link is written in brackets that cannot be resolved
+    let connection = async {
+        match host {
+            Host::Domain(domain) => TcpStream::connect((domain, port)).await,
+            Host::Ipv4(address) => TcpStream::connect((address, port)).await,
+            Host::Ipv6(address) => TcpStream::connect((address, port)).await
+        }
+    };
+
+    let time_now = std::time::Instant::now();
+
+    let stream = timeout(time_limit, connection).await
+        .map_err(|_| ServiceError::Timeout)?
+        .map_err(|err| ServiceError::Connection(err.to_string()))?;
+
+    let duration = time_now.elapsed().as_millis();
+
+    let mut check = ServiceCheck {
+        duration,
+        banner: None,
+        certificate_days: None
+    };
+
+    match MonitorKind::from_link(link) {
+        MonitorKind::Smtp => {
+            check.banner = Some(timeout(time_limit, read_smtp_banner(stream)).await.map_err(|_| ServiceError::Timeout)??);
+        }
+        MonitorKind::Tls => {
+            let server_name = match host {
+                Host::Domain(domain) => domain.to_string(),
+                Host::Ipv4(address) => address.to_string(),
+                Host::Ipv6(address) => address.to_string()
+            };
+
+            check.certificate_days = Some(timeout(time_limit, get_certificate_days(stream, &server_name)).await.map_err(|_| ServiceError::Timeout)??);
+        }
+        _ => ()
+    }
+
+    Ok(check)
+}
+
+/// Reads the greeting of the SMTP server and closes the session.
+///
+/// # Arguments
+///
+/// * `stream` - Connection to the SMTP server.
+///
+/// # Returns
+///
+/// The greeting line, or a `ServiceError` if the server did not respond with the 220 code.
+async fn read_smtp_banner(stream: TcpStream) -> Result<String, ServiceError> {
+    let mut reader = BufReader::new(stream);
+    let mut banner = String::new();
+
+    reader.read_line(&mut banner).await.map_err(|err| ServiceError::Connection(err.to_string()))?;
+
+    let banner = banner.trim().to_string();
+
+    if !banner.starts_with("220") {
+        return Err(ServiceError::UnexpectedBanner(banner));
+    }
+
+    let _ = reader.get_mut().write_all(b"QUIT\r\n").await;
+
+    Ok(banner)
+}
+
+/// Performs the TLS handshake and calculates how many days are left until the server certificate expires.
+///
+/// # Arguments
+///
+/// * `stream` - Connection to the TLS server.
+/// * `host` - Domain name that the certificate must match.
+///
+/// # Returns
+///
+/// Number of days until the certificate expires, or a `ServiceError` if the handshake failed.
+async fn get_certificate_days(stream: TcpStream, host: &str) -> Result<i64, ServiceError> {
+    let mut builder = native_tls::TlsConnector::builder();
+
+    for pem in client::get_extra_root_certificates() {
+        let certificate = native_tls::Certificate::from_pem(pem).map_err(|err| ServiceError::Tls(err.to_string()))?;
+        builder.add_root_certificate(certificate);
+    }
+
+    let connector = builder.build().map_err(|err| ServiceError::Tls(err.to_string()))?;
+
+    let tls_stream = TlsConnector::from(connector).connect(host, stream).await
+        .map_err(|err| ServiceError::Tls(err.to_string()))?;
+
+    let certificate = tls_stream.get_ref().peer_certificate()
+        .map_err(|err| ServiceError::Tls(err.to_string()))?
+        .ok_or(ServiceError::Tls(String::from("the server did not send a certificate")))?;
+
+    let der = certificate.to_der().map_err(|err| ServiceError::Tls(err.to_string()))?;
+
+    let (_, certificate) = x509_parser::parse_x509_certificate(&der)
+        .map_err(|err| ServiceError::Tls(err.to_string()))?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+
+    Ok((certificate.validity().not_after.timestamp() - now) / SECONDS_IN_DAY)
+}
+
+#[cfg(test)]
+mod monitor_tests {
+    use tokio::net::TcpListener;
+    use super::*;
+
+    #[test]
+    fn test_monitor_kind() {
+        assert_eq!(MonitorKind::from_link("https://example.com"), MonitorKind::Http);
+        assert_eq!(MonitorKind::from_link("tcp://db.example.com:5432"), MonitorKind::Tcp);
+        assert_eq!(MonitorKind::from_link("SMTP://mail.example.com:25"), MonitorKind::Smtp);
+        assert_eq!(MonitorKind::from_link("tls://imap.example.com:993"), MonitorKind::Tls);
+        assert!(!is_service_link("example.com"));
+    }
+
+    #[test]
+    fn test_parse_service_link() {
+        assert_eq!(parse_service_link("tcp://DB.example.com:5432"), Some(String::from("tcp://db.example.com:5432")));
+        assert_eq!(parse_service_link("smtp://mail.example.com"), Some(String::from("smtp://mail.example.com:25")));
+        assert_eq!(parse_service_link("tls://imap.example.com:993/"), Some(String::from("tls://imap.example.com:993")));
+        assert_eq!(parse_service_link("tcp://db.example.com"), None);
+        assert_eq!(parse_service_link("tcp://db.example.com:5432/path"), None);
+        assert_eq!(parse_service_link("tcp://"), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_tcp_and_smtp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let _ = socket.write_all(b"220 mail.example.com ESMTP\r\n").await;
+            }
+        });
+
+        let tcp = check_service(&format!("tcp://127.0.0.1:{port}"), Duration::from_secs(5)).await;
+        let smtp = check_service(&format!("smtp://127.0.0.1:{port}"), Duration::from_secs(5)).await;
+
+        assert!(tcp.is_ok());
+        assert_eq!(smtp.unwrap().banner.as_deref(), Some("220 mail.example.com ESMTP"));
+    }
+
+    #[tokio::test]
+    async fn test_check_tcp_over_ipv6() {
+        // The sandbox may have no IPv6 loopback, then there is nothing to check
+        let Ok(listener) = TcpListener::bind("[::1]:0").await else { return };
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            loop {
+                let _ = listener.accept().await.unwrap();
+            }
+        });
+
+        let link = parse_service_link(&format!("tcp://[::1]:{port}")).unwrap();
+
+        assert_eq!(link, format!("tcp://[::1]:{port}"));
+        assert!(check_service(&link, Duration::from_secs(5)).await.is_ok());
+    }
+}
@@ -0,0 +1,233 @@
+use lazy_static::lazy_static;
+use reqwest::{Client, Url};
+use reqwest::header::{HeaderMap, HeaderName, ACCEPT_ENCODING, CACHE_CONTROL, CONTENT_ENCODING, ETAG, LAST_MODIFIED};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+/// Compression algorithms whose support is checked
+const ENCODINGS: [&str; 3] = ["gzip", "br", "zstd"];
+
+/// Maximum number of linked resources that are downloaded to calculate the page weight
+const MAX_RESOURCES: usize = 100;
+
+lazy_static! {
+    static ref STYLESHEETS: Selector = Selector::parse("link[rel~=\"stylesheet\"][href]").unwrap();
+    static ref SCRIPTS: Selector = Selector::parse("script[src]").unwrap();
+    static ref IMAGES: Selector = Selector::parse("img[src]").unwrap();
+}
+
+/// Represents information about the size of the page and how it is delivered.
+#[derive(Default, Serialize, Deserialize)]
+pub struct PerformanceInformation {
+    /// Size of the uncompressed HTML code in bytes
+    pub html_size: usize,
+    /// Compression algorithms offered by the server
+    pub compression: Vec<CompressedSize>,
+    pub cache_control: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>
+}
+
+/// Represents the size of the HTML code compressed by one of the algorithms.
+#[derive(Serialize, Deserialize)]
+pub struct CompressedSize {
+    pub encoding: String,
+    /// Size of the compressed HTML code in bytes
+    pub size: usize
+}
+
+/// Represents the type of a resource linked from the page.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ResourceKind {
+    Stylesheet,
+    Script,
+    Image
+}
+
+/// Represents a resource linked from the page.
+pub struct Resource {
+    pub url: String,
+    pub kind: ResourceKind,
+    /// Size of the resource in bytes, `None` if it could not be downloaded
+    pub size: Option<usize>
+}
+
+/// Represents the total weight of the page with all linked resources.
+pub struct PageWeight {
+    pub html_size: usize,
+    /// Resources sorted from the heaviest to the lightest
+    pub resources: Vec<Resource>
+}
+
+impl PageWeight {
+    /// Returns the size of the HTML code and all downloaded resources in bytes
+    pub fn total_size(&self) -> usize {
+        self.html_size + self.resources.iter().filter_map(|resource| resource.size).sum::<usize>()
+    }
+}
+
+/// Collects information about the size, compression and caching of the page.
+///
+/// For each compression algorithm, a separate request is sent, in which the client
+/// reports that it supports only this algorithm.
+///
+/// # Arguments
+///
+/// * `client` - The client used to send requests.
+/// * `url` - A string slice representing the URL of the page.
+/// * `body` - HTML code of the page.
+/// * `headers` - Headers of the response with the page.
+///
+/// # Returns
+///
+/// The `PerformanceInformation` of the page.
+pub async fn analyze_performance(client: &Client, url: &str, body: &str, headers: &HeaderMap) -> PerformanceInformation {
+    let mut compression: Vec<CompressedSize> = Vec::new();
+
+    for encoding in ENCODINGS {
+        let Ok(resp) = client.get(url).header(ACCEPT_ENCODING, encoding).send().await else { continue };
+
+        let is_encoded = resp.headers().get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.eq_ignore_ascii_case(encoding));
+
+        if is_encoded {
+            if let Ok(bytes) = resp.bytes().await {
+                compression.push(CompressedSize { encoding: encoding.to_string(), size: bytes.len() });
+            }
+        }
+    }
+
+    PerformanceInformation {
+        html_size: body.len(),
+        compression,
+        cache_control: get_header(headers, CACHE_CONTROL),
+        etag: get_header(headers, ETAG),
+        last_modified: get_header(headers, LAST_MODIFIED)
+    }
+}
+
+/// Returns the value of the header as a string, if it is present and readable
+fn get_header(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string)
+}
+
+/// Downloads the page with the stylesheets, scripts and images linked from it and calculates its weight.
+///
+/// # Arguments
+///
+/// * `client` - The client used to send requests.
+/// * `url` - A string slice representing the URL of the page.
+///
+/// # Returns
+///
+/// A `Result` containing a `PageWeight` struct on success, or a `reqwest::Error` if the page could not be downloaded.
+pub async fn get_page_weight(client: &Client, url: &str) -> Result<PageWeight, reqwest::Error> {
+    let resp = client.get(url).send().await?;
+    let base_url = resp.url().clone();
+    let body = resp.text().await?;
+
+    let mut tasks = JoinSet::new();
+
+    for (url, kind) in find_resources(&base_url, &body).into_iter().take(MAX_RESOURCES) {
+        let client = client.clone();
+
+        tasks.spawn(async move {
+            let size = match client.get(url.as_str()).send().await {
+                Ok(resp) if resp.status().is_success() => resp.bytes().await.ok().map(|bytes| bytes.len()),
+                _ => None
+            };
+
+            Resource { url: url.to_string(), kind, size }
+        });
+    }
+
+    let mut resources: Vec<Resource> = Vec::new();
+
+    while let Some(resource) = tasks.join_next().await {
+        if let Ok(resource) = resource {
+            resources.push(resource);
+        }
+    }
+
+    resources.sort_by_key(|resource| std::cmp::Reverse(resource.size));
+
+    Ok(PageWeight {
+        html_size: body.len(),
+        resources
+    })
+}
+
+/// Finds the stylesheets, scripts and images linked from the page.
+///
+/// # Arguments
+///
+/// * `base_url` - URL of the page, relative to which the links are resolved.
+/// * `body` - HTML code of the page.
+///
+/// # Returns
+///
+/// Unique absolute URLs of the resources with their types.
+fn find_resources(base_url: &Url, body: &str) -> Vec<(Url, ResourceKind)> {
+    let html = Html::parse_document(body);
+    let mut resources: Vec<(Url, ResourceKind)> = Vec::new();
+
+    let elements = [
+        (&*STYLESHEETS, "href", ResourceKind::Stylesheet),
+        (&*SCRIPTS, "src", ResourceKind::Script),
+        (&*IMAGES, "src", ResourceKind::Image)
+    ];
+
+    for (selector, attribute, kind) in elements {
+        for element in html.select(selector) {
+            let Some(url) = element.value().attr(attribute).and_then(|link| base_url.join(link.trim()).ok()) else { continue };
+
+            if matches!(url.scheme(), "http" | "https") && !resources.iter().any(|(resource, _)| *resource == url) {
+                resources.push((url, kind));
+            }
+        }
+    }
+
+    resources
+}
+
+#[cfg(test)]
+mod performance_tests {
+    use super::*;
+
+    #[test]
+    fn test_find_resources() {
+        let base_url = Url::parse("https://example.com/shop/").unwrap();
+        let body = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="icon" href="/favicon.ico">
+            <script src="app.js"></script><script>inline();</script>
+        </head><body>
+            <img src="https://cdn.example.com/logo.png"><img src="data:image/png;base64,AAAA"><img src="app.js">
+        </body></html>"#;
+
+        let resources: Vec<(String, bool)> = find_resources(&base_url, body).into_iter()
+            .map(|(url, kind)| (url.to_string(), kind == ResourceKind::Image))
+            .collect();
+
+        assert_eq!(resources, vec![
+            (String::from("https://example.com/style.css"), false),
+            (String::from("https://example.com/shop/app.js"), false),
+            (String::from("https://cdn.example.com/logo.png"), true)
+        ]);
+    }
+
+    #[test]
+    fn test_total_size() {
+        let weight = PageWeight {
+            html_size: 100,
+            resources: vec![
+                Resource { url: String::from("a.js"), kind: ResourceKind::Script, size: Some(50) },
+                Resource { url: String::from("b.png"), kind: ResourceKind::Image, size: None }
+            ]
+        };
+
+        assert_eq!(weight.total_size(), 150);
+    }
+}
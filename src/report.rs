@@ -0,0 +1,287 @@
+use serde::Serialize;
+use crate::website::{self, FileStatus, HealthScore, HealthSection, Recommendation, RequestError, SiteInformation};
+
+/// Represents the site analysis together with its score, as it is written to the JSON report.
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    /// The name that the user has given to the site
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    site: &'a SiteInformation,
+    health_score: HealthScore
+}
+
+/// Renders the site analysis and its score to JSON.
+///
+/// # Arguments
+///
+/// * `site_information` - The results of the site analysis.
+/// * `name` - The name that the user has given to the site, if the site is saved with a name.
+///
+/// # Returns
+///
+/// A pretty-printed JSON document.
+pub fn to_json(site_information: &SiteInformation, name: Option<&str>) -> String {
+    let report = JsonReport {
+        name,
+        site: site_information,
+        health_score: website::calculate_health_score(site_information)
+    };
+
+    serde_json::to_string_pretty(&report).expect("The site analysis must be serializable")
+}
+
+/// Renders the site analysis and its score to a standalone HTML page.
+///
+/// # Arguments
+///
+/// * `site_information` - The results of the site analysis.
+/// * `name` - The name that the user has given to the site, if the site is saved with a name.
+///
+/// # Returns
+///
+/// The HTML code of the report.
+pub fn to_html(site_information: &SiteInformation, name: Option<&str>) -> String {
+    let health_score = website::calculate_health_score(site_information);
+    let title = match name {
+        Some(name) => format!("Отчет о сайте {} ({})", escape_html(name), escape_html(&site_information.url)),
+        None => format!("Отчет о сайте {}", escape_html(&site_information.url))
+    };
+
+    let mut html = format!("<!DOCTYPE html>\n<html lang=\"ru\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+        <style>body{{font-family:sans-serif;max-width:960px;margin:auto}}table{{border-collapse:collapse;margin-bottom:16px}}\
+        td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}}</style>\n</head>\n<body>\n<h1>{title}</h1>\n");
+
+    html.push_str(&format!("<h2>Оценка: {} ({}/100)</h2>\n", health_score.grade, health_score.score));
+    html.push_str(&render_table(&health_score.sections.iter()
+        .map(|section| (describe_section(section.section).to_string(), format!("{} ({})", section.grade, section.score)))
+        .collect::<Vec<_>>()));
+
+    if !health_score.recommendations.is_empty() {
+        html.push_str("<h2>Что исправить в первую очередь</h2>\n<ol>\n");
+
+        for recommendation in &health_score.recommendations {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(describe_recommendation(*recommendation))));
+        }
+
+        html.push_str("</ol>\n");
+    }
+
+    let protocols = &site_information.protocols;
+    let supported: Vec<&str> = [(protocols.http1, "HTTP/1.1"), (protocols.http2, "HTTP/2"), (protocols.http3, "HTTP/3")].iter()
+        .filter(|(is_supported, _)| *is_supported)
+        .map(|(_, name)| *name)
+        .collect();
+
+    html.push_str("<h2>Общая информация</h2>\n");
+    html.push_str(&render_table(&[
+        (String::from("Код ответа"), site_information.status_code.to_string()),
+        (String::from("Время ответа"), format!("{} миллисекунд", site_information.duration)),
+        (String::from("Протоколы"), supported.join(", ")),
+        (String::from("robots.txt"), describe_file_status(site_information.robots)),
+        (String::from("sitemap.xml"), describe_file_status(site_information.sitemap))
+    ]));
+
+    html.push_str("<h2>Сертификат</h2>\n");
+
+    match &site_information.certificate {
+        Some(cert) => html.push_str(&render_table(&[
+            (String::from("Общее название"), cert.common_name.clone()),
+            (String::from("Организация"), cert.organization.clone()),
+            (String::from("Издатель"), cert.issuer.clone()),
+            (String::from("Действителен"), String::from(if cert.is_valid { "да" } else { "нет" })),
+            (String::from("Дней до окончания"), cert.days_left().to_string())
+        ])),
+        None => html.push_str("<p>Не найден</p>\n")
+    }
+
+    if !site_information.address_families.is_empty() {
+        html.push_str("<h2>IPv4 и IPv6</h2>\n");
+        html.push_str(&render_table(&site_information.address_families.iter()
+            .map(|check| {
+                let result = match &check.result {
+                    Ok(result) => format!("код ответа {}, {} миллисекунд", result.status_code, result.duration),
+                    Err(RequestError::Timeout) => String::from("сайт слишком долго отвечал"),
+                    Err(RequestError::Connection) => String::from("не удалось подключиться")
+                };
+
+                (format!("{} ({})", check.family, check.address), result)
+            })
+            .collect::<Vec<_>>()));
+    }
+
+    if let Some(mixed_content) = &site_information.mixed_content {
+        html.push_str("<h2>Смешанное содержимое</h2>\n");
+
+        if mixed_content.is_empty() {
+            html.push_str("<p>Не найдено</p>\n");
+        }
+        else {
+            html.push_str(&render_table(&mixed_content.iter()
+                .map(|content| (format!("{:?}", content.kind), content.url.clone()))
+                .collect::<Vec<_>>()));
+        }
+    }
+
+    let headers = &site_information.security_headers;
+
+    html.push_str("<h2>Заголовки безопасности</h2>\n");
+    html.push_str(&render_table(&[
+        ("Strict-Transport-Security", &headers.strict_transport_security),
+        ("Content-Security-Policy", &headers.content_security_policy),
+        ("X-Content-Type-Options", &headers.x_content_type_options),
+        ("X-Frame-Options", &headers.x_frame_options),
+        ("Referrer-Policy", &headers.referrer_policy),
+        ("Permissions-Policy", &headers.permissions_policy)
+    ].map(|(name, value)| (name.to_string(), value.clone().unwrap_or_else(|| String::from("нет"))))));
+
+    let dns = &site_information.dns;
+
+    html.push_str("<h2>DNS</h2>\n");
+    html.push_str(&render_table(&[("A", &dns.a), ("AAAA", &dns.aaaa), ("CNAME", &dns.cname), ("MX", &dns.mx), ("NS", &dns.ns), ("TXT", &dns.txt), ("CAA", &dns.caa)]
+        .iter()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(record_type, values)| (record_type.to_string(), values.join(", ")))
+        .collect::<Vec<_>>()));
+
+    let performance = &site_information.performance;
+
+    html.push_str("<h2>Производительность</h2>\n");
+    html.push_str(&render_table(&[
+        (String::from("Размер HTML"), format!("{} байт", performance.html_size)),
+        (String::from("Сжатие"), performance.compression.iter().map(|compressed| format!("{} ({} байт)", compressed.encoding, compressed.size)).collect::<Vec<_>>().join(", ")),
+        (String::from("Cache-Control"), performance.cache_control.clone().unwrap_or_default()),
+        (String::from("ETag"), performance.etag.clone().unwrap_or_default()),
+        (String::from("Last-Modified"), performance.last_modified.clone().unwrap_or_default())
+    ]));
+
+    let seo = &site_information.seo;
+
+    html.push_str("<h2>SEO</h2>\n");
+    html.push_str(&render_table(&[
+        (String::from("Заголовок"), seo.title.clone().unwrap_or_default()),
+        (String::from("Описание"), seo.description.clone().unwrap_or_default()),
+        (String::from("Канонический URL"), seo.canonical.clone().unwrap_or_default()),
+        (String::from("Заголовки H1"), seo.headings[0].to_string()),
+        (String::from("Запрет индексации"), String::from(if seo.noindex { "да" } else { "нет" })),
+        (String::from("Изображений без alt"), format!("{} из {}", seo.images_without_alt, seo.images))
+    ]));
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+/// Renders the rows of names and values to an HTML table, escaping the text.
+fn render_table(rows: &[(String, String)]) -> String {
+    let mut html = String::from("<table>\n");
+
+    for (name, value) in rows {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", escape_html(name), escape_html(value)));
+    }
+
+    html.push_str("</table>\n");
+
+    html
+}
+
+/// Escapes the characters that have a special meaning in HTML.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Returns the name of the graded section for the user.
+pub fn describe_section(section: HealthSection) -> &'static str {
+    match section {
+        HealthSection::Availability => "Доступность",
+        HealthSection::Performance => "Производительность",
+        HealthSection::Tls => "TLS",
+        HealthSection::SecurityHeaders => "Заголовки безопасности",
+        HealthSection::Seo => "SEO"
+    }
+}
+
+/// Returns the description of the service file availability for the user.
+pub fn describe_file_status(status: FileStatus) -> String {
+    match status {
+        FileStatus::Found => String::from("есть"),
+        FileStatus::Missing { status_code } => format!("нет (код ответа: {status_code})"),
+        FileStatus::Unreachable => String::from("не удалось проверить")
+    }
+}
+
+/// Returns the description of the recommended fix for the user.
+pub fn describe_recommendation(recommendation: Recommendation) -> &'static str {
+    match recommendation {
+        Recommendation::FixStatusCode => "Главная страница отвечает кодом ошибки, проверьте работу сервера",
+        Recommendation::FixAddressFamily => "Сайт доступен только по одной из версий IP, проверьте записи A и AAAA",
+        Recommendation::ReduceResponseTime => "Сократите время ответа сервера",
+        Recommendation::EnableHttp2 => "Включите поддержку HTTP/2 или HTTP/3",
+        Recommendation::EnableCompression => "Включите сжатие gzip, brotli или zstd",
+        Recommendation::AddCacheHeaders => "Добавьте заголовки кэширования (Cache-Control, ETag)",
+        Recommendation::ReducePageSize => "Уменьшите размер HTML-кода страницы",
+        Recommendation::UseHttps => "Переведите сайт на HTTPS",
+        Recommendation::FixCertificate => "Установите действительный SSL-сертификат",
+        Recommendation::RenewCertificate => "Продлите SSL-сертификат, срок его действия скоро закончится",
+        Recommendation::FixMixedContent => "Загружайте все ресурсы страницы по HTTPS",
+        Recommendation::AddStrictTransportSecurity => "Добавьте заголовок Strict-Transport-Security",
+        Recommendation::AddContentSecurityPolicy => "Добавьте заголовок Content-Security-Policy",
+        Recommendation::AddContentTypeOptions => "Добавьте заголовок X-Content-Type-Options: nosniff",
+        Recommendation::AddFrameOptions => "Запретите встраивание сайта заголовком X-Frame-Options",
+        Recommendation::AddReferrerPolicy => "Добавьте заголовок Referrer-Policy",
+        Recommendation::AddPermissionsPolicy => "Добавьте заголовок Permissions-Policy",
+        Recommendation::AddTitle => "Добавьте заголовок страницы (title)",
+        Recommendation::AddDescription => "Добавьте описание страницы (meta description)",
+        Recommendation::FixH1 => "Оставьте на странице один заголовок H1",
+        Recommendation::RemoveNoindex => "Уберите запрет индексации (noindex), если он не нужен",
+        Recommendation::AddImageAlt => "Добавьте атрибут alt изображениям",
+        Recommendation::AddRobots => "Добавьте файл robots.txt",
+        Recommendation::AddSitemap => "Добавьте файл sitemap.xml"
+    }
+}
+
+#[cfg(test)]
+mod report_tests {
+    use super::*;
+
+    fn create_site_information() -> SiteInformation {
+        SiteInformation {
+            checked_at: 1700000000,
+            sitemap: FileStatus::Missing { status_code: 404 },
+            duration: 120,
+            mixed_content: Some(Vec::new()),
+            ..SiteInformation::new_for_tests("https://example.com/?a=1&b=<2>")
+        }
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = to_json(&create_site_information(), None);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert!(value.get("name").is_none());
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&to_json(&create_site_information(), Some("Shop"))).unwrap()["name"], "Shop");
+
+        assert_eq!(value["site"]["sitemap"]["status"], "missing");
+        assert_eq!(value["site"]["sitemap"]["status_code"], 404);
+        assert_eq!(value["health_score"]["sections"][0]["section"], "availability");
+
+        let site_information: SiteInformation = serde_json::from_value(value["site"].clone()).unwrap();
+
+        assert_eq!(site_information.robots, FileStatus::Found);
+        assert_eq!(site_information.checked_at, 1700000000);
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html = to_html(&create_site_information(), None);
+
+        assert!(html.contains("https://example.com/?a=1&amp;b=&lt;2&gt;"));
+        assert!(!html.contains("<2>"));
+        assert!(html.contains("нет (код ответа: 404)"));
+        assert!(to_html(&create_site_information(), Some("<Shop>")).contains("Отчет о сайте &lt;Shop&gt; (https://example.com/"));
+    }
+}
@@ -0,0 +1,153 @@
+use lazy_static::lazy_static;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    static ref TITLE: Selector = Selector::parse("head title").unwrap();
+    static ref META: Selector = Selector::parse("meta").unwrap();
+    static ref CANONICAL: Selector = Selector::parse("link[rel~=\"canonical\"]").unwrap();
+    static ref HREFLANG: Selector = Selector::parse("link[rel~=\"alternate\"][hreflang]").unwrap();
+    static ref HEADINGS: Selector = Selector::parse("h1, h2, h3, h4, h5, h6").unwrap();
+    static ref IMAGES: Selector = Selector::parse("img").unwrap();
+}
+
+/// Represents the SEO metadata of a page.
+#[derive(Default, Serialize, Deserialize)]
+pub struct SeoInformation {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub canonical: Option<String>,
+    /// Languages of the alternative versions of the page
+    pub hreflang: Vec<String>,
+    /// Names of the OpenGraph tags, for example `og:title`
+    pub open_graph: Vec<String>,
+    /// Names of the Twitter card tags, for example `twitter:card`
+    pub twitter_card: Vec<String>,
+    /// Number of headings of each level, from H1 to H6
+    pub headings: [usize; 6],
+    /// Whether the page is closed from indexing by the robots meta tag or the X-Robots-Tag header
+    pub noindex: bool,
+    pub images: usize,
+    pub images_without_alt: usize
+}
+
+/// Parses the SEO metadata from the HTML code of the page.
+///
+/// # Arguments
+///
+/// * `body` - HTML code of the page.
+/// * `robots_header` - Value of the `X-Robots-Tag` header, if the server sent it.
+///
+/// # Returns
+///
+/// The `SeoInformation` of the page.
+///
+/// # Example
+///
+/// ```
+/// let seo = analyze_seo("<html><head><title>Main page</title></head></html>", None);
+///
+/// assert_eq!(seo.title.as_deref(), Some("Main page"));
+/// ```
+pub fn analyze_seo(body: &str, robots_header: Option<&str>) -> SeoInformation {
+    let html = Html::parse_document(body);
+    let mut seo = SeoInformation {
+        noindex: robots_header.is_some_and(has_noindex),
+        ..Default::default()
+    };
+
+    seo.title = html.select(&TITLE).next().map(element_text).filter(|title| !title.is_empty());
+
+    for meta in html.select(&META) {
+        let element = meta.value();
+        let name = element.attr("name").or(element.attr("property")).unwrap_or_default().to_lowercase();
+        let content = element.attr("content").unwrap_or_default().trim();
+
+        match name.as_str() {
+            "description" => seo.description = Some(content.to_string()).filter(|description| !description.is_empty()),
+            "robots" => seo.noindex |= has_noindex(content),
+            _ if name.starts_with("og:") => seo.open_graph.push(name),
+            _ if name.starts_with("twitter:") => seo.twitter_card.push(name),
+            _ => ()
+        }
+    }
+
+    seo.canonical = html.select(&CANONICAL).next()
+        .and_then(|link| link.value().attr("href"))
+        .map(|href| href.trim().to_string());
+
+    seo.hreflang = html.select(&HREFLANG)
+        .filter_map(|link| link.value().attr("hreflang"))
+        .map(|lang| lang.trim().to_string())
+        .collect();
+
+    for heading in html.select(&HEADINGS) {
+        let level = heading.value().name()[1..].parse::<usize>().unwrap();
+        seo.headings[level - 1] += 1;
+    }
+
+    for image in html.select(&IMAGES) {
+        seo.images += 1;
+
+        if image.value().attr("alt").is_none_or(|alt| alt.trim().is_empty()) {
+            seo.images_without_alt += 1;
+        }
+    }
+
+    seo
+}
+
+/// Returns the text of the element with collapsed whitespace
+fn element_text(element: ElementRef) -> String {
+    element.text().collect::<String>().split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Checks if the robots directives contain `noindex` or `none`
+fn has_noindex(directives: &str) -> bool {
+    directives.split(',')
+        .map(|directive| directive.trim().to_lowercase())
+        .any(|directive| directive == "noindex" || directive == "none")
+}
+
+#[cfg(test)]
+mod seo_tests {
+    use super::*;
+
+    static PAGE: &str = r#"<html><head>
+        <title>  Sparkle   shop </title>
+        <meta name="description" content="The best shop">
+        <meta name="robots" content="index, follow">
+        <meta property="og:title" content="Sparkle">
+        <meta property="og:image" content="/logo.png">
+        <meta name="twitter:card" content="summary">
+        <link rel="canonical" href="https://example.com/">
+        <link rel="alternate" hreflang="en" href="https://example.com/en">
+        <link rel="alternate" hreflang="ru" href="https://example.com/ru">
+    </head><body>
+        <h1>Shop</h1><h2>Goods</h2><h2>Contacts</h2><h1>Second</h1>
+        <img src="a.png" alt="Logo"><img src="b.png"><img src="c.png" alt=" ">
+    </body></html>"#;
+
+    #[test]
+    fn test_analyze_seo() {
+        let seo = analyze_seo(PAGE, None);
+
+        assert_eq!(seo.title.as_deref(), Some("Sparkle shop"));
+        assert_eq!(seo.description.as_deref(), Some("The best shop"));
+        assert_eq!(seo.canonical.as_deref(), Some("https://example.com/"));
+        assert_eq!(seo.hreflang, vec!["en", "ru"]);
+        assert_eq!(seo.open_graph, vec!["og:title", "og:image"]);
+        assert_eq!(seo.twitter_card, vec!["twitter:card"]);
+        assert_eq!(seo.headings, [2, 2, 0, 0, 0, 0]);
+        assert!(!seo.noindex);
+        assert_eq!(seo.images, 3);
+        assert_eq!(seo.images_without_alt, 2);
+    }
+
+    #[test]
+    fn test_noindex() {
+        assert!(analyze_seo("<meta name=\"robots\" content=\"NOINDEX, nofollow\">", None).noindex);
+        assert!(analyze_seo("<p>Text</p>", Some("none")).noindex);
+        assert!(!analyze_seo("<p>Text</p>", Some("nofollow")).noindex);
+    }
+}
@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use crate::content;
+use crate::database::Links;
+use crate::escalation::MAX_ESCALATION_MINUTES;
+use crate::links_list::{self, MAX_LABELS};
+use crate::maintenance::MaintenanceWindow;
+use crate::website::ExpectedStatusCodes;
+
+/// Separator of the maintenance windows of a link in the exported file
+const MAINTENANCE_SEPARATOR: char = ';';
+
+/// Represents the format of the file with exported links.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferFormat {
+    Csv,
+    Json
+}
+
+impl TransferFormat {
+    /// Detects the format of the file by its name, or by its content if the name has no known extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The name of the file, if it is known.
+    /// * `content` - The content of the file.
+    pub fn detect(file_name: Option<&str>, content: &str) -> TransferFormat {
+        let extension = file_name.and_then(|name| name.rsplit_once('.')).map(|(_, extension)| extension.to_lowercase());
+
+        match extension.as_deref() {
+            Some("csv") => TransferFormat::Csv,
+            Some("json") => TransferFormat::Json,
+            _ if content.trim_start().starts_with('[') => TransferFormat::Json,
+            _ => TransferFormat::Csv
+        }
+    }
+
+    /// Returns the extension of the file in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TransferFormat::Csv => "csv",
+            TransferFormat::Json => "json"
+        }
+    }
+}
+
+/// Represents a link with its settings, as it is written to the exported file.
+///
+/// The state of the link (check results, content snapshot, DNS records) is not exported.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportedLink {
+    pub link: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub expected_codes: Option<String>,
+    #[serde(default)]
+    pub slow_threshold: Option<u64>,
+    #[serde(default)]
+    pub dual_stack: bool,
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    #[serde(default)]
+    pub watch_content: bool,
+    /// Rules for ignoring dynamic regions of the page, one per line
+    #[serde(default)]
+    pub content_ignore: Option<String>,
+    /// Maintenance windows separated by `;`
+    #[serde(default)]
+    pub maintenance: Option<String>,
+    /// Labels separated by spaces
+    #[serde(default)]
+    pub labels: Option<String>,
+    /// Delay in minutes before an unacknowledged alert is sent to the next subscriber
+    #[serde(default)]
+    pub escalation_minutes: Option<u64>
+}
+
+impl ExportedLink {
+    /// Creates an exported link from a saved link and its maintenance windows.
+    pub fn new(link: &Links, maintenance: &[String]) -> ExportedLink {
+        ExportedLink {
+            link: link.link.clone(),
+            name: link.name.clone(),
+            paused: link.paused,
+            timeout: link.timeout,
+            expected_codes: link.expected_codes.clone(),
+            slow_threshold: link.slow_threshold,
+            dual_stack: link.dual_stack,
+            accept_invalid_certs: link.accept_invalid_certs,
+            watch_content: link.watch_content,
+            content_ignore: link.content_ignore.clone(),
+            maintenance: Some(maintenance.join(&MAINTENANCE_SEPARATOR.to_string())).filter(|maintenance| !maintenance.is_empty()),
+            labels: Some(link.labels.join(" ")).filter(|labels| !labels.is_empty()),
+            escalation_minutes: link.escalation_minutes
+        }
+    }
+
+    /// Returns the maintenance windows of the link.
+    pub fn get_maintenance_windows(&self) -> Vec<&str> {
+        self.maintenance.as_deref().unwrap_or_default()
+            .split(MAINTENANCE_SEPARATOR)
+            .map(str::trim)
+            .filter(|window| !window.is_empty())
+            .collect()
+    }
+
+    /// Checks that the settings of the link are valid, the link itself is checked by the caller.
+    pub fn validate(&self) -> Result<(), ImportError> {
+        if self.timeout == Some(0) {
+            return Err(ImportError::InvalidTimeout);
+        }
+
+        if self.expected_codes.as_deref().is_some_and(|codes| ExpectedStatusCodes::parse(codes).is_none()) {
+            return Err(ImportError::InvalidExpectedCodes);
+        }
+
+        if self.slow_threshold == Some(0) {
+            return Err(ImportError::InvalidSlowThreshold);
+        }
+
+        if let Err(rule) = content::parse_ignore_rules(self.content_ignore.as_deref().unwrap_or_default()) {
+            return Err(ImportError::InvalidIgnoreRule(rule));
+        }
+
+        if let Some(window) = self.get_maintenance_windows().into_iter().find(|window| MaintenanceWindow::parse(window).is_none()) {
+            return Err(ImportError::InvalidMaintenanceWindow(window.to_string()));
+        }
+
+        match links_list::parse_labels(self.labels.as_deref().unwrap_or_default()) {
+            Err(label) => return Err(ImportError::InvalidLabel(label)),
+            Ok(labels) if labels.len() > MAX_LABELS => return Err(ImportError::TooManyLabels),
+            Ok(_) => ()
+        }
+
+        if self.escalation_minutes.is_some_and(|minutes| minutes == 0 || minutes > MAX_ESCALATION_MINUTES) {
+            return Err(ImportError::InvalidEscalation);
+        }
+
+        Ok(())
+    }
+
+    /// Creates a link of the chat with the settings from the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the chat that owns the link.
+    /// * `link` - The normalized link.
+    /// * `added_at` - Time of the import as a Unix timestamp.
+    pub fn to_links(&self, user_id: i64, link: &str, added_at: u64) -> Links {
+        Links {
+            user_id: user_id as f64,
+            link: link.to_string(),
+            timeout: self.timeout,
+            expected_codes: self.expected_codes.as_deref().and_then(ExpectedStatusCodes::parse).map(|codes| codes.to_string()),
+            slow_threshold: self.slow_threshold,
+            watch_content: self.watch_content,
+            content_ignore: self.content_ignore.clone().filter(|rules| !rules.trim().is_empty()),
+            content_hash: None,
+            content_text: None,
+            dns_records: None,
+            pending_dns_records: None,
+            dual_stack: self.dual_stack,
+            accept_invalid_certs: self.accept_invalid_certs,
+            name: self.name.clone().filter(|name| !name.trim().is_empty()),
+            paused: self.paused,
+            last_checked: None,
+            last_up: None,
+            last_status_code: None,
+            last_duration: None,
+            checks_total: 0,
+            checks_up: 0,
+            maintenance_since: None,
+            maintenance_checks: 0,
+            maintenance_failures: 0,
+            added_at: Some(added_at),
+            labels: links_list::parse_labels(self.labels.as_deref().unwrap_or_default()).unwrap_or_default(),
+            escalation_minutes: self.escalation_minutes
+        }
+    }
+}
+
+/// Represents a problem with an entry of the imported file.
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    /// The entry could not be read, with the description of the problem
+    Format(String),
+    InvalidLink,
+    InvalidTimeout,
+    InvalidExpectedCodes,
+    InvalidSlowThreshold,
+    /// The rule for ignoring dynamic regions of the page that could not be parsed
+    InvalidIgnoreRule(String),
+    /// The maintenance window that could not be parsed
+    InvalidMaintenanceWindow(String),
+    NameTooLong,
+    /// The label that contains invalid characters or is too long
+    InvalidLabel(String),
+    TooManyLabels,
+    /// The delay before an alert is escalated is zero or too long
+    InvalidEscalation
+}
+
+/// Writes the links to a file.
+///
+/// # Arguments
+///
+/// * `links` - The exported links.
+/// * `format` - The format of the file.
+///
+/// # Returns
+///
+/// The content of the file, CSV with a header row or a pretty-printed JSON array.
+pub fn export_links(links: &[ExportedLink], format: TransferFormat) -> String {
+    match format {
+        TransferFormat::Json => serde_json::to_string_pretty(links).expect("The links must be serializable"),
+        TransferFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+
+            for link in links {
+                writer.serialize(link).expect("The links must be serializable");
+            }
+
+            String::from_utf8(writer.into_inner().expect("Writing to memory cannot fail")).expect("CSV must be valid UTF-8")
+        }
+    }
+}
+
+/// Reads the links from a file.
+///
+/// A CSV file must have a header row with the names of the columns, only the `link` column is required.
+///
+/// # Arguments
+///
+/// * `content` - The content of the file.
+/// * `format` - The format of the file.
+///
+/// # Returns
+///
+/// The result of reading each entry in the order of the file, or an error if the file as a whole could not be read.
+///
+/// # Example
+///
+/// ```
+/// let entries = import_links("link,timeout\nexample.com,10\n", TransferFormat::Csv).unwrap();
+///
+/// assert_eq!(entries[0].as_ref().unwrap().timeout, Some(10));
+/// ```
+pub fn import_links(content: &str, format: TransferFormat) -> Result<Vec<Result<ExportedLink, ImportError>>, ImportError> {
+    match format {
+        TransferFormat::Json => {
+            let values: Vec<serde_json::Value> = serde_json::from_str(content).map_err(|err| ImportError::Format(err.to_string()))?;
+
+            Ok(values.into_iter()
+                .map(|value| serde_json::from_value::<ExportedLink>(value).map_err(|err| ImportError::Format(err.to_string())))
+                .collect())
+        }
+        TransferFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(content.as_bytes());
+
+            let headers = reader.headers().map_err(|err| ImportError::Format(err.to_string()))?;
+
+            if !headers.iter().any(|header| header == "link") {
+                return Err(ImportError::Format(String::from("no link column")));
+            }
+
+            Ok(reader.deserialize::<ExportedLink>()
+                .map(|entry| entry.map_err(|err| ImportError::Format(err.to_string())))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod transfer_tests {
+    use super::*;
+
+    fn create_exported_link() -> ExportedLink {
+        ExportedLink {
+            link: String::from("https://example.com"),
+            name: Some(String::from("Main, site")),
+            timeout: Some(10),
+            expected_codes: Some(String::from("200-299,401")),
+            content_ignore: Some(String::from("css:.banner\nregex:\\d+")),
+            maintenance: Some(String::from("sun 03:00 1h UTC;daily 02:00 30m UTC")),
+            labels: Some(String::from("client-x prod")),
+            escalation_minutes: Some(15),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let links = vec![create_exported_link(), ExportedLink { link: String::from("tcp://example.com:22"), ..Default::default() }];
+
+        for format in [TransferFormat::Csv, TransferFormat::Json] {
+            let content = export_links(&links, format);
+            let imported: Vec<ExportedLink> = import_links(&content, format).unwrap().into_iter().map(Result::unwrap).collect();
+
+            assert_eq!(imported, links);
+        }
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let entries = import_links("link,timeout,expected_codes\nexample.com,abc,\nexample.org,5,99\n", TransferFormat::Csv).unwrap();
+
+        assert!(matches!(entries[0], Err(ImportError::Format(_))));
+        assert_eq!(entries[1].as_ref().unwrap().validate(), Err(ImportError::InvalidExpectedCodes));
+
+        assert!(import_links("name\nShop\n", TransferFormat::Csv).is_err());
+        assert!(import_links("{}", TransferFormat::Json).is_err());
+        assert_eq!(create_exported_link().validate(), Ok(()));
+        assert_eq!(ExportedLink { labels: Some(String::from("prod staging!")), ..create_exported_link() }.validate(), Err(ImportError::InvalidLabel(String::from("staging!"))));
+        assert_eq!(ExportedLink { escalation_minutes: Some(0), ..create_exported_link() }.validate(), Err(ImportError::InvalidEscalation));
+        assert_eq!(create_exported_link().get_maintenance_windows(), vec!["sun 03:00 1h UTC", "daily 02:00 30m UTC"]);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(TransferFormat::detect(Some("links.JSON"), ""), TransferFormat::Json);
+        assert_eq!(TransferFormat::detect(Some("links.txt"), " [{}]"), TransferFormat::Json);
+        assert_eq!(TransferFormat::detect(None, "link\n"), TransferFormat::Csv);
+    }
+}
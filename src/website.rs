@@ -1,19 +1,385 @@
-use std::time::Duration;
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, SocketAddr};
+use std::ops::RangeInclusive;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use checkssl::{Cert, CheckSSL};
 use http::{Uri};
 use lazy_static::lazy_static;
 use regex::Regex;
-use reqwest::Client;
+use reqwest::{Client, Url, Version};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use crate::{client, dns, mixed_content, performance, seo};
+use crate::dns::DnsInformation;
+use crate::mixed_content::MixedContent;
+use crate::performance::PerformanceInformation;
+use crate::seo::SeoInformation;
 
 const HTTP_OR_HTTPS_REGEX: &str = "^(http|https)://";
 
+/// Timeout of a request to the site if the user has not specified another one
+pub const DEFAULT_TIMEOUT_SECONDS: u64 = 15;
+
+/// Number of recommended fixes in the health score
+const MAX_RECOMMENDATIONS: usize = 3;
+
+const SECONDS_IN_DAY: i64 = 86400;
+
 /// Represents information about a website.
+///
+/// It can be serialized to render the analysis in other formats and to store it for comparisons over time.
+#[derive(Serialize, Deserialize)]
 pub struct SiteInformation {
+    /// The analyzed URL
+    pub url: String,
+    /// Time of the analysis as a Unix timestamp
+    pub checked_at: u64,
     pub status_code: u16,
-    pub has_robots: u16,
-    pub has_sitemap: u16,
+    pub robots: FileStatus,
+    pub sitemap: FileStatus,
+    /// Response time of the main page in milliseconds
     pub duration: u128,
-    pub certificate: Option<Cert>
+    /// `None` if the certificate could not be obtained
+    pub certificate: Option<CertificateInformation>,
+    pub seo: SeoInformation,
+    pub performance: PerformanceInformation,
+    pub protocols: ProtocolSupport,
+    pub dns: DnsInformation,
+    pub address_families: Vec<FamilyCheck>,
+    /// Resources loaded over HTTP, `None` if the page is not served over HTTPS
+    pub mixed_content: Option<Vec<MixedContent>>,
+    pub security_headers: SecurityHeaders
+}
+
+#[cfg(test)]
+impl SiteInformation {
+    /// Creates the analysis of a working site without a certificate, security headers and SEO metadata,
+    /// which the tests use as a base and override only the fields they check.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The analyzed URL.
+    pub fn new_for_tests(url: &str) -> SiteInformation {
+        SiteInformation {
+            url: url.to_string(),
+            checked_at: 0,
+            status_code: 200,
+            robots: FileStatus::Found,
+            sitemap: FileStatus::Found,
+            duration: 100,
+            certificate: None,
+            seo: Default::default(),
+            performance: Default::default(),
+            protocols: ProtocolSupport { http1: true, http2: true, http3: false },
+            dns: Default::default(),
+            address_families: Vec::new(),
+            mixed_content: None,
+            security_headers: SecurityHeaders::default()
+        }
+    }
+}
+
+/// Represents the availability of a service file of the site, such as robots.txt.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FileStatus {
+    Found,
+    /// The server responded with a status code other than 200
+    Missing { status_code: u16 },
+    /// The request for the file failed
+    Unreachable
+}
+
+/// Represents the server certificate of the site.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CertificateInformation {
+    pub common_name: String,
+    pub organization: String,
+    pub country: String,
+    pub issuer: String,
+    pub is_valid: bool,
+    /// Expiration time as a Unix timestamp
+    pub expires_at: i64
+}
+
+impl CertificateInformation {
+    /// Returns the number of days until the certificate expires, negative if it has already expired.
+    pub fn days_left(&self) -> i64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+
+        (self.expires_at - now) / SECONDS_IN_DAY
+    }
+}
+
+impl From<Cert> for CertificateInformation {
+    fn from(cert: Cert) -> Self {
+        CertificateInformation {
+            common_name: cert.server.common_name,
+            organization: cert.server.organization,
+            country: cert.server.country,
+            issuer: cert.server.issuer,
+            is_valid: cert.server.is_valid,
+            expires_at: cert.server.not_after.timestamp()
+        }
+    }
+}
+
+/// Represents the security headers of the main page response.
+#[derive(Default, Serialize, Deserialize)]
+pub struct SecurityHeaders {
+    pub strict_transport_security: Option<String>,
+    pub content_security_policy: Option<String>,
+    pub x_content_type_options: Option<String>,
+    pub x_frame_options: Option<String>,
+    pub referrer_policy: Option<String>,
+    pub permissions_policy: Option<String>
+}
+
+/// Represents the letter grade of a score, from A (90 and more) to F (less than 60).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F
+}
+
+impl Grade {
+    /// Converts a score from 0 to 100 into a letter grade.
+    pub fn from_score(score: u8) -> Grade {
+        match score {
+            90.. => Grade::A,
+            80..=89 => Grade::B,
+            70..=79 => Grade::C,
+            60..=69 => Grade::D,
+            _ => Grade::F
+        }
+    }
+}
+
+impl Display for Grade {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Represents a graded section of the site analysis.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthSection {
+    Availability,
+    Performance,
+    Tls,
+    SecurityHeaders,
+    Seo
+}
+
+impl HealthSection {
+    /// Returns the weight of the section in the overall score, the weights add up to 100.
+    pub fn weight(&self) -> u32 {
+        match self {
+            HealthSection::Availability => 30,
+            HealthSection::Performance => 20,
+            HealthSection::Tls => 20,
+            HealthSection::SecurityHeaders => 15,
+            HealthSection::Seo => 15
+        }
+    }
+}
+
+/// Represents a problem of the site that lowers the score of its section.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recommendation {
+    FixStatusCode,
+    /// The site works over only one of IPv4 and IPv6
+    FixAddressFamily,
+    ReduceResponseTime,
+    EnableHttp2,
+    EnableCompression,
+    AddCacheHeaders,
+    ReducePageSize,
+    UseHttps,
+    FixCertificate,
+    RenewCertificate,
+    FixMixedContent,
+    AddStrictTransportSecurity,
+    AddContentSecurityPolicy,
+    AddContentTypeOptions,
+    AddFrameOptions,
+    AddReferrerPolicy,
+    AddPermissionsPolicy,
+    AddTitle,
+    AddDescription,
+    FixH1,
+    RemoveNoindex,
+    AddImageAlt,
+    AddRobots,
+    AddSitemap
+}
+
+/// Represents the score of one section of the site analysis.
+#[derive(Serialize)]
+pub struct SectionScore {
+    pub section: HealthSection,
+    /// Score from 0 to 100
+    pub score: u8,
+    pub grade: Grade
+}
+
+/// Represents the overall health of the site.
+#[derive(Serialize)]
+pub struct HealthScore {
+    /// Weighted average of the section scores from 0 to 100
+    pub score: u8,
+    pub grade: Grade,
+    pub sections: Vec<SectionScore>,
+    /// The fixes that raise the overall score the most, from the most important
+    pub recommendations: Vec<Recommendation>
+}
+
+/// Represents the HTTP versions supported by the server.
+#[derive(Serialize, Deserialize)]
+pub struct ProtocolSupport {
+    pub http1: bool,
+    /// HTTP/2 negotiated via ALPN
+    pub http2: bool,
+    /// HTTP/3 advertised in the Alt-Svc header
+    pub http3: bool
+}
+
+/// Represents the result of a request to the site.
+#[derive(Serialize, Deserialize)]
+pub struct RequestResult {
+    pub status_code: u16,
+    pub duration: u128,
+    /// Body of the response, if it was requested
+    #[serde(skip)]
+    pub body: Option<String>
+}
+
+/// Represents the reason why a request to the site failed.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestError {
+    Timeout,
+    Connection
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            RequestError::Timeout
+        }
+        else {
+            RequestError::Connection
+        }
+    }
+}
+
+/// Represents the version of the IP protocol used to connect to the site.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6
+}
+
+impl Display for AddressFamily {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressFamily::Ipv4 => write!(f, "IPv4"),
+            AddressFamily::Ipv6 => write!(f, "IPv6")
+        }
+    }
+}
+
+/// Represents the result of a request to the site over one version of the IP protocol.
+#[derive(Serialize, Deserialize)]
+pub struct FamilyCheck {
+    pub family: AddressFamily,
+    /// The address the request was sent to
+    pub address: IpAddr,
+    pub result: Result<RequestResult, RequestError>
+}
+
+/// A set of status codes that are considered healthy for a link.
+///
+/// It is written by the user as a comma-separated list of codes and ranges, for example `200-299,401`.
+#[derive(Debug, PartialEq)]
+pub struct ExpectedStatusCodes {
+    ranges: Vec<RangeInclusive<u16>>
+}
+
+impl ExpectedStatusCodes {
+    /// Parses a comma-separated list of status codes and ranges.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A string slice like `200-299,401`.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the text contains something other than valid status codes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let codes = ExpectedStatusCodes::parse("200-299,401").unwrap();
+    ///
+    /// assert!(codes.contains(401));
+    /// assert!(!codes.contains(301));
+    /// ```
+    pub fn parse(text: &str) -> Option<ExpectedStatusCodes> {
+        let mut ranges: Vec<RangeInclusive<u16>> = Vec::new();
+
+        for part in text.split(',').map(str::trim) {
+            let (start, end) = match part.split_once('-') {
+                Some((start, end)) => (start.trim(), end.trim()),
+                None => (part, part)
+            };
+
+            let start = start.parse::<u16>().ok()?;
+            let end = end.parse::<u16>().ok()?;
+
+            if !(100..=599).contains(&start) || !(100..=599).contains(&end) || start > end {
+                return None;
+            }
+
+            ranges.push(start..=end);
+        }
+
+        Some(ExpectedStatusCodes { ranges })
+    }
+
+    /// Checks if the status code is in the set.
+    pub fn contains(&self, status_code: u16) -> bool {
+        self.ranges.iter().any(|range| range.contains(&status_code))
+    }
+}
+
+impl Default for ExpectedStatusCodes {
+    /// By default, successful responses and redirects are considered healthy
+    fn default() -> Self {
+        ExpectedStatusCodes { ranges: vec![200..=399] }
+    }
+}
+
+impl Display for ExpectedStatusCodes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.ranges.iter()
+            .map(|range| {
+                if range.start() == range.end() {
+                    range.start().to_string()
+                }
+                else {
+                    format!("{}-{}", range.start(), range.end())
+                }
+            })
+            .collect();
+
+        write!(f, "{}", parts.join(","))
+    }
 }
 
 lazy_static! {
@@ -22,15 +388,18 @@ lazy_static! {
     };
 }
 
-/// Sends a GET request to the specified URL and returns the status code.
+/// Sends a GET request to the specified URL and returns the status code and the response time.
 ///
 /// # Arguments
 ///
+/// * `client` - The client used to send the request.
 /// * `url` - A string slice that holds the URL of the API endpoint.
+/// * `timeout` - Maximum time to wait for a response.
+/// * `read_body` - Whether to download the body of the response.
 ///
 /// # Returns
 ///
-/// * An `Ok` variant containing the status code as a `u16` if the request is successful.
+/// * An `Ok` variant containing the `RequestResult` if the request is successful.
 /// * An `Err` variant containing a `reqwest::Error` if an error occurs during the request.
 ///
 /// # Examples
@@ -40,18 +409,30 @@ lazy_static! {
 ///
 /// async fn example() -> Result<u16, Error> {
 ///     let url = "https://example.com";
-///     let status_code = get_request_code(url).await?;
-///     println!("Status code: {}", status_code);
-///     Ok(status_code)
+///     let result = get_request_code(client::get_client(false), url, Duration::from_secs(15), false).await?;
+///     println!("Status code: {}", result.status_code);
+///     Ok(result.status_code)
 /// }
 /// ```
-pub async fn get_request_code(url: &str) -> Result<u16, reqwest::Error> {
-    let client = Client::builder().timeout(Duration::from_secs(15)).build().unwrap();
+pub async fn get_request_code(client: &Client, url: &str, timeout: Duration, read_body: bool) -> Result<RequestResult, reqwest::Error> {
+    let time_now = std::time::Instant::now();
+    let resp = client.get(url).timeout(timeout).send().await?;
+    let elapsed_time = time_now.elapsed();
 
-    let resp = client.get(url).send().await?;
     let status_code = resp.status().as_u16();
 
-    Ok(status_code)
+    let body = if read_body {
+        Some(resp.text().await?)
+    }
+    else {
+        None
+    };
+
+    Ok(RequestResult {
+        status_code,
+        duration: elapsed_time.as_millis(),
+        body
+    })
 }
 
 /// Fetches site information for a given URL.
@@ -63,36 +444,305 @@ pub async fn get_request_code(url: &str) -> Result<u16, reqwest::Error> {
 /// - Existence of SSL certificate for the domain
 /// - Status code of the response for the robots.txt file
 /// - Status code of the response for the sitemap.xml file
+/// - SEO metadata of the main page
+/// - Size, compression and caching headers of the main page
+/// - Supported HTTP versions
+/// - DNS records of the host
+/// - Availability over IPv4 and IPv6
+/// - Resources loaded over HTTP on an HTTPS page
 ///
 /// # Arguments
 ///
+/// * `client` - The client used to send requests.
 /// * `url` - A string slice representing the URL of the site to fetch information for.
 ///
 /// # Returns
 ///
 /// A `Result` containing a `SiteInformation` struct on success, or a `reqwest::Error` on failure.
-pub async fn get_site_information(url: &str) -> Result<SiteInformation, reqwest::Error> {
-    let client = reqwest::Client::builder().timeout(Duration::from_secs(15)).build().unwrap();
-
+pub async fn get_site_information(client: &Client, url: &str) -> Result<SiteInformation, reqwest::Error> {
     let time_now = std::time::Instant::now();
     let resp_site = client.get(url).send().await?;
     let elapsed_time = time_now.elapsed();
 
+    let status_code = resp_site.status().as_u16();
+    let version = resp_site.version();
+    // The page is checked at the address it was redirected to
+    let is_https = resp_site.url().scheme() == "https";
+    let headers = resp_site.headers().clone();
+    let robots_header = headers.get("x-robots-tag").and_then(|value| value.to_str().ok()).map(str::to_string);
+    let security_headers = get_security_headers(&headers);
+    let body = resp_site.text().await?;
+
+    let mixed_content = if is_https { Some(mixed_content::find_mixed_content(&body)) } else { None };
+    let performance = performance::analyze_performance(client, url, &body, &headers).await;
+
+    let alt_svc = headers.get("alt-svc").and_then(|value| value.to_str().ok()).unwrap_or_default();
+    let protocols = ProtocolSupport {
+        http1: version <= Version::HTTP_11 || supports_http1(url).await,
+        http2: version == Version::HTTP_2,
+        http3: has_http3(alt_svc)
+    };
+
     let uri = url.parse::<Uri>().unwrap();
     let cert = CheckSSL::from_domain(uri.host().unwrap());
 
-    let resp_robots = client.get(format!("{}://{}/robots.txt", uri.scheme_str().unwrap(), uri.host().unwrap())).send().await?;
-    let resp_sitemap = client.get(format!("{}://{}/sitemap.xml", uri.scheme_str().unwrap(), uri.host().unwrap())).send().await?;
+    let robots = get_file_status(client, &format!("{}://{}/robots.txt", uri.scheme_str().unwrap(), uri.host().unwrap())).await;
+    let sitemap = get_file_status(client, &format!("{}://{}/sitemap.xml", uri.scheme_str().unwrap(), uri.host().unwrap())).await;
+
+    let dns = dns::get_dns_information(uri.host().unwrap()).await;
+    let address_families = check_address_families(url, Duration::from_secs(DEFAULT_TIMEOUT_SECONDS), false).await;
 
     Ok(SiteInformation {
-        status_code: resp_site.status().as_u16(),
+        url: url.to_string(),
+        checked_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+        status_code,
         duration: elapsed_time.as_millis(),
-        certificate: cert.ok(),
-        has_robots: resp_robots.status().as_u16(),
-        has_sitemap: resp_sitemap.status().as_u16()
+        certificate: cert.ok().map(CertificateInformation::from),
+        robots,
+        sitemap,
+        seo: seo::analyze_seo(&body, robots_header.as_deref()),
+        performance,
+        protocols,
+        dns,
+        address_families,
+        mixed_content,
+        security_headers
     })
 }
 
+/// Checks if the service file of the site is available.
+///
+/// # Arguments
+///
+/// * `client` - The client used to send the request.
+/// * `url` - A string slice representing the URL of the file.
+async fn get_file_status(client: &Client, url: &str) -> FileStatus {
+    match client.get(url).send().await {
+        Ok(resp) if resp.status().as_u16() == 200 => FileStatus::Found,
+        Ok(resp) => FileStatus::Missing { status_code: resp.status().as_u16() },
+        Err(_) => FileStatus::Unreachable
+    }
+}
+
+/// Reads the security headers from the response headers.
+fn get_security_headers(headers: &HeaderMap) -> SecurityHeaders {
+    let get = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+
+    SecurityHeaders {
+        strict_transport_security: get("strict-transport-security"),
+        content_security_policy: get("content-security-policy"),
+        x_content_type_options: get("x-content-type-options"),
+        x_frame_options: get("x-frame-options"),
+        referrer_policy: get("referrer-policy"),
+        permissions_policy: get("permissions-policy")
+    }
+}
+
+/// Grades the availability, performance, TLS, security headers and SEO of the site.
+///
+/// Each section starts with 100 points, and every problem found subtracts its penalty.
+/// The overall score is the weighted average of the sections, and the recommended fixes
+/// are the problems that cost the most points of the overall score.
+///
+/// # Arguments
+///
+/// * `site_information` - The results of the site analysis.
+///
+/// # Returns
+///
+/// The `HealthScore` of the site.
+pub fn calculate_health_score(site_information: &SiteInformation) -> HealthScore {
+    let mut problems: Vec<(HealthSection, Recommendation, u8)> = Vec::new();
+    let mut add = |section: HealthSection, recommendation: Recommendation, penalty: u8| problems.push((section, recommendation, penalty));
+
+    // Availability
+    if !ExpectedStatusCodes::default().contains(site_information.status_code) {
+        add(HealthSection::Availability, Recommendation::FixStatusCode, 70);
+    }
+
+    if site_information.address_families.iter().any(|check| check.result.is_err()) {
+        add(HealthSection::Availability, Recommendation::FixAddressFamily, 20);
+    }
+
+    // Performance
+    match site_information.duration {
+        3000.. => add(HealthSection::Performance, Recommendation::ReduceResponseTime, 40),
+        1000..=2999 => add(HealthSection::Performance, Recommendation::ReduceResponseTime, 20),
+        _ => ()
+    }
+
+    if !site_information.protocols.http2 && !site_information.protocols.http3 {
+        add(HealthSection::Performance, Recommendation::EnableHttp2, 15);
+    }
+
+    let performance = &site_information.performance;
+
+    if performance.compression.is_empty() {
+        add(HealthSection::Performance, Recommendation::EnableCompression, 20);
+    }
+
+    if performance.cache_control.is_none() && performance.etag.is_none() && performance.last_modified.is_none() {
+        add(HealthSection::Performance, Recommendation::AddCacheHeaders, 10);
+    }
+
+    if performance.html_size > 500 * 1024 {
+        add(HealthSection::Performance, Recommendation::ReducePageSize, 15);
+    }
+
+    // TLS
+    match &site_information.mixed_content {
+        None => add(HealthSection::Tls, Recommendation::UseHttps, 100),
+        Some(mixed_content) => {
+            if mixed_content.iter().any(|content| content.kind.is_blocked()) {
+                add(HealthSection::Tls, Recommendation::FixMixedContent, 30);
+            }
+            else if !mixed_content.is_empty() {
+                add(HealthSection::Tls, Recommendation::FixMixedContent, 10);
+            }
+        }
+    }
+
+    match &site_information.certificate {
+        Some(cert) if cert.is_valid && cert.days_left() < 14 => add(HealthSection::Tls, Recommendation::RenewCertificate, 30),
+        Some(cert) if cert.is_valid => (),
+        _ if site_information.mixed_content.is_some() => add(HealthSection::Tls, Recommendation::FixCertificate, 60),
+        _ => ()
+    }
+
+    // Security headers
+    let headers = &site_information.security_headers;
+    let has_frame_ancestors = headers.content_security_policy.as_deref().is_some_and(|policy| policy.contains("frame-ancestors"));
+
+    for (is_missing, recommendation, penalty) in [
+        (headers.strict_transport_security.is_none(), Recommendation::AddStrictTransportSecurity, 25),
+        (headers.content_security_policy.is_none(), Recommendation::AddContentSecurityPolicy, 25),
+        (headers.x_content_type_options.is_none(), Recommendation::AddContentTypeOptions, 15),
+        (headers.x_frame_options.is_none() && !has_frame_ancestors, Recommendation::AddFrameOptions, 15),
+        (headers.referrer_policy.is_none(), Recommendation::AddReferrerPolicy, 10),
+        (headers.permissions_policy.is_none(), Recommendation::AddPermissionsPolicy, 10)
+    ] {
+        if is_missing {
+            add(HealthSection::SecurityHeaders, recommendation, penalty);
+        }
+    }
+
+    // SEO
+    let seo = &site_information.seo;
+
+    for (is_problem, recommendation, penalty) in [
+        (seo.noindex, Recommendation::RemoveNoindex, 30),
+        (seo.title.is_none(), Recommendation::AddTitle, 25),
+        (seo.description.is_none(), Recommendation::AddDescription, 15),
+        (seo.headings[0] != 1, Recommendation::FixH1, 10),
+        (seo.images_without_alt > 0, Recommendation::AddImageAlt, 10),
+        (site_information.robots != FileStatus::Found, Recommendation::AddRobots, 5),
+        (site_information.sitemap != FileStatus::Found, Recommendation::AddSitemap, 5)
+    ] {
+        if is_problem {
+            add(HealthSection::Seo, recommendation, penalty);
+        }
+    }
+
+    let sections: Vec<SectionScore> = [HealthSection::Availability, HealthSection::Performance, HealthSection::Tls, HealthSection::SecurityHeaders, HealthSection::Seo].into_iter()
+        .map(|section| {
+            let penalty: u32 = problems.iter()
+                .filter(|(problem_section, _, _)| *problem_section == section)
+                .map(|(_, _, penalty)| *penalty as u32)
+                .sum();
+            let score = 100u32.saturating_sub(penalty) as u8;
+
+            SectionScore { section, score, grade: Grade::from_score(score) }
+        })
+        .collect();
+
+    let score = (sections.iter().map(|section| section.score as u32 * section.section.weight()).sum::<u32>() / 100) as u8;
+
+    // A fix raises the overall score by its penalty multiplied by the weight of the section
+    problems.sort_by_key(|(section, _, penalty)| std::cmp::Reverse(*penalty as u32 * section.weight()));
+
+    HealthScore {
+        score,
+        grade: Grade::from_score(score),
+        sections,
+        recommendations: problems.into_iter().map(|(_, recommendation, _)| recommendation).take(MAX_RECOMMENDATIONS).collect()
+    }
+}
+
+/// Sends a request to the site over IPv4 and IPv6 separately.
+///
+/// The request for each version of the protocol is sent to the first resolved address of this version.
+/// Versions for which the host has no addresses are skipped.
+///
+/// # Arguments
+///
+/// * `url` - A string slice representing the URL of the site.
+/// * `timeout` - Maximum time to wait for a response.
+/// * `accept_invalid_certs` - Whether to accept invalid and self-signed certificates of the site.
+///
+/// # Returns
+///
+/// The results of the requests, or an empty vector if the link contains an IP address instead of a domain name.
+pub async fn check_address_families(url: &str, timeout: Duration, accept_invalid_certs: bool) -> Vec<FamilyCheck> {
+    let Some(host) = Url::parse(url).ok().and_then(|url| url.domain().map(str::to_string)) else { return Vec::new() };
+
+    let (ipv4, ipv6) = dns::resolve_addresses(&host).await;
+    let mut checks: Vec<FamilyCheck> = Vec::new();
+
+    for (family, addresses) in [(AddressFamily::Ipv4, ipv4), (AddressFamily::Ipv6, ipv6)] {
+        let Some(address) = addresses.first().copied() else { continue };
+
+        // The port of the overridden address is ignored, the port from the URL is used
+        let client = client::create_builder()
+            .timeout(timeout)
+            .danger_accept_invalid_certs(accept_invalid_certs)
+            .resolve(&host, SocketAddr::new(address, 0))
+            .build()
+            .unwrap();
+
+        let time_now = std::time::Instant::now();
+        let result = client.get(url).send().await
+            .map(|resp| RequestResult {
+                status_code: resp.status().as_u16(),
+                duration: time_now.elapsed().as_millis(),
+                body: None
+            })
+            .map_err(RequestError::from);
+
+        checks.push(FamilyCheck { family, address, result });
+    }
+
+    checks
+}
+
+/// Checks if the server responds to a client that supports only HTTP/1.1.
+///
+/// # Arguments
+///
+/// * `url` - A string slice representing the URL of the site.
+async fn supports_http1(url: &str) -> bool {
+    let client = client::create_builder().http1_only().build().unwrap();
+
+    client.get(url).send().await.is_ok_and(|resp| resp.version() == Version::HTTP_11)
+}
+
+/// Checks if the Alt-Svc header advertises HTTP/3.
+///
+/// # Arguments
+///
+/// * `alt_svc` - Value of the Alt-Svc header, for example `h3=":443"; ma=86400, h3-29=":443"`.
+///
+/// # Returns
+///
+/// `true` if one of the alternative services uses HTTP/3 or one of its drafts.
+fn has_http3(alt_svc: &str) -> bool {
+    alt_svc.split(',')
+        .filter_map(|service| service.split_once('='))
+        .any(|(protocol, _)| {
+            let protocol = protocol.trim();
+            protocol == "h3" || protocol.starts_with("h3-")
+        })
+}
+
 /// Checks if a given URL has either "http" or "https" protocol.
 ///
 /// # Arguments
@@ -116,50 +766,117 @@ pub fn has_http_or_https(url: &str) -> bool {
 
 #[cfg(test)]
 mod website_checker_tests {
-    use crate::website;
+    use std::time::Duration;
+    use crate::{client, website};
+    use crate::website::{ExpectedStatusCodes, FileStatus, Grade, HealthSection, Recommendation, SecurityHeaders, SiteInformation, has_http3};
 
     static GOOGLE: &str = "https://google.com";
-    static YOUTUBE: &str = "youtube.com";
-    static PYTHON: &str = "www.python.org";
-    static RSVPU: &str = "https://rsvpu.ru/programs/bakalavriat";
-    static HTTP: &str = "http://info.cern.ch/";
 
     #[tokio::test]
     async fn test_get_certificate() {
-        let google_information = website::get_site_information(GOOGLE).await;
-
-        match google_information {
-            Ok(info) => {
-                println!("Status code: {}", info.status_code);
-
-                match info.certificate {
-                    Some(cert) => {
-                        println!("Organization name: {}", cert.intermediate.organization)
-                    }
-                    None => {
-                        println!("Organization name: none")
-                    }
-                }
+        let info = website::get_site_information(client::get_client(false), GOOGLE).await.expect("Failed to verify the site");
 
-                println!("Duration: {}", info.duration);
-                println!("Has robots.txt: {}", info.has_robots);
-                println!("Has sitemap.xml: {}", info.has_sitemap);
+        assert_eq!(info.status_code, 200);
+        assert!(info.certificate.is_some_and(|cert| cert.is_valid));
+        assert_eq!(info.robots, FileStatus::Found);
+    }
 
-                assert!(true)
-            }
-            Err(e) => {
-                println!("Failed to verify the site: {}", e);
+    #[tokio::test]
+    async fn test_get_request_code() {
+        let google_request = website::get_request_code(client::get_client(false), "https://latitude.google.com/", Duration::from_secs(15), false).await.unwrap();
+
+        assert!(http::StatusCode::from_u16(google_request.status_code).is_ok());
+        assert!(google_request.body.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_request_code_timeout() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // The server accepts connections but never answers
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
 
-                assert!(false)
+            loop {
+                sockets.push(listener.accept().await.unwrap().0);
             }
-        }
+        });
+
+        let result = website::get_request_code(client::get_client(false), &format!("http://127.0.0.1:{port}/"), Duration::from_secs(1), false).await;
+
+        assert!(result.is_err_and(|err| err.is_timeout()));
     }
 
     #[tokio::test]
-    async fn test_get_request_code() {
-        let google_request = website::get_request_code("https://latitude.google.com/").await.unwrap();
-        println!("Request code: {}", google_request);
+    async fn test_check_address_families_without_domain() {
+        let checks = website::check_address_families("http://127.0.0.1/", Duration::from_secs(1), false).await;
+
+        assert!(checks.is_empty());
+    }
+
+    fn create_site_information(status_code: u16) -> SiteInformation {
+        SiteInformation {
+            status_code,
+            sitemap: FileStatus::Missing { status_code: 404 },
+            duration: 150,
+            security_headers: SecurityHeaders {
+                x_content_type_options: Some(String::from("nosniff")),
+                ..Default::default()
+            },
+            ..SiteInformation::new_for_tests("http://example.com/")
+        }
+    }
+
+    #[test]
+    fn test_calculate_health_score() {
+        let health_score = website::calculate_health_score(&create_site_information(200));
+        let grades: Vec<(HealthSection, u8)> = health_score.sections.iter().map(|section| (section.section, section.score)).collect();
+
+        assert_eq!(grades, vec![
+            (HealthSection::Availability, 100),
+            (HealthSection::Performance, 70),
+            (HealthSection::Tls, 0),
+            (HealthSection::SecurityHeaders, 15),
+            (HealthSection::Seo, 45)
+        ]);
+        assert_eq!(health_score.score, 53);
+        assert_eq!(health_score.grade, Grade::F);
+        assert_eq!(health_score.recommendations, vec![Recommendation::UseHttps, Recommendation::EnableCompression, Recommendation::AddStrictTransportSecurity]);
+
+        let health_score = website::calculate_health_score(&create_site_information(503));
+
+        assert_eq!(health_score.recommendations[0], Recommendation::FixStatusCode);
+    }
+
+    #[test]
+    fn test_grade_from_score() {
+        assert_eq!(Grade::from_score(100), Grade::A);
+        assert_eq!(Grade::from_score(85), Grade::B);
+        assert_eq!(Grade::from_score(60), Grade::D);
+        assert_eq!(Grade::from_score(0), Grade::F);
+    }
+
+    #[test]
+    fn test_has_http3() {
+        assert!(has_http3("h3=\":443\"; ma=86400, h3-29=\":443\"; ma=86400"));
+        assert!(has_http3("h2=\":443\", h3-29=\":443\""));
+        assert!(!has_http3("h2=\"alt.example.com:443\""));
+        assert!(!has_http3("clear"));
+    }
+
+    #[test]
+    fn test_expected_status_codes() {
+        let codes = ExpectedStatusCodes::parse("200-299, 401").unwrap();
+
+        assert!(codes.contains(204));
+        assert!(codes.contains(401));
+        assert!(!codes.contains(301));
+        assert_eq!(codes.to_string(), "200-299,401");
 
-        assert!(true)
+        assert!(ExpectedStatusCodes::default().contains(301));
+        assert_eq!(ExpectedStatusCodes::parse("200,abc"), None);
+        assert_eq!(ExpectedStatusCodes::parse("299-200"), None);
+        assert_eq!(ExpectedStatusCodes::parse("700"), None);
     }
 }
\ No newline at end of file